@@ -0,0 +1,728 @@
+use crate::calc_base::func_registry::{Arity, ParamType};
+use crate::calc_base::trig_funcs::AngleUnit;
+use crate::s;
+use std::fmt::Display;
+
+/// Vybere text podle jazyka. Oba texty jsou formátovací řetězce, takže můžou obsahovat
+/// proměnné z okolí: tr!(lang, "Funkce '{name}'", "Function '{name}'")
+macro_rules! tr {
+    ($lang:expr, $cs:literal, $en:literal) => {
+        match $lang {
+            $crate::base::messages::Language::Czech => format!($cs),
+            $crate::base::messages::Language::English => format!($en),
+        }
+    };
+}
+pub(crate) use tr;
+
+/// Jazyk chybových hlášení a textů konzole. Chyby se vytvářejí bez jazyka (ErrorKind)
+/// a do textu se převedou až při výpisu, viz CalcError::localized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    Czech,
+    English,
+}
+
+impl Language {
+    /// Proměnná prostředí, kterou jde zvolit jazyk konzole: NEUCALC_LANG=en
+    pub const ENV_VAR: &'static str = "NEUCALC_LANG";
+
+    /// Jazyk podle názvu. Stačí kód jazyka, zbytek názvu locale se ignoruje (cs_CZ.UTF-8).
+    pub fn from_name(name: &str) -> Option<Language> {
+        let name = name.trim().to_lowercase();
+        let code = name.split(['_', '-', '.']).next().unwrap_or_default();
+        match code {
+            "cs" | "cz" | "czech" | "cesky" | "česky" => Some(Language::Czech),
+            "en" | "english" | "anglicky" => Some(Language::English),
+            _ => None,
+        }
+    }
+
+    /// Jazyk zvolený proměnnou prostředí ENV_VAR, pokud je nastavená a platná
+    pub fn from_env() -> Option<Language> {
+        std::env::var(Language::ENV_VAR)
+            .ok()
+            .and_then(|name| Language::from_name(&name))
+    }
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Language::Czech => write!(f, "čeština"),
+            Language::English => write!(f, "English"),
+        }
+    }
+}
+
+/// Co parser očekával místo neočekávaného symbolu nebo konce výrazu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    Value,
+    RightBracket,
+    /// Oddělovač parametrů (podle zápisu čísel) nebo pravá závorka
+    SeparatorOrRightBracket(char),
+}
+
+impl Expected {
+    fn text(&self, lang: Language) -> String {
+        match self {
+            Expected::Value => tr!(lang, "hodnota", "a value"),
+            Expected::RightBracket => tr!(lang, "pravá závorka", "a closing bracket"),
+            Expected::SeparatorOrRightBracket(sep) => tr!(
+                lang,
+                "oddělovač '{sep}' nebo pravá závorka",
+                "separator '{sep}' or a closing bracket"
+            ),
+        }
+    }
+}
+
+/// Druh chyby s hodnotami, které do hlášení patří. Podle druhu se v katalogu (message)
+/// vybere text v požadovaném jazyce, kód (code) se nemění a dá se použít pro vlastní
+/// překlad. Hodnoty (value, literal...) jsou už převedené na text, typy hodnot jsou názvy
+/// z Value::type_name, takže se podle nich dá rozhodovat.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    // Zápis výrazu
+    UnexpectedChar {
+        ch: char,
+    },
+    UnterminatedText,
+    /// Závorka bez páru: pravá bez levé, nebo neuzavřená levá. pos je index bajtu závorky
+    /// ve vstupním textu.
+    UnbalancedBrackets {
+        pos: usize,
+        bracket: char,
+    },
+    UnexpectedToken {
+        token: String,
+        expected: Option<Expected>,
+    },
+    UnexpectedEnd {
+        expected: Expected,
+    },
+    MisplacedDigitSeparator {
+        literal: String,
+    },
+    MultipleDecimalSeparators {
+        literal: String,
+    },
+    InvalidNumber {
+        literal: String,
+    },
+    NumberWithoutDigits {
+        literal: String,
+    },
+    NonIntegerExponent {
+        literal: String,
+    },
+    ExponentTooLarge {
+        literal: String,
+        max: u32,
+    },
+    InvalidValue {
+        text: String,
+    },
+    NotFuncCall,
+    NotParsed,
+
+    // Nastavení
+    NotDigitCount {
+        text: String,
+    },
+    /// Počet číslic mimo povolený rozsah. subject je funkce nebo formát, kterého se týká.
+    DigitsOutOfRange {
+        subject: Option<String>,
+        min: u32,
+        max: u32,
+        got: String,
+    },
+    FormatTooManyParts {
+        format: String,
+    },
+    UnknownFormat {
+        format: String,
+    },
+
+    // Operátory a hodnoty
+    /// Binární operátor nejde použít na hodnoty těchto typů, např. 1 / "a"
+    TypeMismatch {
+        op: char,
+        left_type: &'static str,
+        right_type: &'static str,
+    },
+    /// Unární operátor nejde použít na hodnotu tohoto typu, např. -"a"
+    UnsupportedOperand {
+        op: char,
+        operand_type: &'static str,
+    },
+    ListArithmetic,
+    DivisionByZero,
+    /// Výsledek je příliš velký, než aby ho šlo spočítat nebo uložit
+    Overflow,
+    BigIntPower,
+    ZeroToNonPositivePower,
+    UnknownOperator {
+        op: String,
+    },
+    UnknownUnaryOperator {
+        op: String,
+    },
+    NoOperands {
+        op: String,
+    },
+    MissingOperand {
+        op: String,
+    },
+    MissingRightOperand {
+        op: String,
+    },
+    NotReal {
+        value: String,
+    },
+    NotDecimal {
+        value: String,
+    },
+    NotComparable {
+        left: String,
+        right: String,
+    },
+    NotOrderable {
+        left: String,
+        right: String,
+    },
+    ExpectedBool {
+        context: String,
+        got: String,
+    },
+    /// Funkce není pro tento parametr definovaná, např. ln(0), tan(90°) nebo fact(-1)
+    DomainError {
+        func: String,
+        arg: String,
+    },
+    ComplexAngleUnit {
+        unit: AngleUnit,
+    },
+
+    // Proměnné a funkce
+    UnknownConstant {
+        name: String,
+    },
+    UndefinedVariable {
+        name: String,
+    },
+    InvalidVariableName {
+        name: String,
+    },
+    AssignToAns,
+    AssignToConstant {
+        name: String,
+    },
+    UnknownFunction {
+        name: String,
+    },
+    InvalidFuncName {
+        name: String,
+    },
+    BuiltinFunc {
+        name: String,
+    },
+    ConstantFuncName {
+        name: String,
+    },
+    DuplicateParam {
+        func: String,
+        param: String,
+    },
+    CallDepthExceeded {
+        func: String,
+        max: usize,
+    },
+    /// Výraz je zanořený hlouběji než MAX_NESTING_DEPTH
+    NestingTooDeep {
+        max: usize,
+    },
+    /// Funkce dostala špatný počet parametrů
+    ArityMismatch {
+        name: String,
+        expected: Arity,
+        got: usize,
+    },
+    /// Parametr funkce má špatný typ. position se počítá od 1.
+    ParamTypeMismatch {
+        name: String,
+        position: usize,
+        expected: ParamType,
+        got_type: &'static str,
+    },
+    /// Funkci nejde zavolat s parametry těchto typů nebo hodnot, např. fact(Rational)
+    InvalidCall {
+        func: String,
+        args: String,
+    },
+    /// Parametry funkce nesplňují podmínku, např. nck(n, k) a n >= k
+    Requirement {
+        call: String,
+        condition: String,
+    },
+}
+
+impl ErrorKind {
+    /// Kód chyby nezávislý na jazyce, např. "division_by_zero"
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::UnexpectedChar { .. } => "unexpected_char",
+            ErrorKind::UnterminatedText => "unterminated_text",
+            ErrorKind::UnbalancedBrackets { .. } => "unbalanced_brackets",
+            ErrorKind::UnexpectedToken { .. } => "unexpected_token",
+            ErrorKind::UnexpectedEnd { .. } => "unexpected_end",
+            ErrorKind::MisplacedDigitSeparator { .. } => "misplaced_digit_separator",
+            ErrorKind::MultipleDecimalSeparators { .. } => "multiple_decimal_separators",
+            ErrorKind::InvalidNumber { .. } => "invalid_number",
+            ErrorKind::NumberWithoutDigits { .. } => "number_without_digits",
+            ErrorKind::NonIntegerExponent { .. } => "non_integer_exponent",
+            ErrorKind::ExponentTooLarge { .. } => "exponent_too_large",
+            ErrorKind::InvalidValue { .. } => "invalid_value",
+            ErrorKind::NotFuncCall => "not_func_call",
+            ErrorKind::NotParsed => "not_parsed",
+            ErrorKind::NotDigitCount { .. } => "not_digit_count",
+            ErrorKind::DigitsOutOfRange { .. } => "digits_out_of_range",
+            ErrorKind::FormatTooManyParts { .. } => "format_too_many_parts",
+            ErrorKind::UnknownFormat { .. } => "unknown_format",
+            ErrorKind::TypeMismatch { .. } => "type_mismatch",
+            ErrorKind::UnsupportedOperand { .. } => "unsupported_operand",
+            ErrorKind::ListArithmetic => "list_arithmetic",
+            ErrorKind::DivisionByZero => "division_by_zero",
+            ErrorKind::Overflow => "overflow",
+            ErrorKind::BigIntPower => "bigint_power",
+            ErrorKind::ZeroToNonPositivePower => "zero_to_non_positive_power",
+            ErrorKind::UnknownOperator { .. } => "unknown_operator",
+            ErrorKind::UnknownUnaryOperator { .. } => "unknown_unary_operator",
+            ErrorKind::NoOperands { .. } => "no_operands",
+            ErrorKind::MissingOperand { .. } => "missing_operand",
+            ErrorKind::MissingRightOperand { .. } => "missing_right_operand",
+            ErrorKind::NotReal { .. } => "not_real",
+            ErrorKind::NotDecimal { .. } => "not_decimal",
+            ErrorKind::NotComparable { .. } => "not_comparable",
+            ErrorKind::NotOrderable { .. } => "not_orderable",
+            ErrorKind::ExpectedBool { .. } => "expected_bool",
+            ErrorKind::DomainError { .. } => "domain_error",
+            ErrorKind::ComplexAngleUnit { .. } => "complex_angle_unit",
+            ErrorKind::UnknownConstant { .. } => "unknown_constant",
+            ErrorKind::UndefinedVariable { .. } => "undefined_variable",
+            ErrorKind::InvalidVariableName { .. } => "invalid_variable_name",
+            ErrorKind::AssignToAns => "assign_to_ans",
+            ErrorKind::AssignToConstant { .. } => "assign_to_constant",
+            ErrorKind::UnknownFunction { .. } => "unknown_function",
+            ErrorKind::InvalidFuncName { .. } => "invalid_func_name",
+            ErrorKind::BuiltinFunc { .. } => "builtin_func",
+            ErrorKind::ConstantFuncName { .. } => "constant_func_name",
+            ErrorKind::DuplicateParam { .. } => "duplicate_param",
+            ErrorKind::CallDepthExceeded { .. } => "call_depth_exceeded",
+            ErrorKind::NestingTooDeep { .. } => "nesting_too_deep",
+            ErrorKind::ArityMismatch { .. } => "arity_mismatch",
+            ErrorKind::ParamTypeMismatch { .. } => "param_type_mismatch",
+            ErrorKind::InvalidCall { .. } => "invalid_call",
+            ErrorKind::Requirement { .. } => "requirement",
+        }
+    }
+
+    /// Katalog hlášení: text chyby v daném jazyce
+    pub fn message(&self, lang: Language) -> String {
+        match self {
+            ErrorKind::UnexpectedChar { ch } => tr!(
+                lang,
+                "Znak '{ch}' nepatří do matematického výrazu",
+                "Character '{ch}' does not belong in a mathematical expression"
+            ),
+            ErrorKind::UnterminatedText => tr!(
+                lang,
+                "Textovému výrazu chybí ukončovací uvozovka",
+                "Text is missing its closing quote"
+            ),
+            ErrorKind::UnbalancedBrackets { bracket: ')', .. } => tr!(
+                lang,
+                "Počet pravých závorek předběhl počet levých závorek",
+                "Closing bracket without a matching opening bracket"
+            ),
+            ErrorKind::UnbalancedBrackets { .. } => tr!(
+                lang,
+                "Počet levých a pravých závorek musí být stejný",
+                "Opening bracket is never closed"
+            ),
+            ErrorKind::UnexpectedToken {
+                token,
+                expected: None,
+            } => tr!(
+                lang,
+                "Neočekávaný symbol '{token}' ve výrazu",
+                "Unexpected symbol '{token}' in the expression"
+            ),
+            ErrorKind::UnexpectedToken {
+                token,
+                expected: Some(expected),
+            } => {
+                let expected = expected.text(lang);
+                tr!(
+                    lang,
+                    "Neočekávaný symbol '{token}', očekává se {expected}",
+                    "Unexpected symbol '{token}', expected {expected}"
+                )
+            }
+            ErrorKind::UnexpectedEnd { expected } => {
+                let expected = expected.text(lang);
+                tr!(
+                    lang,
+                    "Výraz končí, ale očekává se {expected}",
+                    "Unexpected end of the expression, expected {expected}"
+                )
+            }
+            ErrorKind::MisplacedDigitSeparator { literal } => tr!(
+                lang,
+                "Číslo '{literal}' má oddělovač číslic na špatném místě",
+                "Number '{literal}' has a digit separator in the wrong place"
+            ),
+            ErrorKind::MultipleDecimalSeparators { literal } => tr!(
+                lang,
+                "Číslo '{literal}' má víc desetinných oddělovačů",
+                "Number '{literal}' has more than one decimal separator"
+            ),
+            ErrorKind::InvalidNumber { literal } => tr!(
+                lang,
+                "'{literal}' není platné číslo",
+                "'{literal}' is not a valid number"
+            ),
+            ErrorKind::NumberWithoutDigits { literal } => tr!(
+                lang,
+                "Číslo '{literal}' nemá číslice před ani za desetinným oddělovačem",
+                "Number '{literal}' has no digits before or after the decimal separator"
+            ),
+            ErrorKind::NonIntegerExponent { literal } => tr!(
+                lang,
+                "Exponent čísla '{literal}' musí být celé číslo",
+                "Exponent of number '{literal}' must be an integer"
+            ),
+            ErrorKind::ExponentTooLarge { literal, max } => tr!(
+                lang,
+                "Exponent čísla '{literal}' může být nejvýše {max}",
+                "Exponent of number '{literal}' can be at most {max}"
+            ),
+            ErrorKind::InvalidValue { text } => tr!(
+                lang,
+                "Výraz '{text}' není platná hodnota",
+                "'{text}' is not a valid value"
+            ),
+            ErrorKind::NotFuncCall => tr!(
+                lang,
+                "Výraz nemá tvar volání funkce: název(parametry)",
+                "Expression is not a function call: name(parameters)"
+            ),
+            ErrorKind::NotParsed => tr!(
+                lang,
+                "Výraz nebyl před vyhodnocením parsován",
+                "Expression was not parsed before evaluation"
+            ),
+            ErrorKind::NotDigitCount { text } => tr!(
+                lang,
+                "'{text}' není počet číslic",
+                "'{text}' is not a number of digits"
+            ),
+            ErrorKind::DigitsOutOfRange {
+                subject,
+                min,
+                max,
+                got,
+            } => {
+                let message = tr!(
+                    lang,
+                    "počet číslic musí být {min} až {max}, zadáno {got}",
+                    "number of digits must be {min} to {max}, got {got}"
+                );
+                match subject {
+                    Some(subject) => format!("{subject}: {message}"),
+                    None => capitalize(&message),
+                }
+            }
+            ErrorKind::FormatTooManyParts { format } => tr!(
+                lang,
+                "Formát '{format}' má příliš mnoho částí",
+                "Format '{format}' has too many parts"
+            ),
+            ErrorKind::UnknownFormat { format } => tr!(
+                lang,
+                "Neznámý formát čísel '{format}'. Dostupné formáty: default, hex, bin, oct, \
+                mixed, repeating, fixed <místa>, sig <číslice>, sci [číslice], eng [číslice]",
+                "Unknown number format '{format}'. Available formats: default, hex, bin, oct, \
+                mixed, repeating, fixed <places>, sig <digits>, sci [digits], eng [digits]"
+            ),
+            ErrorKind::TypeMismatch {
+                op,
+                left_type,
+                right_type,
+            } => tr!(
+                lang,
+                "Operátor {op} nelze použít na {left_type} a {right_type}",
+                "Operator {op} cannot be applied to {left_type} and {right_type}"
+            ),
+            ErrorKind::UnsupportedOperand { op, operand_type } => tr!(
+                lang,
+                "Operátor {op} nelze použít na {operand_type}",
+                "Operator {op} cannot be applied to {operand_type}"
+            ),
+            ErrorKind::ListArithmetic => tr!(
+                lang,
+                "Se seznamem hodnot nelze počítat, lze ho jen vypsat",
+                "A list of values cannot be calculated with, only printed"
+            ),
+            ErrorKind::DivisionByZero => tr!(lang, "Nulou nelze dělit", "Division by zero"),
+            ErrorKind::Overflow => tr!(lang, "Výsledek je příliš velký", "The result is too large"),
+            ErrorKind::BigIntPower => tr!(
+                lang,
+                "Mocnění velkých celých čísel není povoleno",
+                "Raising to a big integer power is not supported"
+            ),
+            ErrorKind::ZeroToNonPositivePower => tr!(
+                lang,
+                "Nulu lze mocnit jen exponentem s kladnou reálnou částí",
+                "Zero can only be raised to an exponent with a positive real part"
+            ),
+            ErrorKind::UnknownOperator { op } => tr!(
+                lang,
+                "Znak '{op}' není definovaný operátor",
+                "'{op}' is not a defined operator"
+            ),
+            ErrorKind::UnknownUnaryOperator { op } => tr!(
+                lang,
+                "Znak '{op}' není definovaný unární operátor",
+                "'{op}' is not a defined unary operator"
+            ),
+            ErrorKind::NoOperands { op } => tr!(
+                lang,
+                "Operátor {op} nemá žádné operandy",
+                "Operator {op} has no operands"
+            ),
+            ErrorKind::MissingOperand { op } => tr!(
+                lang,
+                "Operátor {op} vyžaduje dva operandy",
+                "Operator {op} requires two operands"
+            ),
+            ErrorKind::MissingRightOperand { op } => tr!(
+                lang,
+                "Operátoru {op} chybí pravý operand",
+                "Operator {op} is missing its right operand"
+            ),
+            ErrorKind::NotReal { value } => tr!(
+                lang,
+                "{value} nelze převést na reálné číslo",
+                "{value} cannot be converted to a real number"
+            ),
+            ErrorKind::NotDecimal { value } => tr!(
+                lang,
+                "{value} nelze převést na desetinné číslo",
+                "{value} cannot be converted to a decimal number"
+            ),
+            ErrorKind::NotComparable { left, right } => tr!(
+                lang,
+                "Hodnoty {left} a {right} nelze porovnat",
+                "Values {left} and {right} cannot be compared"
+            ),
+            ErrorKind::NotOrderable { left, right } => tr!(
+                lang,
+                "Nelze porovnat {left} a {right}, uspořádat lze jen čísla",
+                "Cannot compare {left} and {right}, only numbers can be ordered"
+            ),
+            ErrorKind::ExpectedBool { context, got } => tr!(
+                lang,
+                "'{context}' očekává bool, zadáno bylo {got}",
+                "'{context}' expects a bool, got {got}"
+            ),
+            ErrorKind::DomainError { func, arg } => tr!(
+                lang,
+                "{func}({arg}) není definováno",
+                "{func}({arg}) is undefined"
+            ),
+            ErrorKind::ComplexAngleUnit { unit } => {
+                let unit = angle_unit_name(*unit, lang);
+                tr!(
+                    lang,
+                    "Komplexní úhel lze zadat jen v radiánech, nastavené jsou {unit}",
+                    "A complex angle can only be given in radians, the angle unit is {unit}"
+                )
+            }
+            ErrorKind::UnknownConstant { name } => tr!(
+                lang,
+                "'{name}' není pojmenovaná konstanta",
+                "'{name}' is not a named constant"
+            ),
+            ErrorKind::UndefinedVariable { name } => tr!(
+                lang,
+                "Proměnná '{name}' není definována",
+                "Variable '{name}' is not defined"
+            ),
+            ErrorKind::InvalidVariableName { name } => tr!(
+                lang,
+                "'{name}' není platný název proměnné",
+                "'{name}' is not a valid variable name"
+            ),
+            ErrorKind::AssignToAns => tr!(
+                lang,
+                "Do proměnné 'ans' nelze přiřazovat, obsahuje výsledek posledního výpočtu",
+                "Cannot assign to 'ans', it holds the result of the last calculation"
+            ),
+            ErrorKind::AssignToConstant { name } => tr!(
+                lang,
+                "'{name}' je konstanta, nelze do ní přiřadit hodnotu",
+                "'{name}' is a constant, it cannot be assigned to"
+            ),
+            ErrorKind::UnknownFunction { name } => tr!(
+                lang,
+                "Funkce '{name}' není definována",
+                "Function '{name}' is not defined"
+            ),
+            ErrorKind::InvalidFuncName { name } => tr!(
+                lang,
+                "'{name}' není platný název funkce",
+                "'{name}' is not a valid function name"
+            ),
+            ErrorKind::BuiltinFunc { name } => tr!(
+                lang,
+                "Funkce '{name}' je vestavěná a nelze ji předefinovat",
+                "Function '{name}' is built in and cannot be redefined"
+            ),
+            ErrorKind::ConstantFuncName { name } => tr!(
+                lang,
+                "'{name}' je konstanta, funkce se nemůže jmenovat stejně",
+                "'{name}' is a constant, a function cannot have the same name"
+            ),
+            ErrorKind::DuplicateParam { func, param } => tr!(
+                lang,
+                "Parametr '{param}' je ve funkci '{func}' uveden vícekrát",
+                "Parameter '{param}' appears more than once in function '{func}'"
+            ),
+            ErrorKind::CallDepthExceeded { func, max } => tr!(
+                lang,
+                "Funkce '{func}' překročila maximální hloubku volání {max}",
+                "Function '{func}' exceeded the maximum call depth of {max}"
+            ),
+            ErrorKind::NestingTooDeep { max } => tr!(
+                lang,
+                "Výraz je zanořený příliš hluboko, povoleno je nejvýše {max} úrovní",
+                "The expression is nested too deeply, at most {max} levels are allowed"
+            ),
+            ErrorKind::ArityMismatch {
+                name,
+                expected,
+                got,
+            } => {
+                let expected = match (lang, expected) {
+                    (Language::Czech, Arity::Exact(count)) => {
+                        format!("{count} {}", params_word_cs(*count))
+                    }
+                    (Language::Czech, Arity::Range(min, max)) => {
+                        format!("{min} až {max} {}", params_word_cs(*max))
+                    }
+                    (Language::Czech, Arity::AtLeast(min)) => {
+                        format!("aspoň {min} {}", params_word_cs(*min))
+                    }
+                    (Language::English, Arity::Exact(count)) => {
+                        format!("{count} {}", params_word_en(*count))
+                    }
+                    (Language::English, Arity::Range(min, max)) => {
+                        format!("{min} to {max} {}", params_word_en(*max))
+                    }
+                    (Language::English, Arity::AtLeast(min)) => {
+                        format!("at least {min} {}", params_word_en(*min))
+                    }
+                };
+                tr!(
+                    lang,
+                    "Funkce '{name}' vyžaduje {expected}, zadáno bylo {got}",
+                    "Function '{name}' takes {expected}, got {got}"
+                )
+            }
+            ErrorKind::ParamTypeMismatch {
+                name,
+                position,
+                expected,
+                got_type,
+            } => {
+                let expected = param_type_name(*expected, lang);
+                tr!(
+                    lang,
+                    "Funkce '{name}' očekává jako {position}. parametr {expected}, zadáno bylo {got_type}",
+                    "Function '{name}' expects {expected} as parameter {position}, got {got_type}"
+                )
+            }
+            ErrorKind::InvalidCall { func, args } => tr!(
+                lang,
+                "{func}({args}) není platné volání funkce",
+                "{func}({args}) is not a valid function call"
+            ),
+            ErrorKind::Requirement { call, condition } => tr!(
+                lang,
+                "{call} vyžaduje {condition}",
+                "{call} requires {condition}"
+            ),
+        }
+    }
+}
+
+/// Hlášení v češtině, stejně jako dřív, než byly chyby přeložitelné
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message(Language::Czech))
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn params_word_cs(count: usize) -> &'static str {
+    match count {
+        1 => "parametr",
+        2..=4 => "parametry",
+        _ => "parametrů",
+    }
+}
+
+fn params_word_en(count: usize) -> &'static str {
+    match count {
+        1 => "parameter",
+        _ => "parameters",
+    }
+}
+
+fn param_type_name(param_type: ParamType, lang: Language) -> String {
+    match lang {
+        Language::Czech => param_type.to_string(),
+        Language::English => match param_type {
+            ParamType::Any => s!("a value"),
+            ParamType::Number => s!("a number"),
+            ParamType::Complex => s!("a complex number"),
+            ParamType::Integer => s!("an int"),
+            ParamType::Bool => s!("a bool"),
+            ParamType::Text => s!("a text"),
+        },
+    }
+}
+
+/// Název jednotky úhlu v daném jazyce
+pub fn angle_unit_name(unit: AngleUnit, lang: Language) -> String {
+    match lang {
+        Language::Czech => unit.to_string(),
+        Language::English => match unit {
+            AngleUnit::Rad => s!("radians"),
+            AngleUnit::Deg => s!("degrees"),
+            AngleUnit::Grad => s!("gradians"),
+            AngleUnit::PiFraction => s!("multiples of π"),
+        },
+    }
+}
@@ -1,7 +1,13 @@
 use num_bigint::BigInt;
 use std::str::FromStr;
 
+pub mod messages;
+
+use messages::tr;
+pub use messages::{ErrorKind, Expected, Language};
+
 /// Makro převede něco na strukturu String
+#[doc(hidden)]
 #[macro_export]
 macro_rules! s {
     ($str:expr) => {
@@ -9,52 +15,149 @@ macro_rules! s {
     };
 }
 
-/// Flushne výstupní konzoli, čímž se vytiskne vše, co je v bufferu.
-#[macro_export]
-macro_rules! flush_stdout {
-    () => {
-        std::io::Write::flush(&mut std::io::stdout()).unwrap();
-    };
+/// Úsek vstupního textu. Pozice jsou indexy bajtů (ne znaků!), aby se daly přímo
+/// použít pro řezání &str. Konec je exkluzivní.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
 }
 
-/// Simple print with one or multiple parameters. Flushes IO automatically
-#[macro_export]
-macro_rules! sprint {
-    ($expr:tt) => {
-       print!("{}", $expr);
-       flush_stdout!();
-    };
-    ($($arg:tt)*) => {
-        print!($($arg)*);
-        flush_stdout!();
-    };
-}
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
 
-/// Simple println with one parameter
-#[macro_export]
-macro_rules! sprintln {
-    ($expr:tt) => {
-        println!("{}", $expr)
-    };
-    ($expr:expr) => {
-        println!("{}", $expr)
-    };
+    /// Posune úsek o daný počet bajtů doprava
+    pub fn shift(self, offset: usize) -> Span {
+        Span::new(self.start + offset, self.end + offset)
+    }
+
+    /// Vytvoří řádek se značkou ^~~~ pod tímto úsekem textu. Pozice úseku jsou v bajtech,
+    /// odsazení značky ale musí být ve znacích. Tabulátory se zachovají, aby značka
+    /// seděla i pod odsazeným textem.
+    pub fn underline(self, input: &str) -> Option<String> {
+        let before = input.get(..self.start)?;
+        let marked = input.get(self.start..self.end)?.trim_end();
+        let indent = before.chars().map(|c| if c == '\t' { '\t' } else { ' ' });
+        let tildes = "~".repeat(marked.chars().count().saturating_sub(1));
+        Some(indent.collect::<String>() + "^" + &tildes)
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum CalcError {
     #[error("Chyba vyhodnocení matematického výrazu: {0}")]
-    EvaluateErr(String),
+    EvaluateErr(ErrorKind),
 
     #[error("Chyba volání funkce: {0}")]
-    FuncCallErr(String),
+    FuncCallErr(ErrorKind),
 
     #[error("Syntaktická chyba matematického výrazu: {0}")]
-    ParseErr(String),
+    ParseErr(ErrorKind),
 
     #[error("Nepodařilo se převést text na BigInt.")]
     ParseBigIntErr(#[source] <BigInt as FromStr>::Err),
 
-    #[error("Nepodařilo se převést hodnotu na typ double.")]
-    ConvertToDoubleErr,
+    /// Chyba s vyznačeným místem ve vstupním výrazu, kde nastala
+    #[error("{inner}")]
+    Spanned { inner: Box<CalcError>, span: Span },
+}
+
+impl CalcError {
+    /// Druh chyby, ze kterého vzniká hlášení. Chyby převodu čísel druh nemají.
+    pub fn kind(&self) -> Option<&ErrorKind> {
+        match self {
+            CalcError::EvaluateErr(kind)
+            | CalcError::FuncCallErr(kind)
+            | CalcError::ParseErr(kind) => Some(kind),
+            CalcError::ParseBigIntErr(_) => None,
+            CalcError::Spanned { inner, .. } => inner.kind(),
+        }
+    }
+
+    /// Kód chyby nezávislý na jazyce (viz ErrorKind::code)
+    pub fn code(&self) -> &'static str {
+        match self {
+            CalcError::ParseBigIntErr(_) => "parse_bigint",
+            _ => self.kind().map_or("unknown", ErrorKind::code),
+        }
+    }
+
+    /// Hlášení chyby v daném jazyce. Display vypisuje hlášení v češtině.
+    pub fn localized(&self, lang: Language) -> String {
+        match self {
+            CalcError::EvaluateErr(kind) => {
+                let message = kind.message(lang);
+                tr!(
+                    lang,
+                    "Chyba vyhodnocení matematického výrazu: {message}",
+                    "Error evaluating the expression: {message}"
+                )
+            }
+            CalcError::FuncCallErr(kind) => {
+                let message = kind.message(lang);
+                tr!(
+                    lang,
+                    "Chyba volání funkce: {message}",
+                    "Function call error: {message}"
+                )
+            }
+            CalcError::ParseErr(kind) => {
+                let message = kind.message(lang);
+                tr!(
+                    lang,
+                    "Syntaktická chyba matematického výrazu: {message}",
+                    "Syntax error in the expression: {message}"
+                )
+            }
+            CalcError::ParseBigIntErr(_) => tr!(
+                lang,
+                "Nepodařilo se převést text na BigInt.",
+                "Failed to convert text to BigInt."
+            ),
+            CalcError::Spanned { inner, .. } => inner.localized(lang),
+        }
+    }
+
+    /// Připojí k chybě úsek vstupu, kterého se týká. Pokud už chyba úsek má, ponechá se
+    /// ten původní, protože ukazuje přesněji (chyba vznikla hlouběji ve výrazu).
+    pub fn with_span(self, span: Span) -> CalcError {
+        match self {
+            CalcError::Spanned { .. } => self,
+            _ => CalcError::Spanned {
+                inner: Box::new(self),
+                span,
+            },
+        }
+    }
+
+    /// Úsek vstupu, kterého se chyba týká, pokud je znám
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            CalcError::Spanned { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
+
+    /// Odstraní z chyby úsek vstupu. Hodí se, když chyba vznikla v jiném textu, než je
+    /// vstupní výraz (např. v těle uživatelské funkce).
+    pub fn without_span(self) -> CalcError {
+        match self {
+            CalcError::Spanned { inner, .. } => *inner,
+            _ => self,
+        }
+    }
+
+    /// Posune úsek chyby. Hodí se, když se chyba hledala jen v části vstupního textu.
+    pub fn shift_span(self, offset: usize) -> CalcError {
+        match self {
+            CalcError::Spanned { inner, span } => CalcError::Spanned {
+                inner,
+                span: span.shift(offset),
+            },
+            _ => self,
+        }
+    }
 }
@@ -0,0 +1,283 @@
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::std_funcs;
+use crate::calc_base::value::Value;
+use crate::s;
+use num_traits::Zero;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// Největší exponent, se kterým se komplexní číslo mocní přesně (opakovaným násobením).
+/// Při větším exponentu by zlomky neúměrně narostly, počítá se proto nepřesně.
+const MAX_EXACT_EXPONENT: i64 = 1024;
+
+/// Komplexní číslo re + im·i. Obě části jsou reálná čísla (Value) a počítá se s nimi jako
+/// s ostatními hodnotami: přesné části (zlomky, odmocniny, násobky π) zůstanou přesné při
+/// sčítání, odčítání, násobení, dělení a celočíselných mocninách, desetinné si zachovají
+/// přesnost. Funkce jako ln nebo sin vrací části f64.
+#[derive(Debug, Clone)]
+pub struct Complex {
+    re: Box<Value>,
+    im: Box<Value>,
+}
+
+impl Complex {
+    /// Komplexní číslo z reálné a imaginární části. Obě musí být reálná čísla.
+    pub fn new(re: Value, im: Value) -> Complex {
+        Complex {
+            re: Box::new(re),
+            im: Box::new(im),
+        }
+    }
+
+    /// Imaginární jednotka
+    pub fn i() -> Complex {
+        Complex::new(Value::Integer(0), Value::Integer(1))
+    }
+
+    pub fn one() -> Complex {
+        Complex::new(Value::Integer(1), Value::Integer(0))
+    }
+
+    /// Nepřesné číslo s částmi f64
+    fn inexact(re: f64, im: f64) -> Complex {
+        Complex::new(Value::Real(re), Value::Real(im))
+    }
+
+    /// Převede číslo na komplexní. Pro hodnoty, které nejsou čísla, vrátí None.
+    pub fn from_value(value: &Value) -> Option<Complex> {
+        match value {
+            Value::Integer(_)
+            | Value::BigInt(_)
+            | Value::Rational(_)
+            | Value::Real(_)
+            | Value::Decimal(_)
+            | Value::PiMultiple(_)
+            | Value::Surd(_) => Some(Complex::new(value.clone(), Value::Integer(0))),
+            Value::Complex(c) => Some(c.clone()),
+            _ => None,
+        }
+    }
+
+    /// Převede číslo na co nejjednodušší hodnotu. Bez imaginární části je to obyčejné číslo.
+    pub fn into_value(self) -> Result<Value, CalcError> {
+        if is_zero_part(&self.im) {
+            return self.re.simplify_type_move();
+        }
+        Ok(Value::Complex(Complex::new(
+            self.re.simplify_type_move()?,
+            self.im.simplify_type_move()?,
+        )))
+    }
+
+    /// Reálná a imaginární část
+    pub fn parts(&self) -> (Value, Value) {
+        ((*self.re).clone(), (*self.im).clone())
+    }
+
+    /// Reálná a imaginární část jako f64
+    pub fn to_f64_parts(&self) -> (f64, f64) {
+        (
+            self.re.as_real().unwrap_or(f64::NAN),
+            self.im.as_real().unwrap_or(f64::NAN),
+        )
+    }
+
+    pub fn is_zero(&self) -> bool {
+        is_zero_part(&self.re) && is_zero_part(&self.im)
+    }
+
+    /// (a + bi)/(c + di) = ((ac + bd) + (bc - ad)i) / (c² + d²)
+    pub fn checked_div(self, rhs: Complex) -> Result<Complex, CalcError> {
+        if rhs.is_zero() {
+            return Err(CalcError::EvaluateErr(ErrorKind::DivisionByZero));
+        }
+        let (a, b) = (*self.re, *self.im);
+        let (c, d) = (*rhs.re, *rhs.im);
+        let denom = ((c.clone() * c.clone())? + (d.clone() * d.clone())?)?;
+        let re = ((a.clone() * c.clone())? + (b.clone() * d.clone())?)?;
+        let im = ((b * c)? - (a * d)?)?;
+        Ok(Complex::new((re / denom.clone())?, (im / denom)?))
+    }
+
+    /// Absolutní hodnota |a + bi| = sqrt(a² + b²). U přesného čísla je přesná, pokud to
+    /// sqrt dovolí, např. |3 + 4i| = 5 nebo |1 + i| = √2.
+    pub fn abs(&self) -> Result<Value, CalcError> {
+        if let (Value::Real(_), _) | (_, Value::Real(_)) = (&*self.re, &*self.im) {
+            let (re, im) = self.to_f64_parts();
+            return Ok(Value::Real(re.hypot(im)));
+        }
+        let (re, im) = self.parts();
+        let square = ((re.clone() * re)? + (im.clone() * im)?)?;
+        std_funcs::sqrt(square, None)
+    }
+
+    /// Argument (úhel) čísla v intervalu (-π, π]
+    pub fn arg(&self) -> f64 {
+        let (re, im) = self.to_f64_parts();
+        im.atan2(re)
+    }
+
+    /// Hlavní hodnota přirozeného logaritmu: ln|z| + arg(z)·i
+    pub fn ln(&self) -> Result<Complex, CalcError> {
+        if self.is_zero() {
+            return Err(CalcError::EvaluateErr(ErrorKind::DomainError {
+                func: s!("ln"),
+                arg: s!("0"),
+            }));
+        }
+        let (re, im) = self.to_f64_parts();
+        Ok(Complex::inexact(re.hypot(im).ln(), self.arg()))
+    }
+
+    /// e^(a + bi) = e^a (cos b + i sin b)
+    pub fn exp(&self) -> Complex {
+        let (re, im) = self.to_f64_parts();
+        let modulus = re.exp();
+        Complex::inexact(modulus * im.cos(), modulus * im.sin())
+    }
+
+    /// Hlavní hodnota druhé odmocniny (s nezápornou reálnou částí)
+    pub fn sqrt(&self) -> Complex {
+        let (a, b) = self.to_f64_parts();
+        let modulus = a.hypot(b);
+        let re = ((modulus + a) / 2.0).sqrt();
+        let im = ((modulus - a) / 2.0).sqrt();
+        Complex::inexact(re, if b < 0.0 { -im } else { im })
+    }
+
+    /// sin(a + bi) = sin a · cosh b + i · cos a · sinh b
+    pub fn sin(&self) -> Complex {
+        let (a, b) = self.to_f64_parts();
+        Complex::inexact(a.sin() * b.cosh(), a.cos() * b.sinh())
+    }
+
+    /// cos(a + bi) = cos a · cosh b - i · sin a · sinh b
+    pub fn cos(&self) -> Complex {
+        let (a, b) = self.to_f64_parts();
+        Complex::inexact(a.cos() * b.cosh(), -a.sin() * b.sinh())
+    }
+
+    /// tan z = sin z / cos z
+    pub fn tan(&self) -> Result<Complex, CalcError> {
+        self.sin().checked_div(self.cos())
+    }
+
+    /// Mocnina z^w. Celočíselný exponent se počítá násobením, takže přesné části zůstanou
+    /// přesné, jinak se použije hlavní hodnota z^w = e^(w·ln z).
+    pub fn pow(&self, exponent: &Complex) -> Result<Complex, CalcError> {
+        if let (Value::Integer(n), true) = (&*exponent.re, is_zero_part(&exponent.im)) {
+            if n.abs() <= MAX_EXACT_EXPONENT {
+                return self.pow_int(*n);
+            }
+        }
+        if self.is_zero() {
+            let (re, _) = exponent.to_f64_parts();
+            return if re > 0.0 {
+                Ok(Complex::inexact(0.0, 0.0))
+            } else {
+                Err(CalcError::EvaluateErr(ErrorKind::ZeroToNonPositivePower))
+            };
+        }
+        Ok((exponent.clone() * self.ln()?)?.exp())
+    }
+
+    /// Celočíselná mocnina (binární umocňování)
+    fn pow_int(&self, exponent: i64) -> Result<Complex, CalcError> {
+        let mut result = Complex::one();
+        let mut base = self.clone();
+        let mut n = exponent.unsigned_abs();
+        while n > 0 {
+            if n & 1 == 1 {
+                result = (result * base.clone())?;
+            }
+            base = (base.clone() * base)?;
+            n >>= 1;
+        }
+        if exponent < 0 {
+            return Complex::one().checked_div(result);
+        }
+        Ok(result)
+    }
+}
+
+/// Vrací true, pokud je část komplexního čísla nulová
+fn is_zero_part(part: &Value) -> bool {
+    match part {
+        Value::Integer(i) => *i == 0,
+        Value::BigInt(i) => i.is_zero(),
+        Value::Rational(q) => q.numerator.is_zero(),
+        Value::Real(r) => *r == 0.0,
+        Value::Decimal(d) => d.is_zero(),
+        Value::PiMultiple(k) => k.numerator.is_zero(),
+        Value::Surd(x) => x.is_zero(),
+        _ => false,
+    }
+}
+
+/// Část komplexního čísla zapsaná jako výraz
+fn fmt_part(part: &Value) -> String {
+    match part {
+        Value::Rational(q) => q.to_compact_string(),
+        part => part.to_plain_string(),
+    }
+}
+
+impl Neg for Complex {
+    type Output = Result<Complex, CalcError>;
+
+    fn neg(self) -> Self::Output {
+        Ok(Complex::new((-*self.re)?, (-*self.im)?))
+    }
+}
+
+impl Add for Complex {
+    type Output = Result<Complex, CalcError>;
+
+    fn add(self, rhs: Complex) -> Self::Output {
+        Ok(Complex::new((*self.re + *rhs.re)?, (*self.im + *rhs.im)?))
+    }
+}
+
+impl Sub for Complex {
+    type Output = Result<Complex, CalcError>;
+
+    fn sub(self, rhs: Complex) -> Self::Output {
+        Ok(Complex::new((*self.re - *rhs.re)?, (*self.im - *rhs.im)?))
+    }
+}
+
+impl Mul for Complex {
+    type Output = Result<Complex, CalcError>;
+
+    /// (a + bi)(c + di) = (ac - bd) + (ad + bc)i
+    fn mul(self, rhs: Complex) -> Self::Output {
+        let (a, b) = (*self.re, *self.im);
+        let (c, d) = (*rhs.re, *rhs.im);
+        let re = ((a.clone() * c.clone())? - (b.clone() * d.clone())?)?;
+        let im = ((a * d)? + (b * c)?)?;
+        Ok(Complex::new(re, im))
+    }
+}
+
+impl Display for Complex {
+    /// Zapisuje se tak, aby šel výsledek znovu zadat jako výraz: 1/2 - 3/4*i
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let im_negative = self.im.is_negative_number();
+        let im_abs = std_funcs::abs((*self.im).clone()).unwrap_or_else(|_| (*self.im).clone());
+        let im = match im_abs {
+            // Odmocnina a + b·√n je součet, před násobením i se uzávorkuje
+            Value::Surd(x) if !x.a.numerator.is_zero() => format!("({x})*i"),
+            im => match fmt_part(&im).as_str() {
+                "1" => s!("i"),
+                im => format!("{im}*i"),
+            },
+        };
+        let re = fmt_part(&self.re);
+        match (is_zero_part(&self.re), im_negative) {
+            (true, false) => write!(f, "{im}"),
+            (true, true) => write!(f, "-{im}"),
+            (false, false) => write!(f, "{re} + {im}"),
+            (false, true) => write!(f, "{re} - {im}"),
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::decimal::MAX_DIGITS;
+use crate::calc_base::func_registry::{Arity, FuncDescriptor, FunctionRegistry};
+use crate::calc_base::lazy_funcs::is_lazy_func;
+use crate::calc_base::lexer::is_ident;
+use crate::calc_base::locale::Locale;
+use crate::calc_base::number_format::NumberFormat;
+use crate::calc_base::settings::CalcSettings;
+use crate::calc_base::trig_funcs::AngleUnit;
+use crate::calc_base::value::{is_named_const, Value};
+use crate::s;
+use std::collections::HashMap;
+
+/// Název proměnné, do které se automaticky ukládá výsledek posledního výpočtu
+pub const ANS: &str = "ans";
+
+/// Maximální hloubka zanoření volání uživatelských funkcí. Chrání před nekonečnou rekurzí.
+/// Každé volání spotřebuje i několik úrovní MAX_NESTING_DEPTH (volání, parametry, tělo).
+pub const MAX_CALL_DEPTH: usize = 250;
+
+/// Maximální hloubka zanoření při parsování a vyhodnocení výrazu (závorky, operátory, volání
+/// funkcí včetně těl uživatelských funkcí). Hlubší výraz by přetekl zásobník. Výraz zanořený
+/// až na tuto hranici potřebuje v ladicím sestavení zásobník kolem 16 MB, proto konzole
+/// počítá ve vlákně s větším zásobníkem (viz EVAL_STACK_SIZE v main.rs).
+pub const MAX_NESTING_DEPTH: usize = 1000;
+
+/// Funkce, kterou si uživatel nadefinoval přímo ve výrazu, např. f(x, y) = x^2 + y/3.
+/// Tělo se ukládá jako text, aby ho mohla vyhodnotit kterákoli strategie.
+#[derive(Debug, Clone)]
+pub struct UserFunc {
+    pub params: Vec<String>,
+    pub body: String,
+    /// Zápis, ve kterém je tělo napsané. Tělo se v něm čte i po změně locale.
+    pub locale: Locale,
+}
+
+/// Stav kalkulátoru, který přetrvává mezi jednotlivými výpočty (např. mezi řádky v konzoli).
+/// Strategie ho dostávají při vyhodnocení, takže z něj mohou číst a mohou do něj zapisovat.
+#[derive(Debug, Clone)]
+pub struct CalcContext {
+    variables: HashMap<String, Value>,
+    functions: HashMap<String, UserFunc>,
+    /// Vestavěné funkce a funkce zaregistrované přes register_func
+    registry: FunctionRegistry,
+    /// Parametry právě volaných uživatelských funkcí. Poslední prvek patří nejvnitřnějšímu
+    /// volání. Tělo funkce vidí jen své parametry a globální proměnné, ne parametry volajícího.
+    call_stack: Vec<HashMap<String, Value>>,
+    /// Aktuální hloubka zanoření vyhodnocení, viz nested
+    nesting: usize,
+    settings: CalcSettings,
+}
+
+impl Default for CalcContext {
+    fn default() -> Self {
+        CalcContext::with_registry(FunctionRegistry::default())
+    }
+}
+
+impl CalcContext {
+    /// Kontext s vlastním registrem funkcí, např. prázdným nebo rozšířeným o další funkce
+    pub fn with_registry(registry: FunctionRegistry) -> CalcContext {
+        let mut variables = HashMap::new();
+        variables.insert(ANS.to_string(), Value::Integer(0));
+        CalcContext {
+            variables,
+            functions: HashMap::new(),
+            registry,
+            call_stack: vec![],
+            nesting: 0,
+            settings: CalcSettings::default(),
+        }
+    }
+
+    /// Vrátí hodnotu proměnné. Pojmenované konstanty (pi, e...) mají přednost, proto se
+    /// proměnné hledají až po nich (viz Value::parse). Parametry funkce zastíní globální proměnné.
+    pub fn get_var(&self, name: &str) -> Option<&Value> {
+        self.call_stack
+            .last()
+            .and_then(|locals| locals.get(name))
+            .or_else(|| self.variables.get(name))
+    }
+
+    /// Přiřadí hodnotu proměnné. Název nesmí kolidovat s konstantou, booleanem ani s 'ans'.
+    pub fn set_var(&mut self, name: &str, value: Value) -> Result<(), CalcError> {
+        let name = name.trim();
+        check_var_name(name)?;
+        self.variables.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// Uloží výsledek posledního výpočtu do proměnné 'ans'
+    pub fn set_ans(&mut self, value: Value) {
+        self.variables.insert(ANS.to_string(), value);
+    }
+
+    /// Uloží uživatelskou funkci. Existující uživatelská funkce se stejným názvem se přepíše.
+    /// Vestavěné funkce a konstanty předefinovat nelze. Parametry se řídí stejnými pravidly
+    /// jako názvy proměnných, protože se při volání chovají jako lokální proměnné.
+    pub fn define_func(
+        &mut self,
+        name: &str,
+        params: Vec<String>,
+        body: &str,
+    ) -> Result<(), CalcError> {
+        let name = name.trim().to_lowercase();
+        if self.registry.contains(&name) || is_lazy_func(&name) {
+            return Err(CalcError::EvaluateErr(ErrorKind::BuiltinFunc { name }));
+        }
+        if is_named_const(&name).is_some() {
+            return Err(CalcError::EvaluateErr(ErrorKind::ConstantFuncName { name }));
+        }
+        for (idx, param) in params.iter().enumerate() {
+            check_var_name(param)?;
+            if params[..idx].contains(param) {
+                return Err(CalcError::EvaluateErr(ErrorKind::DuplicateParam {
+                    func: name,
+                    param: param.clone(),
+                }));
+            }
+        }
+        let func = UserFunc {
+            params,
+            body: body.trim().to_string(),
+            locale: self.settings.locale,
+        };
+        self.functions.insert(name, func);
+        Ok(())
+    }
+
+    /// Nastavení platné pro všechny výpočty s tímto kontextem
+    pub fn settings(&self) -> &CalcSettings {
+        &self.settings
+    }
+
+    /// Nastaví jednotku úhlu pro goniometrické funkce bez přípony (sin, asin...) a pro
+    /// úhly zadané s příponou jednotky (30deg se převede na tuto jednotku)
+    pub fn set_angle_unit(&mut self, unit: AngleUnit) {
+        self.settings.angle_unit = unit;
+    }
+
+    /// Zapne režim desetinných čísel s daným počtem platných číslic (1 až MAX_DIGITS),
+    /// nebo ho vypne (None)
+    pub fn set_decimal_digits(&mut self, digits: Option<u32>) -> Result<(), CalcError> {
+        if let Some(digits) = digits {
+            if !(1..=MAX_DIGITS).contains(&digits) {
+                return Err(CalcError::EvaluateErr(ErrorKind::DigitsOutOfRange {
+                    subject: None,
+                    min: 1,
+                    max: MAX_DIGITS,
+                    got: digits.to_string(),
+                }));
+            }
+        }
+        self.settings.decimal_digits = digits;
+        Ok(())
+    }
+
+    /// Nastaví formát, ve kterém se vypisují výsledky (viz Value::format_with)
+    pub fn set_number_format(&mut self, format: NumberFormat) -> Result<(), CalcError> {
+        format.check_digits()?;
+        self.settings.number_format = format;
+        Ok(())
+    }
+
+    /// Zapne nebo vypne oddělování tisíců ve výpisu výsledků
+    pub fn set_digit_grouping(&mut self, grouping: bool) {
+        self.settings.digit_grouping = grouping;
+    }
+
+    /// Nastaví desetinný oddělovač a oddělovač parametrů (viz Locale). Už definované
+    /// uživatelské funkce si ponechají zápis, ve kterém byly definované.
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.settings.locale = locale;
+    }
+
+    /// Registr vestavěných funkcí
+    pub fn registry(&self) -> &FunctionRegistry {
+        &self.registry
+    }
+
+    /// Přidá do registru novou vestavěnou funkci (viz FunctionRegistry::register). Případná
+    /// uživatelská funkce se stejným názvem se odstraní, jinak by novou funkci zastínila.
+    pub fn register_func(&mut self, func: FuncDescriptor) -> Result<(), CalcError> {
+        let name = func.name().to_string();
+        self.registry.register(func)?;
+        self.functions.remove(&name);
+        Ok(())
+    }
+
+    /// Vrátí uživatelskou funkci s daným názvem (názvy funkcí nerozlišují velikost písmen)
+    pub fn get_func(&self, name: &str) -> Option<&UserFunc> {
+        self.functions.get(&name.trim().to_lowercase())
+    }
+
+    /// Zavolá uživatelskou funkci. Zkontroluje počet parametrů a hloubku rekurze, dosadí
+    /// parametry jako lokální proměnné a tělo nechá vyhodnotit funkcí eval_body (tu dodá
+    /// strategie) v zápisu, ve kterém byla funkce definovaná. Pozice chyb uvnitř těla se
+    /// zahodí, protože se netýkají vstupního výrazu.
+    pub fn call_user_func(
+        &mut self,
+        name: &str,
+        args: Vec<Value>,
+        eval_body: impl FnOnce(&str, Locale, &mut CalcContext) -> Result<Value, CalcError>,
+    ) -> Result<Value, CalcError> {
+        let func = self
+            .get_func(name)
+            .cloned()
+            .ok_or_else(|| CalcError::FuncCallErr(ErrorKind::UnknownFunction { name: s!(name) }))?;
+        if args.len() != func.params.len() {
+            return Err(CalcError::FuncCallErr(ErrorKind::ArityMismatch {
+                name: s!(name),
+                expected: Arity::Exact(func.params.len()),
+                got: args.len(),
+            }));
+        }
+        if self.call_stack.len() >= MAX_CALL_DEPTH {
+            return Err(CalcError::FuncCallErr(ErrorKind::CallDepthExceeded {
+                func: s!(name),
+                max: MAX_CALL_DEPTH,
+            }));
+        }
+
+        self.call_stack
+            .push(func.params.into_iter().zip(args).collect());
+        let result = eval_body(&func.body, func.locale, self);
+        self.call_stack.pop();
+        result.map_err(CalcError::without_span)
+    }
+
+    /// Zahodí stav rozpracovaného vyhodnocení (parametry volaných funkcí a hloubku
+    /// zanoření). Hodí se, když byl výpočet násilně přerušen a neskončil normálně.
+    pub fn abort_evaluation(&mut self) {
+        self.call_stack.clear();
+        self.nesting = 0;
+    }
+
+    /// Vyhodnotí zanořenou část výrazu o úroveň hlouběji. Strategie tím obalí své rekurzivní
+    /// vyhodnocení, takže příliš hluboký výraz skončí chybou místo přetečení zásobníku.
+    pub fn nested<T>(
+        &mut self,
+        eval: impl FnOnce(&mut CalcContext) -> Result<T, CalcError>,
+    ) -> Result<T, CalcError> {
+        if self.nesting >= MAX_NESTING_DEPTH {
+            return Err(CalcError::EvaluateErr(ErrorKind::NestingTooDeep {
+                max: MAX_NESTING_DEPTH,
+            }));
+        }
+        self.nesting += 1;
+        let result = eval(self);
+        self.nesting -= 1;
+        result
+    }
+}
+
+/// Zkontroluje, že se do proměnné s tímto názvem dá přiřadit
+fn check_var_name(name: &str) -> Result<(), CalcError> {
+    if !is_ident(name) {
+        Err(CalcError::EvaluateErr(ErrorKind::InvalidVariableName {
+            name: s!(name),
+        }))
+    } else if name == ANS {
+        Err(CalcError::EvaluateErr(ErrorKind::AssignToAns))
+    } else if is_named_const(name).is_some() || name.parse::<bool>().is_ok() {
+        Err(CalcError::EvaluateErr(ErrorKind::AssignToConstant {
+            name: s!(name),
+        }))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,678 @@
+use crate::calc_base::rational::Rational;
+use crate::calc_base::value::Value;
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::Neg;
+
+/// Počet platných číslic, pokud ho uživatel nezadá (funkce dec bez druhého parametru)
+pub const DEFAULT_DIGITS: u32 = 50;
+
+/// Největší povolený počet platných číslic. S větší přesností by výpočty trvaly příliš dlouho.
+pub const MAX_DIGITS: u32 = 5_000;
+
+/// Číslice navíc, se kterými se počítají mezivýsledky funkcí, aby zaokrouhlovací chyby
+/// neovlivnily platné číslice výsledku
+pub const GUARD_DIGITS: u32 = 10;
+
+/// Největší argument exp, se kterým se ještě počítá. exp(10000) má přes 4000 číslic.
+const MAX_EXP_ARG: f64 = 10_000.0;
+
+/// Desetinné číslo s volitelnou přesností: mantissa · 10^exponent, kde mantisa má nejvýše
+/// `digits` platných číslic. Vzniká funkcí dec, nebo v režimu desetinných čísel tam, kde by
+/// jinak vzniklo nepřesné reálné číslo (ln, goniometrické funkce, konstanta e). Přesnost se
+/// přenáší výpočtem: přesné číslo (zlomek, násobek π, odmocnina) se při operaci s desetinným
+/// převede na desetinné se stejnou přesností, dvě desetinná čísla dají výsledek s menší
+/// z jejich přesností. S reálným číslem (f64) je výsledek opět reálné číslo.
+#[derive(Debug, Clone)]
+pub struct Decimal {
+    mantissa: BigInt,
+    exponent: i64,
+    digits: u32,
+}
+
+impl Decimal {
+    /// Číslo mantissa · 10^exponent zaokrouhlené na daný počet platných číslic (polovina
+    /// se zaokrouhluje od nuly). Nuly na konci mantisy se přesunou do exponentu.
+    fn rounded(mut mantissa: BigInt, mut exponent: i64, digits: u32) -> Decimal {
+        let len = digit_count(&mantissa);
+        if len > i64::from(digits) {
+            let dropped = (len - i64::from(digits)) as u32;
+            let divisor = pow10(dropped);
+            let (quotient, remainder) = mantissa.div_rem(&divisor);
+            mantissa = quotient;
+            if remainder.abs() * 2 >= divisor {
+                mantissa += remainder.signum();
+            }
+            exponent += i64::from(dropped);
+        }
+        let ten = BigInt::from(10);
+        while !mantissa.is_zero() && (&mantissa % &ten).is_zero() {
+            mantissa /= &ten;
+            exponent += 1;
+        }
+        if mantissa.is_zero() {
+            exponent = 0;
+        }
+        Decimal {
+            mantissa,
+            exponent,
+            digits,
+        }
+    }
+
+    pub fn zero(digits: u32) -> Decimal {
+        Decimal::from_int(0, digits)
+    }
+
+    pub fn one(digits: u32) -> Decimal {
+        Decimal::from_int(1, digits)
+    }
+
+    pub fn from_int(i: i64, digits: u32) -> Decimal {
+        Decimal::rounded(BigInt::from(i), 0, digits)
+    }
+
+    /// Zlomek zaokrouhlený na daný počet platných číslic
+    pub fn from_rational(q: &Rational, digits: u32) -> Decimal {
+        let q = q.clone().reduce_move();
+        let (numerator, denominator) = (&q.numerator, &q.denominator);
+        if numerator.is_zero() {
+            return Decimal::zero(digits);
+        }
+        // Podíl bude mít aspoň o jednu číslici víc, než je potřeba, takže ho stačí useknout
+        // a zaokrouhlit až podle první useknuté číslice
+        let shift = i64::from(digits) + 2 - (digit_count(numerator) - digit_count(denominator));
+        let mantissa = if shift >= 0 {
+            numerator * pow10(shift as u32) / denominator
+        } else {
+            numerator / (denominator * pow10((-shift) as u32))
+        };
+        Decimal::rounded(mantissa, -shift, digits)
+    }
+
+    /// Převede přesné číslo na desetinné s danou přesností. Desetinné číslo si ponechá svou
+    /// přesnost. Reálné ani komplexní číslo převést nelze, jejich přesnost je omezená.
+    pub fn from_value(value: &Value, digits: u32) -> Option<Decimal> {
+        match value {
+            Value::Integer(i) => Some(Decimal::from_int(*i, digits)),
+            Value::BigInt(i) => Some(Decimal::rounded(i.clone(), 0, digits)),
+            Value::Rational(q) => Some(Decimal::from_rational(q, digits)),
+            Value::PiMultiple(k) => {
+                let work = digits + GUARD_DIGITS;
+                let pi = Decimal::pi(work);
+                Some(pi.mul(&Decimal::from_rational(k, work)).with_digits(digits))
+            }
+            Value::Surd(x) => {
+                let work = digits + GUARD_DIGITS;
+                let root = Decimal::rounded(x.n.clone(), 0, work).sqrt_abs();
+                let a = Decimal::from_rational(&x.a, work);
+                let b = Decimal::from_rational(&x.b, work);
+                Some(a.add(&b.mul(&root)).with_digits(digits))
+            }
+            Value::Decimal(d) => Some(d.clone()),
+            _ => None,
+        }
+    }
+
+    /// Číslo s přesností zvýšenou o GUARD_DIGITS pro výpočet funkce, jejíž výsledek
+    /// má mít `digits` platných číslic
+    pub fn working(value: &Value, digits: u32) -> Option<Decimal> {
+        let work = digits + GUARD_DIGITS;
+        Some(Decimal::from_value(value, work)?.with_digits(work))
+    }
+
+    /// Přesnost, se kterou se má spočítat funkce daných parametrů: nejmenší z přesností
+    /// desetinných parametrů, u přesných parametrů přesnost z režimu desetinných čísel.
+    /// None znamená výpočet v f64 (režim je vypnutý, nebo je některý parametr reálný).
+    pub fn precision_for(params: &[&Value], mode_digits: Option<u32>) -> Option<u32> {
+        let mut digits = None;
+        for param in params {
+            match param {
+                Value::Decimal(d) => {
+                    digits = Some(digits.map_or(d.digits, |x: u32| x.min(d.digits)))
+                }
+                Value::Integer(_)
+                | Value::BigInt(_)
+                | Value::Rational(_)
+                | Value::PiMultiple(_)
+                | Value::Surd(_) => {}
+                _ => return None,
+            }
+        }
+        digits.or(mode_digits)
+    }
+
+    /// Přesná hodnota jako zlomek
+    pub fn to_rational(&self) -> Rational {
+        if self.exponent >= 0 {
+            Rational::from_bigint(&self.mantissa * pow10(self.exponent as u32))
+        } else {
+            Rational::new_bigint(self.mantissa.clone(), pow10((-self.exponent) as u32))
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        format!("{}e{}", self.mantissa, self.exponent)
+            .parse()
+            .unwrap_or(f64::NAN)
+    }
+
+    /// Počet platných číslic
+    pub fn digits(&self) -> u32 {
+        self.digits
+    }
+
+    /// Stejné číslo s jinou přesností. Při snížení přesnosti se číslo zaokrouhlí.
+    pub fn with_digits(&self, digits: u32) -> Decimal {
+        Decimal::rounded(self.mantissa.clone(), self.exponent, digits)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa.sign() == Sign::Minus
+    }
+
+    /// Je číslo celé?
+    pub fn is_integer(&self) -> bool {
+        self.exponent >= 0
+    }
+
+    pub fn abs(&self) -> Decimal {
+        Decimal {
+            mantissa: self.mantissa.abs(),
+            ..self.clone()
+        }
+    }
+
+    /// Řád čísla: nenulové číslo leží v intervalu [10^(m-1), 10^m)
+    pub fn magnitude(&self) -> i64 {
+        digit_count(&self.mantissa) + self.exponent
+    }
+
+    /// Platné číslice bez znaménka a bez nul na konci (1.2300 má číslice 123). Spolu s řádem
+    /// čísla (magnitude) určují hodnotu až na znaménko.
+    pub fn significant_digits(&self) -> String {
+        self.mantissa.magnitude().to_string()
+    }
+
+    /// Přesné uspořádání
+    pub fn compare(&self, other: &Decimal) -> Ordering {
+        let (sign_a, sign_b) = (self.mantissa.sign(), other.mantissa.sign());
+        if sign_a != sign_b || sign_a == Sign::NoSign {
+            return sign_a.cmp(&sign_b);
+        }
+        let ordering = match self.magnitude().cmp(&other.magnitude()) {
+            Ordering::Equal => {
+                let exponent = self.exponent.min(other.exponent);
+                let a = self.mantissa.abs() * pow10((self.exponent - exponent) as u32);
+                let b = other.mantissa.abs() * pow10((other.exponent - exponent) as u32);
+                a.cmp(&b)
+            }
+            ordering => ordering,
+        };
+        if sign_a == Sign::Minus {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    /// Liší se čísla nejvýše v posledních dvou platných číslicích menší z přesností?
+    pub fn nearly_equals(&self, other: &Decimal) -> bool {
+        let digits = self.digits.min(other.digits);
+        let diff = self.sub(other).with_digits(digits.max(1));
+        let scale = self.magnitude().max(other.magnitude());
+        diff.is_zero() || diff.magnitude() <= scale - i64::from(digits) + 2
+    }
+
+    pub fn add(&self, other: &Decimal) -> Decimal {
+        let digits = self.digits.min(other.digits);
+        if self.is_zero() {
+            return other.with_digits(digits);
+        } else if other.is_zero() {
+            return self.with_digits(digits);
+        }
+        // Sčítanec menší než setina poslední platné číslice výsledek nezmění
+        let (big, small) = if self.magnitude() >= other.magnitude() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        if big.magnitude() - small.magnitude() > i64::from(digits) + 2 {
+            return big.with_digits(digits);
+        }
+        let exponent = self.exponent.min(other.exponent);
+        let a = &self.mantissa * pow10((self.exponent - exponent) as u32);
+        let b = &other.mantissa * pow10((other.exponent - exponent) as u32);
+        Decimal::rounded(a + b, exponent, digits)
+    }
+
+    pub fn sub(&self, other: &Decimal) -> Decimal {
+        self.add(&-other.clone())
+    }
+
+    pub fn mul(&self, other: &Decimal) -> Decimal {
+        Decimal::rounded(
+            &self.mantissa * &other.mantissa,
+            self.exponent + other.exponent,
+            self.digits.min(other.digits),
+        )
+    }
+
+    /// Podíl. Pro dělení nulou vrátí None.
+    pub fn checked_div(&self, other: &Decimal) -> Option<Decimal> {
+        if other.is_zero() {
+            return None;
+        }
+        Some(self.quotient(other))
+    }
+
+    /// Podíl, dělitel nesmí být nula (jinak vyjde nula)
+    fn quotient(&self, other: &Decimal) -> Decimal {
+        let digits = self.digits.min(other.digits);
+        if other.is_zero() {
+            return Decimal::zero(digits);
+        }
+        let shift = (i64::from(digits) + 2 + digit_count(&other.mantissa)
+            - digit_count(&self.mantissa))
+        .max(0);
+        let mantissa = &self.mantissa * pow10(shift as u32) / &other.mantissa;
+        Decimal::rounded(mantissa, self.exponent - other.exponent - shift, digits)
+    }
+
+    pub fn mul_int(&self, i: i64) -> Decimal {
+        self.mul(&Decimal::from_int(i, self.digits))
+    }
+
+    pub fn div_int(&self, i: i64) -> Decimal {
+        self.quotient(&Decimal::from_int(i, self.digits))
+    }
+
+    /// Nejbližší celé číslo (polovina se zaokrouhluje od nuly)
+    fn round_to_integer(&self) -> Decimal {
+        if self.is_integer() {
+            return self.clone();
+        }
+        let divisor = pow10((-self.exponent) as u32);
+        let (mut quotient, remainder) = self.mantissa.div_rem(&divisor);
+        if remainder.abs() * 2 >= divisor {
+            quotient += remainder.signum();
+        }
+        Decimal::rounded(quotient, 0, self.digits)
+    }
+
+    /// Mocnina. Celočíselný exponent se počítá opakovaným násobením, jiný jako
+    /// e^(exponent · ln x), takže základ musí být kladný. Jinak vrátí None.
+    pub fn pow(&self, exponent: &Decimal) -> Option<Decimal> {
+        let digits = self.digits.min(exponent.digits);
+        let work = digits + GUARD_DIGITS;
+        // Mocnina konstanty e je přímo exp. Výpočet přes ln by ještě zesílil chybu, o kterou
+        // je e zaokrouhlené, a poslední platná číslice by mohla vyjít špatně.
+        if self.is_e() {
+            if let Some(power) = exponent.with_digits(work).exp() {
+                return Some(power.with_digits(digits));
+            }
+        }
+        if exponent.is_integer() && exponent.magnitude() <= 18 {
+            let n: i64 = exponent.to_rational().to_bigint()?.try_into().ok()?;
+            return self.pow_int(n, digits);
+        }
+        if self.is_zero() {
+            return (!exponent.is_negative()).then(|| Decimal::zero(digits));
+        }
+        if self.is_negative() {
+            return None;
+        }
+        let ln = self.with_digits(work).ln()?;
+        let power = ln.mul(&exponent.with_digits(work)).exp()?;
+        Some(power.with_digits(digits))
+    }
+
+    /// Celočíselná mocnina (binární umocňování). Každé násobení zaokrouhluje, proto se
+    /// počítá s tolika číslicemi navíc, kolik má exponent bitů.
+    fn pow_int(&self, exponent: i64, digits: u32) -> Option<Decimal> {
+        if self.is_zero() && exponent < 0 {
+            return None;
+        }
+        let work = digits + GUARD_DIGITS + 64 - exponent.unsigned_abs().leading_zeros();
+        let mut result = Decimal::one(work);
+        let mut base = self.with_digits(work);
+        let mut n = exponent.unsigned_abs();
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.mul(&base);
+            }
+            base = base.mul(&base);
+            n >>= 1;
+        }
+        if exponent < 0 {
+            result = Decimal::one(work).checked_div(&result)?;
+        }
+        Some(result.with_digits(digits))
+    }
+
+    /// Druhá odmocnina. Pro záporné číslo vrátí None.
+    pub fn sqrt(&self) -> Option<Decimal> {
+        if self.is_negative() {
+            return None;
+        }
+        Some(self.sqrt_abs())
+    }
+
+    /// Druhá odmocnina z absolutní hodnoty: mantisa se doplní nulami tak, aby měla dvakrát
+    /// víc číslic než výsledek a exponent byl sudý, a odmocní se celočíselně.
+    fn sqrt_abs(&self) -> Decimal {
+        if self.is_zero() {
+            return Decimal::zero(self.digits);
+        }
+        let mantissa = self.mantissa.abs();
+        let mut shift = (2 * (i64::from(self.digits) + 2) - digit_count(&mantissa)).max(0);
+        if (self.exponent - shift).rem_euclid(2) != 0 {
+            shift += 1;
+        }
+        let root = (mantissa * pow10(shift as u32)).sqrt();
+        Decimal::rounded(root, (self.exponent - shift) / 2, self.digits)
+    }
+
+    /// π s daným počtem platných číslic, Machinův vzorec π = 16·atan(1/5) - 4·atan(1/239)
+    pub fn pi(digits: u32) -> Decimal {
+        let work = digits + GUARD_DIGITS;
+        let pi = atan_inverse(5, work)
+            .mul_int(16)
+            .sub(&atan_inverse(239, work).mul_int(4));
+        pi.with_digits(digits)
+    }
+
+    /// e^x. Argument se vydělí 2^k, aby Taylorova řada rychle konvergovala, a součet se pak
+    /// k-krát umocní na druhou. Pro příliš velký argument vrátí None.
+    pub fn exp(&self) -> Option<Decimal> {
+        let digits = self.digits;
+        if self.is_zero() {
+            return Some(Decimal::one(digits));
+        }
+        if self.to_f64().abs() > MAX_EXP_ARG {
+            return None;
+        }
+        if self.is_negative() {
+            return Decimal::one(digits).checked_div(&(-self.clone()).exp()?);
+        }
+        Some(self.exp_positive())
+    }
+
+    /// e^x pro kladné x, které nepřesahuje MAX_EXP_ARG
+    fn exp_positive(&self) -> Decimal {
+        let digits = self.digits;
+        let halvings = (self.magnitude().max(0) * 10 / 3 + 10) as u32;
+        let work = digits + GUARD_DIGITS + halvings / 3;
+        let x =
+            self.with_digits(work)
+                .quotient(&Decimal::rounded(BigInt::one() << halvings, 0, work));
+        let mut sum = Decimal::one(work);
+        let mut term = Decimal::one(work);
+        for n in 1.. {
+            term = term.mul(&x).div_int(n);
+            if is_negligible(&term, &sum) {
+                break;
+            }
+            sum = sum.add(&term);
+        }
+        for _ in 0..halvings {
+            sum = sum.mul(&sum);
+        }
+        sum.with_digits(digits)
+    }
+
+    /// Eulerovo číslo e s daným počtem platných číslic
+    pub fn e(digits: u32) -> Decimal {
+        Decimal::one(digits + GUARD_DIGITS)
+            .exp_positive()
+            .with_digits(digits)
+    }
+
+    /// Vrací true, pokud je číslo konstanta e zaokrouhlená na svou přesnost
+    fn is_e(&self) -> bool {
+        let e = Decimal::e(self.digits);
+        self.mantissa == e.mantissa && self.exponent == e.exponent
+    }
+
+    /// Přirozený logaritmus kladného čísla, jinak None. Pro x = y·10^d, y ∈ [1, 10)
+    /// a y = z·2^k, z ∈ [0.75, 1.5] je ln x = d·ln 10 + k·ln 2 + 2·atanh((z - 1)/(z + 1)).
+    pub fn ln(&self) -> Option<Decimal> {
+        if self.is_negative() || self.is_zero() {
+            return None;
+        }
+        let digits = self.digits;
+        // Pro x blízko jedničky je výsledek malý a počítá se s více číslicemi
+        let diff = self.sub(&Decimal::one(digits));
+        if diff.is_zero() {
+            return Some(Decimal::zero(digits));
+        }
+        let decade = self.magnitude() - 1;
+        let work = digits
+            + GUARD_DIGITS
+            + (-diff.magnitude()).max(0) as u32
+            + digit_count(&BigInt::from(decade)) as u32;
+        let mut z = Decimal {
+            mantissa: self.mantissa.clone(),
+            exponent: self.exponent - decade,
+            digits: work,
+        };
+        let limit = Decimal::from_rational(&Rational::new(3, 2), work);
+        let mut halvings = 0;
+        while z.compare(&limit) == Ordering::Greater {
+            z = z.div_int(2);
+            halvings += 1;
+        }
+        let one = Decimal::one(work);
+        let t = z.sub(&one).quotient(&z.add(&one));
+        let mut result = atanh_series(&t).mul_int(2);
+        if halvings > 0 {
+            result = result.add(&ln2(work).mul_int(halvings));
+        }
+        if decade != 0 {
+            result = result.add(&ln10(work).mul_int(decade));
+        }
+        Some(result.with_digits(digits))
+    }
+
+    /// Sinus a kosinus úhlu v radiánech. Úhel se nejprve zmenší o násobek 2π do intervalu
+    /// [-π, π]. Úhel s víc než MAX_DIGITS číslicemi před desetinnou čárkou vrátí None.
+    pub fn sin_cos(&self) -> Option<(Decimal, Decimal)> {
+        let digits = self.digits;
+        if self.magnitude() > i64::from(MAX_DIGITS) {
+            return None;
+        }
+        let work = digits + GUARD_DIGITS + self.magnitude().max(0) as u32;
+        let x = self.with_digits(work);
+        let two_pi = Decimal::pi(work).mul_int(2);
+        let x = x.sub(&two_pi.mul(&x.quotient(&two_pi).round_to_integer()));
+        let x_squared = x.mul(&x);
+        let (mut sin, mut cos) = (x.clone(), Decimal::one(work));
+        let (mut sin_term, mut cos_term) = (x, Decimal::one(work));
+        for n in 1.. {
+            sin_term = -sin_term.mul(&x_squared).div_int((2 * n) * (2 * n + 1));
+            cos_term = -cos_term.mul(&x_squared).div_int((2 * n - 1) * (2 * n));
+            if is_negligible(&sin_term, &sin) && is_negligible(&cos_term, &cos) {
+                break;
+            }
+            sin = sin.add(&sin_term);
+            cos = cos.add(&cos_term);
+        }
+        Some((sin.with_digits(digits), cos.with_digits(digits)))
+    }
+
+    /// Arkus tangens v radiánech. Pro |x| > 1 se použije atan x = ±π/2 - atan(1/x).
+    pub fn atan(&self) -> Decimal {
+        let digits = self.digits;
+        let work = digits + GUARD_DIGITS;
+        let x = self.with_digits(work);
+        let one = Decimal::one(work);
+        if x.abs().compare(&one) != Ordering::Greater {
+            return atan_reduced(&x).with_digits(digits);
+        }
+        let half_pi = Decimal::pi(work).div_int(2);
+        let half_pi = if x.is_negative() { -half_pi } else { half_pi };
+        half_pi
+            .sub(&atan_reduced(&one.quotient(&x)))
+            .with_digits(digits)
+    }
+
+    /// Arkus sinus pro |x| <= 1: asin x = atan(x / √(1 - x²)), jinak None
+    pub fn asin(&self) -> Option<Decimal> {
+        let digits = self.digits;
+        let work = digits + GUARD_DIGITS;
+        let x = self.with_digits(work);
+        let cos_squared = Decimal::one(work).sub(&x.mul(&x));
+        if cos_squared.is_negative() {
+            return None;
+        }
+        if cos_squared.is_zero() {
+            let half_pi = Decimal::pi(digits).div_int(2);
+            return Some(if x.is_negative() { -half_pi } else { half_pi });
+        }
+        Some(
+            x.quotient(&cos_squared.sqrt_abs())
+                .atan()
+                .with_digits(digits),
+        )
+    }
+
+    /// Arkus kosinus pro |x| <= 1: acos x = π/2 - asin x, jinak None
+    pub fn acos(&self) -> Option<Decimal> {
+        let work = self.digits + GUARD_DIGITS;
+        let asin = self.with_digits(work).asin()?;
+        Some(
+            Decimal::pi(work)
+                .div_int(2)
+                .sub(&asin)
+                .with_digits(self.digits),
+        )
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        Decimal {
+            mantissa: -self.mantissa,
+            ..self
+        }
+    }
+}
+
+impl Display for Decimal {
+    /// Čísla s rozumným řádem se zapisují s desetinnou tečkou (0.001234, 1234.5), velmi malá
+    /// a velmi velká v semilogaritmickém tvaru (1.2345e-12)
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let sign = if self.is_negative() { "-" } else { "" };
+        let digits = self.mantissa.abs().to_string();
+        let point = self.magnitude();
+        let max_point = i64::from(self.digits).max(21);
+        if self.exponent >= 0 && point <= max_point {
+            write!(f, "{sign}{digits}{}", "0".repeat(self.exponent as usize))
+        } else if point > 0 && point <= max_point {
+            let (int_part, frac_part) = digits.split_at(point as usize);
+            write!(f, "{sign}{int_part}.{frac_part}")
+        } else if point <= 0 && point > -6 {
+            write!(f, "{sign}0.{}{digits}", "0".repeat((-point) as usize))
+        } else {
+            let (first, rest) = digits.split_at(1);
+            let rest = if rest.is_empty() {
+                String::new()
+            } else {
+                format!(".{rest}")
+            };
+            write!(f, "{sign}{first}{rest}e{}", point - 1)
+        }
+    }
+}
+
+/// Počet číslic celého čísla (bez znaménka), nula má jednu číslici
+fn digit_count(x: &BigInt) -> i64 {
+    x.magnitude().to_str_radix(10).len() as i64
+}
+
+fn pow10(exponent: u32) -> BigInt {
+    BigInt::from(10).pow(exponent)
+}
+
+/// Je člen řady tak malý, že už součet neovlivní?
+fn is_negligible(term: &Decimal, sum: &Decimal) -> bool {
+    term.is_zero() || term.magnitude() < sum.magnitude() - i64::from(sum.digits) - 1
+}
+
+/// atan(1/n) = 1/n - 1/(3n³) + 1/(5n⁵) - ...
+fn atan_inverse(n: i64, digits: u32) -> Decimal {
+    let n_squared = Decimal::from_int(n * n, digits);
+    let mut power = Decimal::one(digits).div_int(n);
+    let mut sum = power.clone();
+    for k in 1.. {
+        power = -power.quotient(&n_squared);
+        let term = power.div_int(2 * k + 1);
+        if is_negligible(&term, &sum) {
+            break;
+        }
+        sum = sum.add(&term);
+    }
+    sum
+}
+
+/// atanh t = t + t³/3 + t⁵/5 + ..., konverguje rychle pro malé |t|
+fn atanh_series(t: &Decimal) -> Decimal {
+    let t_squared = t.mul(t);
+    let mut power = t.clone();
+    let mut sum = t.clone();
+    for k in 1.. {
+        power = power.mul(&t_squared);
+        let term = power.div_int(2 * k + 1);
+        if is_negligible(&term, &sum) {
+            break;
+        }
+        sum = sum.add(&term);
+    }
+    sum
+}
+
+/// ln 2 = 2·atanh(1/3)
+fn ln2(digits: u32) -> Decimal {
+    atanh_series(&Decimal::one(digits).div_int(3)).mul_int(2)
+}
+
+/// ln 10 = 3·ln 2 + ln 1.25 = 3·ln 2 + 2·atanh(1/9)
+fn ln10(digits: u32) -> Decimal {
+    ln2(digits)
+        .mul_int(3)
+        .add(&atanh_series(&Decimal::one(digits).div_int(9)).mul_int(2))
+}
+
+/// Arkus tangens pro |x| <= 1. Argument se třikrát zmenší podle atan x = 2·atan(x / (1 + √(1 + x²))),
+/// takže |x| < 0.1 a řada x - x³/3 + x⁵/5 - ... rychle konverguje.
+fn atan_reduced(x: &Decimal) -> Decimal {
+    let one = Decimal::one(x.digits);
+    let mut x = x.clone();
+    for _ in 0..3 {
+        x = x.quotient(&one.add(&one.add(&x.mul(&x)).sqrt_abs()));
+    }
+    let x_squared = x.mul(&x);
+    let mut power = x.clone();
+    let mut sum = x;
+    for k in 1.. {
+        power = -power.mul(&x_squared);
+        let term = power.div_int(2 * k + 1);
+        if is_negligible(&term, &sum) {
+            break;
+        }
+        sum = sum.add(&term);
+    }
+    sum.mul_int(8)
+}
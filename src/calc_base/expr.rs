@@ -1,4 +1,5 @@
-use std::str::Chars;
+use crate::base::Span;
+use std::ops::Range;
 
 /// Obsahuje syntakticky platný již zkontrolovaný výraz.
 /// To ale neznamená, že v něm nemohou být chyby, např. špatné parametry funkcí. Různé
@@ -6,11 +7,16 @@ use std::str::Chars;
 #[derive(Debug)]
 pub struct Expr<'expr> {
     expr: &'expr str,
+    /// Pozice výrazu (v bajtech) v celém výrazu, ze kterého vznikl řezáním (viz slice)
+    start: usize,
 }
 
 impl<'expr> Expr<'expr> {
     pub fn new(expr: &'expr str) -> Self {
-        Expr { expr: expr.trim() }
+        Expr {
+            expr: expr.trim(),
+            start: 0,
+        }
     }
 
     pub fn as_str(&self) -> &'expr str {
@@ -21,8 +27,20 @@ impl<'expr> Expr<'expr> {
         self.expr.is_empty()
     }
 
-    pub fn chars(&self) -> Chars<'_> {
-        self.expr.chars()
+    /// Úsek celého výrazu, který tento výraz zabírá
+    pub fn span(&self) -> Span {
+        Span::new(self.start, self.start + self.expr.len())
+    }
+
+    /// Podvýraz z daného rozsahu bajtů tohoto výrazu, oříznutý o bílé znaky. Pamatuje si,
+    /// kde v celém výrazu leží, aby se chyba v něm dala ukázat na správném místě.
+    pub fn slice(&self, range: Range<usize>) -> Expr<'expr> {
+        let text = &self.expr[range.clone()];
+        let trimmed = text.trim_start();
+        Expr {
+            expr: trimmed.trim_end(),
+            start: self.start + range.start + (text.len() - trimmed.len()),
+        }
     }
 }
 
@@ -34,6 +52,9 @@ impl<'expr> Default for Expr<'expr> {
 
 impl<'expr> Clone for Expr<'expr> {
     fn clone(&self) -> Self {
-        Self::new(self.expr)
+        Expr {
+            expr: self.expr,
+            start: self.start,
+        }
     }
 }
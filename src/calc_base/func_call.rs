@@ -1,5 +1,5 @@
 use crate::base::CalcError;
-use crate::calc_base::std_funcs;
+use crate::calc_base::context::CalcContext;
 use crate::calc_base::value::Value;
 
 #[derive(Debug, Clone)]
@@ -16,136 +16,18 @@ impl FuncCall {
         }
     }
 
-    // pub fn name(&self) -> &str {
-    //     self.name.as_str()
-    // }
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
 
-    /// Najde funkci s daným názvem a zavolá ji. Pokud funkce neexistuje, nebo se
-    /// nepovede výpočet, vrátí chybu.
-    pub fn eval(&self) -> Result<Value, CalcError> {
-        match self.name.as_str() {
-            "ln" => {
-                if self.params.len() == 1 {
-                    std_funcs::ln(self.params[0].clone().simplify_type_move()?)
-                } else {
-                    Err(CalcError::FuncCallErr(format!(
-                        "Funkce '{}' vyžaduje 1 parametr",
-                        self.name
-                    )))
-                }
-            }
-            "abs" => {
-                if self.params.len() == 1 {
-                    std_funcs::abs(self.params[0].clone().simplify_type_move()?)
-                } else {
-                    Err(CalcError::FuncCallErr(format!(
-                        "Funkce '{}' vyžaduje 1 parametr",
-                        self.name
-                    )))
-                }
-            }
-            "comb" => {
-                if self.params.len() == 3 {
-                    std_funcs::comb(
-                        self.params[0].clone().simplify_type_move()?,
-                        self.params[1].clone().simplify_type_move()?,
-                        self.params[2].clone(),
-                    )
-                } else {
-                    Err(CalcError::FuncCallErr(format!(
-                        "Funkce '{}' vyžaduje 3 parametry (int, int, bool)",
-                        self.name
-                    )))
-                }
-            }
-            "nck" => {
-                if self.params.len() == 2 {
-                    std_funcs::nck(
-                        self.params[0].clone().simplify_type_move()?,
-                        self.params[1].clone().simplify_type_move()?,
-                    )
-                } else {
-                    Err(CalcError::FuncCallErr(format!(
-                        "Funkce '{}' vyžaduje 2 parametry (int, int)",
-                        self.name
-                    )))
-                }
-            }
-            "fact" => {
-                if self.params.len() == 1 {
-                    std_funcs::fact(self.params[0].clone().simplify_type_move()?)
-                } else {
-                    Err(CalcError::FuncCallErr(format!(
-                        "Funkce '{}' vyžaduje 1 parametr",
-                        self.name
-                    )))
-                }
-            }
-            "max" => {
-                if self.params.len() == 0 {
-                    Err(CalcError::FuncCallErr(format!(
-                        "Funkce '{}' vyžaduje aspoň 1 parametr",
-                        self.name
-                    )))
-                } else {
-                    std_funcs::max(&self.params)
-                }
-            }
-            "sqrt" => {
-                if self.params.len() == 1 {
-                    std_funcs::sqrt(self.params[0].clone().simplify_type_move()?)
-                } else {
-                    Err(CalcError::FuncCallErr(format!(
-                        "Funkce '{}' vyžaduje 1 parametr",
-                        self.name
-                    )))
-                }
-            }
-            "sin" => {
-                if self.params.len() == 1 {
-                    std_funcs::sin(self.params[0].clone().simplify_type_move()?)
-                } else {
-                    Err(CalcError::FuncCallErr(format!(
-                        "Funkce '{}' vyžaduje 1 parametr",
-                        self.name
-                    )))
-                }
-            }
-            "sind" => {
-                if self.params.len() == 1 {
-                    std_funcs::sind(self.params[0].clone().simplify_type_move()?)
-                } else {
-                    Err(CalcError::FuncCallErr(format!(
-                        "Funkce '{}' vyžaduje 1 parametr",
-                        self.name
-                    )))
-                }
-            }
-            "sinpi" => {
-                if self.params.len() == 1 {
-                    std_funcs::sinpi(self.params[0].clone().simplify_type_move()?)
-                } else {
-                    Err(CalcError::FuncCallErr(format!(
-                        "Funkce '{}' vyžaduje 1 parametr",
-                        self.name
-                    )))
-                }
-            }
-            "cista" => {
-                if self.params.len() == 1 {
-                    std_funcs::cista_mzda(self.params[0].clone())
-                } else {
-                    Err(CalcError::FuncCallErr(format!(
-                        "Funkce '{}' vyžaduje 1 parametr",
-                        self.name
-                    )))
-                }
-            }
-            _ => Err(CalcError::FuncCallErr(format!(
-                "Funkce '{}' není definována",
-                self.name
-            ))),
-        }
+    pub fn into_params(self) -> Vec<Value> {
+        self.params
+    }
+
+    /// Najde funkci s daným názvem v registru kontextu a zavolá ji s nastavením kontextu.
+    /// Pokud funkce neexistuje, nebo se nepovede výpočet, vrátí chybu.
+    pub fn eval(self, ctx: &CalcContext) -> Result<Value, CalcError> {
+        ctx.registry().call(&self.name, self.params, ctx.settings())
     }
 
     // pub fn params_as_string(&self) -> String {
@@ -0,0 +1,552 @@
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::lazy_funcs::is_lazy_func;
+use crate::calc_base::lexer::is_ident;
+use crate::calc_base::number_format::{self, NumberFormat};
+use crate::calc_base::settings::CalcSettings;
+use crate::calc_base::std_funcs;
+use crate::calc_base::trig_funcs;
+use crate::calc_base::value::{is_named_const, Value};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Implementace funkce. Dostane parametry, u kterých už je ověřený počet i typy
+/// (viz FuncDescriptor::call), a hodnoty jsou zjednodušené na co nejjednodušší typ.
+pub type FuncImpl = fn(&[Value]) -> Result<Value, CalcError>;
+
+/// Implementace funkce, jejíž výsledek závisí na nastavení kontextu (např. sin na jednotce
+/// úhlu). Kromě parametrů dostane i aktuální nastavení.
+pub type SettingsFuncImpl = fn(&[Value], &CalcSettings) -> Result<Value, CalcError>;
+
+/// Implementace funkce s nastavením, nebo bez něj
+#[derive(Debug, Clone, Copy)]
+enum Implementation {
+    Plain(FuncImpl),
+    WithSettings(SettingsFuncImpl),
+}
+
+/// Kolik parametrů funkce přijímá
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    /// Přesně daný počet parametrů
+    Exact(usize),
+    /// Proměnný počet parametrů, ale aspoň daný počet (např. max(a, b, c...))
+    AtLeast(usize),
+    /// Počet parametrů v rozsahu od-do, parametry navíc jsou nepovinné (např. sci(x, číslice))
+    Range(usize, usize),
+}
+
+impl Arity {
+    /// Vrací true, pokud funkce přijme daný počet parametrů
+    pub fn accepts(&self, count: usize) -> bool {
+        match *self {
+            Arity::Exact(exact) => count == exact,
+            Arity::AtLeast(min) => count >= min,
+            Arity::Range(min, max) => (min..=max).contains(&count),
+        }
+    }
+}
+
+/// Očekávaný typ parametru funkce. Kontroluje se před zavoláním implementace, takže
+/// implementace nemusí opakovat stejné kontroly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamType {
+    Any,
+    /// Integer, BigInt, Rational, Real, Decimal, PiMultiple nebo Surd
+    Number,
+    /// Number nebo Complex
+    Complex,
+    /// Integer nebo BigInt
+    Integer,
+    Bool,
+    Text,
+}
+
+impl ParamType {
+    pub fn accepts(&self, value: &Value) -> bool {
+        match self {
+            ParamType::Any => true,
+            ParamType::Number => matches!(
+                value,
+                Value::Integer(_)
+                    | Value::BigInt(_)
+                    | Value::Rational(_)
+                    | Value::Real(_)
+                    | Value::Decimal(_)
+                    | Value::PiMultiple(_)
+                    | Value::Surd(_)
+            ),
+            ParamType::Complex => {
+                ParamType::Number.accepts(value) || matches!(value, Value::Complex(_))
+            }
+            ParamType::Integer => matches!(value, Value::Integer(_) | Value::BigInt(_)),
+            ParamType::Bool => matches!(value, Value::Bool(_)),
+            ParamType::Text => matches!(value, Value::Text(_)),
+        }
+    }
+}
+
+impl Display for ParamType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamType::Any => write!(f, "hodnota"),
+            ParamType::Number => write!(f, "číslo"),
+            ParamType::Complex => write!(f, "komplexní číslo"),
+            ParamType::Integer => write!(f, "int"),
+            ParamType::Bool => write!(f, "bool"),
+            ParamType::Text => write!(f, "text"),
+        }
+    }
+}
+
+/// Popis vestavěné funkce: název, počet a typy parametrů, nápověda a implementace.
+#[derive(Debug, Clone)]
+pub struct FuncDescriptor {
+    name: String,
+    arity: Arity,
+    /// Typy parametrů. U funkce s proměnným počtem parametrů platí poslední typ i pro
+    /// všechny další parametry. Chybějící typy znamenají ParamType::Any.
+    param_types: Vec<ParamType>,
+    help: String,
+    implementation: Implementation,
+}
+
+impl FuncDescriptor {
+    pub fn new(name: &str, arity: Arity, implementation: FuncImpl) -> FuncDescriptor {
+        FuncDescriptor::with_implementation(name, arity, Implementation::Plain(implementation))
+    }
+
+    /// Funkce, která při volání dostane i nastavení kontextu
+    pub fn new_with_settings(
+        name: &str,
+        arity: Arity,
+        implementation: SettingsFuncImpl,
+    ) -> FuncDescriptor {
+        FuncDescriptor::with_implementation(
+            name,
+            arity,
+            Implementation::WithSettings(implementation),
+        )
+    }
+
+    fn with_implementation(
+        name: &str,
+        arity: Arity,
+        implementation: Implementation,
+    ) -> FuncDescriptor {
+        FuncDescriptor {
+            name: name.trim().to_lowercase(),
+            arity,
+            param_types: vec![],
+            help: String::new(),
+            implementation,
+        }
+    }
+
+    pub fn with_params(mut self, param_types: &[ParamType]) -> FuncDescriptor {
+        self.param_types = param_types.to_vec();
+        self
+    }
+
+    pub fn with_help(mut self, help: &str) -> FuncDescriptor {
+        self.help = help.to_string();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn arity(&self) -> Arity {
+        self.arity
+    }
+
+    pub fn help(&self) -> &str {
+        self.help.as_str()
+    }
+
+    /// Očekávaný typ parametru na dané pozici
+    pub fn param_type(&self, idx: usize) -> ParamType {
+        match (self.param_types.get(idx), self.arity) {
+            (Some(param_type), _) => *param_type,
+            (None, Arity::AtLeast(_)) => self.param_types.last().copied().unwrap_or(ParamType::Any),
+            (None, Arity::Exact(_) | Arity::Range(..)) => ParamType::Any,
+        }
+    }
+
+    /// Zápis funkce pro nápovědu, např. comb(int, int, bool), max(číslo, ...) nebo
+    /// sci(číslo, [int]) s nepovinnými parametry v hranatých závorkách
+    pub fn signature(&self) -> String {
+        let (required, optional, variadic) = match self.arity {
+            Arity::Exact(count) => (count, count, false),
+            Arity::AtLeast(count) => (count.max(1), count.max(1), true),
+            Arity::Range(min, max) => (min, max, false),
+        };
+        let mut params: Vec<_> = (0..optional)
+            .map(|idx| {
+                if idx < required {
+                    self.param_type(idx).to_string()
+                } else {
+                    format!("[{}]", self.param_type(idx))
+                }
+            })
+            .collect();
+        if variadic {
+            params.push(String::from("..."));
+        }
+        format!("{}({})", self.name, params.join(", "))
+    }
+
+    /// Zkontroluje počet a typy parametrů a zavolá implementaci funkce
+    pub fn call(&self, params: Vec<Value>, settings: &CalcSettings) -> Result<Value, CalcError> {
+        if !self.arity.accepts(params.len()) {
+            return Err(CalcError::FuncCallErr(ErrorKind::ArityMismatch {
+                name: self.name.clone(),
+                expected: self.arity,
+                got: params.len(),
+            }));
+        }
+
+        let params = params
+            .into_iter()
+            .map(Value::simplify_type_move)
+            .collect::<Result<Vec<_>, _>>()?;
+        for (idx, param) in params.iter().enumerate() {
+            let expected = self.param_type(idx);
+            if !expected.accepts(param) {
+                return Err(CalcError::FuncCallErr(ErrorKind::ParamTypeMismatch {
+                    name: self.name.clone(),
+                    position: idx + 1,
+                    expected,
+                    got_type: param.type_name(),
+                }));
+            }
+        }
+        match self.implementation {
+            Implementation::Plain(implementation) => implementation(&params),
+            Implementation::WithSettings(implementation) => implementation(&params, settings),
+        }
+    }
+}
+
+/// Goniometrické, cyklometrické a hyperbolické funkce. Funkce s úhlem mají tři varianty:
+/// v jednotce z nastavení (sin, výchozí jsou radiány), ve stupních (sind) a v násobcích π (sinpi).
+fn trig_funcs() -> Vec<FuncDescriptor> {
+    use trig_funcs::{AngleUnit::*, Circular::*, Hyperbolic::*, InverseCircular::*};
+
+    macro_rules! circular {
+        ($($func:ident $name:literal $help:literal),* $(,)?) => {
+            vec![$(
+                FuncDescriptor::new_with_settings($name, Arity::Exact(1), |p, settings| {
+                    trig_funcs::circular($func, p[0].clone(), settings.angle_unit, settings.decimal_digits)
+                })
+                .with_params(&[ParamType::Complex])
+                .with_help(concat!($help, " úhlu v nastavené jednotce")),
+                FuncDescriptor::new_with_settings(concat!($name, "d"), Arity::Exact(1), |p, settings| {
+                    trig_funcs::circular($func, p[0].clone(), Deg, settings.decimal_digits)
+                })
+                .with_params(&[ParamType::Number])
+                .with_help(concat!($help, " úhlu ve stupních")),
+                FuncDescriptor::new_with_settings(concat!($name, "pi"), Arity::Exact(1), |p, settings| {
+                    trig_funcs::circular($func, p[0].clone(), PiFraction, settings.decimal_digits)
+                })
+                .with_params(&[ParamType::Number])
+                .with_help(concat!($help, " úhlu x·π radiánů")),
+            )*]
+        };
+    }
+    macro_rules! inverse_circular {
+        ($($func:ident $name:literal $help:literal),* $(,)?) => {
+            vec![$(
+                FuncDescriptor::new_with_settings($name, Arity::Exact(1), |p, settings| {
+                    trig_funcs::inverse_circular($func, p[0].clone(), settings.angle_unit, settings.decimal_digits)
+                })
+                .with_params(&[ParamType::Number])
+                .with_help(concat!($help, " v nastavené jednotce")),
+                FuncDescriptor::new_with_settings(concat!($name, "d"), Arity::Exact(1), |p, settings| {
+                    trig_funcs::inverse_circular($func, p[0].clone(), Deg, settings.decimal_digits)
+                })
+                .with_params(&[ParamType::Number])
+                .with_help(concat!($help, " ve stupních")),
+                FuncDescriptor::new_with_settings(concat!($name, "pi"), Arity::Exact(1), |p, settings| {
+                    trig_funcs::inverse_circular($func, p[0].clone(), PiFraction, settings.decimal_digits)
+                })
+                .with_params(&[ParamType::Number])
+                .with_help(concat!($help, " v násobcích π")),
+            )*]
+        };
+    }
+    macro_rules! hyperbolic {
+        ($($func:ident $name:literal $help:literal),* $(,)?) => {
+            vec![$(
+                FuncDescriptor::new_with_settings($name, Arity::Exact(1), |p, settings| {
+                    trig_funcs::hyperbolic($func, p[0].clone(), settings.decimal_digits)
+                })
+                .with_params(&[ParamType::Number])
+                .with_help($help),
+            )*]
+        };
+    }
+
+    let atan2 = [
+        FuncDescriptor::new_with_settings("atan2", Arity::Exact(2), |p, settings| {
+            trig_funcs::atan2(
+                p[0].clone(),
+                p[1].clone(),
+                settings.angle_unit,
+                settings.decimal_digits,
+            )
+        })
+        .with_help("Úhel bodu [x, y] v nastavené jednotce: atan2(y, x)"),
+        FuncDescriptor::new_with_settings("atan2d", Arity::Exact(2), |p, settings| {
+            trig_funcs::atan2(p[0].clone(), p[1].clone(), Deg, settings.decimal_digits)
+        })
+        .with_help("Úhel bodu [x, y] ve stupních: atan2d(y, x)"),
+        FuncDescriptor::new_with_settings("atan2pi", Arity::Exact(2), |p, settings| {
+            trig_funcs::atan2(
+                p[0].clone(),
+                p[1].clone(),
+                PiFraction,
+                settings.decimal_digits,
+            )
+        })
+        .with_help("Úhel bodu [x, y] v násobcích π: atan2pi(y, x)"),
+    ]
+    .map(|func| func.with_params(&[ParamType::Number, ParamType::Number]));
+
+    let mut funcs = circular![
+        Sin "sin" "Sinus",
+        Cos "cos" "Kosinus",
+        Tan "tan" "Tangens",
+        Cot "cot" "Kotangens",
+        Sec "sec" "Sekans",
+        Csc "csc" "Kosekans",
+    ];
+    funcs.extend(inverse_circular![
+        Asin "asin" "Arkus sinus",
+        Acos "acos" "Arkus kosinus",
+        Atan "atan" "Arkus tangens",
+        Acot "acot" "Arkus kotangens",
+        Asec "asec" "Arkus sekans",
+        Acsc "acsc" "Arkus kosekans",
+    ]);
+    funcs.extend(atan2);
+    funcs.extend(hyperbolic![
+        Sinh "sinh" "Hyperbolický sinus",
+        Cosh "cosh" "Hyperbolický kosinus",
+        Tanh "tanh" "Hyperbolický tangens",
+        Coth "coth" "Hyperbolický kotangens",
+        Sech "sech" "Hyperbolický sekans",
+        Csch "csch" "Hyperbolický kosekans",
+        Asinh "asinh" "Argument hyperbolického sinu",
+        Acosh "acosh" "Argument hyperbolického kosinu",
+        Atanh "atanh" "Argument hyperbolického tangentu",
+        Acoth "acoth" "Argument hyperbolického kotangentu",
+        Asech "asech" "Argument hyperbolického sekantu",
+        Acsch "acsch" "Argument hyperbolického kosekantu",
+    ]);
+    funcs
+}
+
+/// Funkce, které vypíší číslo v daném formátu jako text (viz NumberFormat)
+fn format_funcs() -> Vec<FuncDescriptor> {
+    use Arity::*;
+    use ParamType::*;
+
+    vec![
+        FuncDescriptor::new_with_settings("hex", Exact(1), |p, settings| {
+            number_format::format_func(NumberFormat::Hex, &p[0], settings)
+        })
+        .with_params(&[Integer])
+        .with_help("Celé číslo v šestnáctkové soustavě: hex(255) = \"0xFF\""),
+        FuncDescriptor::new_with_settings("bin", Exact(1), |p, settings| {
+            number_format::format_func(NumberFormat::Bin, &p[0], settings)
+        })
+        .with_params(&[Integer])
+        .with_help("Celé číslo ve dvojkové soustavě: bin(10) = \"0b1010\""),
+        FuncDescriptor::new_with_settings("oct", Exact(1), |p, settings| {
+            number_format::format_func(NumberFormat::Oct, &p[0], settings)
+        })
+        .with_params(&[Integer])
+        .with_help("Celé číslo v osmičkové soustavě: oct(15) = \"0o17\""),
+        FuncDescriptor::new_with_settings("mixed", Exact(1), |p, settings| {
+            number_format::format_func(NumberFormat::Mixed, &p[0], settings)
+        })
+        .with_params(&[Number])
+        .with_help("Smíšené číslo: mixed(5/3) = \"1 2/3\""),
+        FuncDescriptor::new_with_settings("repeating", Exact(1), |p, settings| {
+            number_format::format_func(NumberFormat::Repeating, &p[0], settings)
+        })
+        .with_params(&[Number])
+        .with_help("Desetinný rozvoj s periodou: repeating(1/7) = \"0.(142857)\""),
+        FuncDescriptor::new_with_settings("fixed", Exact(2), |p, settings| {
+            let places = number_format::required_digits("fixed", &p[1])?;
+            number_format::format_func(NumberFormat::Fixed(places), &p[0], settings)
+        })
+        .with_params(&[Number, Integer])
+        .with_help("Pevný počet desetinných míst: fixed(2/3, 2) = \"0.67\""),
+        FuncDescriptor::new_with_settings("sig", Exact(2), |p, settings| {
+            let digits = number_format::required_digits("sig", &p[1])?;
+            number_format::format_func(NumberFormat::Significant(digits), &p[0], settings)
+        })
+        .with_params(&[Number, Integer])
+        .with_help("Počet platných číslic: sig(2/3, 2) = \"0.67\""),
+        FuncDescriptor::new_with_settings("sci", Range(1, 2), |p, settings| {
+            let digits = number_format::optional_digits("sci", p)?;
+            number_format::format_func(NumberFormat::Scientific(digits), &p[0], settings)
+        })
+        .with_params(&[Number, Integer])
+        .with_help("Semilogaritmický tvar: sci(x), sci(x, číslice)"),
+        FuncDescriptor::new_with_settings("eng", Range(1, 2), |p, settings| {
+            let digits = number_format::optional_digits("eng", p)?;
+            number_format::format_func(NumberFormat::Engineering(digits), &p[0], settings)
+        })
+        .with_params(&[Number, Integer])
+        .with_help("Inženýrský tvar s exponentem dělitelným 3: eng(x), eng(x, číslice)"),
+        FuncDescriptor::new_with_settings("group", Exact(1), number_format::group)
+            .with_params(&[Number])
+            .with_help("Číslo s oddělenými tisíci: group(1234567) = \"1 234 567\""),
+    ]
+}
+
+/// Seznam vestavěných funkcí, které lze volat z výrazu. Funkce se hledají podle názvu,
+/// který nerozlišuje velikost písmen. Další funkce lze přidat metodou register.
+#[derive(Debug, Clone)]
+pub struct FunctionRegistry {
+    funcs: HashMap<String, FuncDescriptor>,
+}
+
+impl Default for FunctionRegistry {
+    /// Registr se standardními funkcemi kalkulátoru
+    fn default() -> Self {
+        FunctionRegistry::with_std_funcs()
+    }
+}
+
+impl FunctionRegistry {
+    /// Prázdný registr bez jediné funkce
+    pub fn new() -> FunctionRegistry {
+        FunctionRegistry {
+            funcs: HashMap::new(),
+        }
+    }
+
+    /// Registr naplněný standardními funkcemi (ln, abs, sqrt, sin...)
+    pub fn with_std_funcs() -> FunctionRegistry {
+        use Arity::*;
+        use ParamType::*;
+
+        let mut registry = FunctionRegistry::new();
+        let funcs = [
+            FuncDescriptor::new_with_settings("ln", Exact(1), |p, settings| {
+                std_funcs::ln(p[0].clone(), settings.decimal_digits)
+            })
+            .with_params(&[Complex])
+            .with_help("Přirozený logaritmus"),
+            FuncDescriptor::new("abs", Exact(1), |p| std_funcs::abs(p[0].clone()))
+                .with_params(&[Complex])
+                .with_help("Absolutní hodnota"),
+            FuncDescriptor::new("comb", Exact(3), |p| {
+                std_funcs::comb(p[0].clone(), p[1].clone(), p[2].clone())
+            })
+            .with_params(&[Integer, Integer, Bool])
+            .with_help("Počet kombinací k prvků z n, s opakováním nebo bez"),
+            FuncDescriptor::new("nck", Exact(2), |p| {
+                std_funcs::nck(p[0].clone(), p[1].clone())
+            })
+            .with_params(&[Integer, Integer])
+            .with_help("Kombinační číslo n nad k"),
+            FuncDescriptor::new("fact", Exact(1), |p| std_funcs::fact(p[0].clone()))
+                .with_params(&[Integer])
+                .with_help("Faktoriál nezáporného celého čísla"),
+            FuncDescriptor::new("max", AtLeast(1), std_funcs::max)
+                .with_params(&[Number])
+                .with_help("Největší z parametrů"),
+            FuncDescriptor::new("min", AtLeast(1), std_funcs::min)
+                .with_params(&[Number])
+                .with_help("Nejmenší z parametrů"),
+            FuncDescriptor::new("argmax", AtLeast(1), std_funcs::argmax)
+                .with_params(&[Number])
+                .with_help("Pořadí (od 1) největšího z parametrů"),
+            FuncDescriptor::new("argmin", AtLeast(1), std_funcs::argmin)
+                .with_params(&[Number])
+                .with_help("Pořadí (od 1) nejmenšího z parametrů"),
+            FuncDescriptor::new("clamp", Exact(3), |p| {
+                std_funcs::clamp(p[0].clone(), p[1].clone(), p[2].clone())
+            })
+            .with_params(&[Number, Number, Number])
+            .with_help("Omezí x na interval [low, high]: clamp(x, low, high)"),
+            FuncDescriptor::new("sort", AtLeast(1), std_funcs::sort)
+                .with_params(&[Number])
+                .with_help("Seřadí parametry vzestupně"),
+            FuncDescriptor::new_with_settings("sqrt", Exact(1), |p, settings| {
+                std_funcs::sqrt(p[0].clone(), settings.decimal_digits)
+            })
+            .with_params(&[Complex])
+            .with_help("Druhá odmocnina"),
+            FuncDescriptor::new_with_settings("dec", Range(1, 2), std_funcs::dec)
+                .with_params(&[Number, Integer])
+                .with_help("Desetinné číslo s danou přesností: dec(x), dec(x, číslice)"),
+            FuncDescriptor::new("cista", Exact(1), |p| std_funcs::cista_mzda(p[0].clone()))
+                .with_params(&[Number])
+                .with_help("Čistá měsíční mzda z hrubé mzdy"),
+        ];
+        for func in funcs.into_iter().chain(trig_funcs()).chain(format_funcs()) {
+            registry.funcs.insert(func.name.clone(), func);
+        }
+        registry
+    }
+
+    /// Přidá funkci do registru. Funkce se stejným názvem se nahradí, takže lze upravit
+    /// i chování standardní funkce. Název musí být platný identifikátor a nesmí kolidovat
+    /// s konstantou.
+    pub fn register(&mut self, func: FuncDescriptor) -> Result<(), CalcError> {
+        if !is_ident(&func.name) {
+            return Err(CalcError::FuncCallErr(ErrorKind::InvalidFuncName {
+                name: func.name,
+            }));
+        }
+        if is_lazy_func(&func.name) {
+            return Err(CalcError::EvaluateErr(ErrorKind::BuiltinFunc {
+                name: func.name,
+            }));
+        }
+        if is_named_const(&func.name).is_some() || func.name.parse::<bool>().is_ok() {
+            return Err(CalcError::EvaluateErr(ErrorKind::ConstantFuncName {
+                name: func.name,
+            }));
+        }
+        self.funcs.insert(func.name.clone(), func);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&FuncDescriptor> {
+        self.funcs.get(&name.trim().to_lowercase())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Všechny funkce v registru seřazené podle názvu
+    pub fn funcs(&self) -> Vec<&FuncDescriptor> {
+        let mut funcs: Vec<_> = self.funcs.values().collect();
+        funcs.sort_by(|a, b| a.name.cmp(&b.name));
+        funcs
+    }
+
+    /// Najde funkci s daným názvem a zavolá ji. Pokud funkce neexistuje, nebo se
+    /// nepovede výpočet, vrátí chybu.
+    pub fn call(
+        &self,
+        name: &str,
+        params: Vec<Value>,
+        settings: &CalcSettings,
+    ) -> Result<Value, CalcError> {
+        match self.get(name) {
+            Some(func) => func.call(params, settings),
+            None => Err(CalcError::FuncCallErr(ErrorKind::UnknownFunction {
+                name: name.trim().to_lowercase(),
+            })),
+        }
+    }
+}
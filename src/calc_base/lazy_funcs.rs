@@ -0,0 +1,39 @@
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::func_registry::Arity;
+use crate::calc_base::value::Value;
+use crate::s;
+
+/// Funkce, jejichž parametry se nevyhodnocují předem, ale až podle potřeby. Proto je nelze
+/// zaregistrovat do FunctionRegistry, strategie je musí volat přes call_lazy_func.
+const LAZY_FUNCS: &[&str] = &["if"];
+
+/// Vrací true, pokud se parametry funkce s tímto názvem vyhodnocují líně
+pub fn is_lazy_func(name: &str) -> bool {
+    LAZY_FUNCS.contains(&name.trim().to_lowercase().as_str())
+}
+
+/// Zavolá funkci s líně vyhodnocovanými parametry. Parametr s daným indexem vyhodnotí
+/// funkce eval_param, kterou dodá strategie. Parametry, které výsledek neovlivní, se
+/// nevyhodnotí vůbec, takže v nich nevadí ani chyba (if(x != 0, 1/x, 0)).
+pub fn call_lazy_func(
+    name: &str,
+    param_count: usize,
+    mut eval_param: impl FnMut(usize) -> Result<Value, CalcError>,
+) -> Result<Value, CalcError> {
+    match name.trim().to_lowercase().as_str() {
+        "if" => {
+            if param_count != 3 {
+                return Err(CalcError::FuncCallErr(ErrorKind::ArityMismatch {
+                    name: s!("if"),
+                    expected: Arity::Exact(3),
+                    got: param_count,
+                }));
+            }
+            let condition = eval_param(0)?.as_bool("if")?;
+            eval_param(if condition { 1 } else { 2 })
+        }
+        _ => Err(CalcError::FuncCallErr(ErrorKind::UnknownFunction {
+            name: s!(name),
+        })),
+    }
+}
@@ -0,0 +1,257 @@
+use crate::base::{CalcError, ErrorKind, Span};
+use crate::calc_base::locale::Locale;
+use std::fmt::Display;
+
+/// Symboly, které lexer považuje za operátory. Jejich priority určují strategie.
+/// Víceznakové operátory musí být před svými prefixy (<= před <), lexer bere první shodu.
+pub const OPERATORS: &[&str] = &[
+    "==", "!=", "<=", ">=", "<", ">", "!", "+", "-", "*", "/", "^",
+];
+
+/// Slova, která jsou operátory, ne identifikátory. Nerozlišují velikost písmen.
+pub const KEYWORD_OPERATORS: &[&str] = &["and", "or", "xor", "not"];
+
+/// Druh lexikální jednotky výrazu
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// Číselný literál (viz number_len), případně i s příponou jednotky úhlu (30deg, 0.5rad,
+    /// 100grad). Může být i neplatný (0x, 2pi), chybu nahlásí až jeho načtení. Desetinný
+    /// oddělovač je podle zápisu výrazu tečka, nebo čárka (viz Value::parse_number).
+    Number(String),
+    /// Název funkce, konstanty apod. Začíná písmenem.
+    Ident(String),
+    /// Obsah textového literálu bez uvozovek. Escape sekvence \" a \\ jsou už rozvinuté.
+    Text(String),
+    Oper(&'static str),
+    LeftBracket,
+    RightBracket,
+    /// Oddělovač parametrů funkce: čárka, nebo středník v českém zápisu (viz Locale)
+    Comma,
+    /// Přiřazení do proměnné, např. x = 3/7
+    Assign,
+}
+
+/// Token a jeho pozice ve vstupním textu
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+impl Display for TokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenKind::Number(n) => write!(f, "{n}"),
+            TokenKind::Ident(i) => write!(f, "{i}"),
+            TokenKind::Text(t) => write!(f, "\"{t}\""),
+            TokenKind::Oper(o) => write!(f, "{o}"),
+            TokenKind::LeftBracket => write!(f, "("),
+            TokenKind::RightBracket => write!(f, ")"),
+            TokenKind::Comma => write!(f, ","),
+            TokenKind::Assign => write!(f, "="),
+        }
+    }
+}
+
+/// Převede text výrazu na posloupnost tokenů. Zde je jediné místo, kde se definuje,
+/// co je string (včetně escapovaných uvozovek), závorka, operátor nebo oddělovač parametrů.
+/// Všechny strategie i validace výrazu by měly pracovat s tokeny, ne se znaky.
+/// Desetinný oddělovač a oddělovač parametrů se řídí zápisem locale.
+pub fn tokenize(expr: &str, locale: Locale) -> Result<Vec<Token>, CalcError> {
+    let mut tokens = vec![];
+    let mut chars = expr.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if let Some(oper) = operator_at(&expr[start..]) {
+            // První znak už je načtený, zbytek operátoru se přeskočí (operátory jsou ASCII)
+            for _ in 1..oper.len() {
+                chars.next();
+            }
+            tokens.push(Token {
+                kind: TokenKind::Oper(oper),
+                span: Span::new(start, start + oper.len()),
+            });
+            continue;
+        }
+        let kind = match c {
+            _ if c.is_whitespace() => continue,
+            '(' => TokenKind::LeftBracket,
+            ')' => TokenKind::RightBracket,
+            _ if c == locale.param_separator() => TokenKind::Comma,
+            '=' => TokenKind::Assign,
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, escaped @ ('"' | '\\'))) => text.push(escaped),
+                            Some((_, other)) => {
+                                text.push('\\');
+                                text.push(other);
+                            }
+                            None => return Err(missing_quote_err(start, expr.len())),
+                        },
+                        Some((_, c)) => text.push(c),
+                        None => return Err(missing_quote_err(start, expr.len())),
+                    }
+                }
+                TokenKind::Text(text)
+            }
+            _ if c.is_ascii_digit() || c == locale.decimal_separator() => {
+                let separator = locale.decimal_separator() as u8;
+                let mut end = start + number_len(&expr[start..], separator);
+                let suffix: String = expr[end..]
+                    .chars()
+                    .take_while(|c| is_ident_char(*c))
+                    .collect();
+                // Písmena a číslice hned za číslem k němu patří: jednotka úhlu (30deg), nebo
+                // zbytek neplatného literálu (0b102, 0x, 1e), který se pak nahlásí celý.
+                // Výjimkou jsou slovní operátory (1and 0).
+                if keyword_operator(&suffix).is_none() {
+                    end += suffix.len();
+                }
+                while chars.peek().is_some_and(|(pos, _)| *pos < end) {
+                    chars.next();
+                }
+                TokenKind::Number(expr[start..end].to_string())
+            }
+            _ if c.is_alphabetic() => {
+                let mut ident = String::from(c);
+                while let Some(&(_, next)) = chars.peek() {
+                    if is_ident_char(next) {
+                        ident.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                match keyword_operator(&ident) {
+                    Some(keyword) => TokenKind::Oper(keyword),
+                    None => TokenKind::Ident(ident),
+                }
+            }
+            _ => {
+                return Err(CalcError::ParseErr(ErrorKind::UnexpectedChar { ch: c })
+                    .with_span(Span::new(start, start + c.len_utf8())))
+            }
+        };
+        let end = chars.peek().map_or(expr.len(), |(pos, _)| *pos);
+        tokens.push(Token {
+            kind,
+            span: Span::new(start, end),
+        });
+    }
+    Ok(tokens)
+}
+
+/// Délka číselného literálu na začátku textu v bajtech. Literál je celé číslo s předponou
+/// soustavy (0xFF, 0b1010, 0o17), nebo číslo s desetinným oddělovačem a exponentem (1.5e-3).
+/// Číslice lze oddělovat podtržítkem (1_000_000) a celou část i mezerou před každou
+/// trojicí číslic (1 000 000).
+fn number_len(text: &str, decimal_separator: u8) -> usize {
+    let bytes = text.as_bytes();
+    let radix_digit: Option<fn(&u8) -> bool> = match bytes {
+        [b'0', b'x' | b'X', ..] => Some(u8::is_ascii_hexdigit),
+        [b'0', b'b' | b'B', ..] => Some(|c| matches!(c, b'0' | b'1')),
+        [b'0', b'o' | b'O', ..] => Some(|c| matches!(c, b'0'..=b'7')),
+        _ => None,
+    };
+    if let Some(is_digit) = radix_digit {
+        let len = digits_end(bytes, 2, is_digit);
+        if len > 2 {
+            return len;
+        }
+    }
+    let mut len = digits_end(bytes, 0, u8::is_ascii_digit);
+    if (1..=3).contains(&len) && !bytes[..len].contains(&b'_') {
+        while bytes.get(len) == Some(&b' ')
+            && bytes
+                .get(len + 1..len + 4)
+                .is_some_and(|group| group.iter().all(u8::is_ascii_digit))
+            && !bytes
+                .get(len + 4)
+                .is_some_and(|c| c.is_ascii_digit() || *c == b'_')
+        {
+            len += 4;
+        }
+    }
+    // Další desetinné tečky patří ke stejnému (neplatnému) číslu, aby chyba hlásila celé
+    // číslo 3.5.5, a ne nečekaný symbol .5
+    while bytes.get(len) == Some(&decimal_separator) {
+        len = digits_end(bytes, len + 1, u8::is_ascii_digit);
+    }
+    if let Some(b'e' | b'E') = bytes.get(len) {
+        let digits_start = len + 1 + usize::from(matches!(bytes.get(len + 1), Some(b'+' | b'-')));
+        let exponent_end = digits_end(bytes, digits_start, u8::is_ascii_digit);
+        if exponent_end > digits_start {
+            len = exponent_end;
+        }
+    }
+    len
+}
+
+/// Konec posloupnosti číslic začínající na pozici from. Podtržítko může být jen mezi dvěma
+/// číslicemi.
+fn digits_end(bytes: &[u8], from: usize, is_digit: fn(&u8) -> bool) -> usize {
+    let mut end = from;
+    while let Some(c) = bytes.get(end) {
+        let separator = *c == b'_' && end > from && bytes.get(end + 1).is_some_and(is_digit);
+        if !is_digit(c) && !separator {
+            break;
+        }
+        end += 1;
+    }
+    end
+}
+
+/// Pokud text začíná operátorem, vrátí ho
+fn operator_at(text: &str) -> Option<&'static str> {
+    OPERATORS
+        .iter()
+        .copied()
+        .find(|oper| text.starts_with(oper))
+}
+
+/// Pokud je slovo operátor (and, or...), vrátí ho
+pub fn keyword_operator(word: &str) -> Option<&'static str> {
+    KEYWORD_OPERATORS
+        .iter()
+        .copied()
+        .find(|keyword| keyword.eq_ignore_ascii_case(word))
+}
+
+/// Vrací true, pokud je text platný identifikátor (název proměnné nebo funkce):
+/// začíná písmenem, dál obsahuje jen písmena, číslice a podtržítka.
+pub fn is_ident(name: &str) -> bool {
+    name.chars().next().is_some_and(char::is_alphabetic) && name.chars().all(is_ident_char)
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn missing_quote_err(start: usize, end: usize) -> CalcError {
+    CalcError::ParseErr(ErrorKind::UnterminatedText).with_span(Span::new(start, end))
+}
+
+/// Projde tokeny a ke každému vrátí hloubku zanoření do závorek. Závorky samotné mají
+/// hloubku vnějšího výrazu, takže tokeny mimo všechny závorky mají hloubku 0.
+/// Pokud pravá závorka předběhne levé, vrátí index té závorky jako chybu.
+pub fn bracket_depths(tokens: &[Token]) -> Result<Vec<usize>, usize> {
+    let mut depths = Vec::with_capacity(tokens.len());
+    let mut curr_depth = 0usize;
+    for (idx, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::LeftBracket => {
+                depths.push(curr_depth);
+                curr_depth += 1;
+            }
+            TokenKind::RightBracket => {
+                curr_depth = curr_depth.checked_sub(1).ok_or(idx)?;
+                depths.push(curr_depth);
+            }
+            _ => depths.push(curr_depth),
+        }
+    }
+    Ok(depths)
+}
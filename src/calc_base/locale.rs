@@ -0,0 +1,63 @@
+use std::fmt::Display;
+
+/// Zápis čísel ve výrazech a ve výpisu výsledků. Lexer podle něj pozná desetinný
+/// oddělovač a oddělovač parametrů funkcí, výpis hodnot (Value::format_with) ho používá
+/// pro desetinná čísla a seznamy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    /// Desetinná tečka, parametry oddělené čárkou: max(1.5, 2)
+    #[default]
+    International,
+    /// Desetinná čárka, parametry oddělené středníkem jako v českých tabulkových
+    /// procesorech: max(1,5; 2)
+    Czech,
+}
+
+impl Locale {
+    pub fn from_name(name: &str) -> Option<Locale> {
+        match name.trim().to_lowercase().as_str() {
+            "intl" | "en" => Some(Locale::International),
+            "cs" | "cz" => Some(Locale::Czech),
+            _ => None,
+        }
+    }
+
+    pub fn decimal_separator(&self) -> char {
+        match self {
+            Locale::International => '.',
+            Locale::Czech => ',',
+        }
+    }
+
+    pub fn param_separator(&self) -> char {
+        match self {
+            Locale::International => ',',
+            Locale::Czech => ';',
+        }
+    }
+
+    /// Převede text vypsaný s desetinnou tečkou a čárkou mezi hodnotami do tohoto zápisu:
+    /// "[1.5, 2]" na "[1,5; 2]". Textové hodnoty se převádět nesmí.
+    pub fn localize(&self, text: &str) -> String {
+        match self {
+            Locale::International => text.to_string(),
+            Locale::Czech => text
+                .chars()
+                .map(|c| match c {
+                    '.' => ',',
+                    ',' => ';',
+                    c => c,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Locale::International => write!(f, "mezinárodní (1.5, parametry oddělené čárkou)"),
+            Locale::Czech => write!(f, "český (1,5, parametry oddělené středníkem)"),
+        }
+    }
+}
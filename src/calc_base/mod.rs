@@ -1,8 +1,22 @@
 use num_bigint::*;
 
+pub mod complex;
+pub mod context;
+pub mod decimal;
 pub mod expr;
 pub mod func_call;
+pub mod func_registry;
+pub mod lazy_funcs;
+pub mod lexer;
+pub mod locale;
+pub mod number_format;
+pub mod pi_multiple;
 pub mod rational;
+pub mod settings;
 pub mod std_funcs;
+pub mod surd;
+pub mod trig_funcs;
 pub mod value;
 pub mod value_algebra;
+pub mod value_cmp;
+pub mod value_logic;
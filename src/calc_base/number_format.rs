@@ -0,0 +1,467 @@
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::decimal::{Decimal, DEFAULT_DIGITS, MAX_DIGITS};
+use crate::calc_base::func_registry::Arity;
+use crate::calc_base::pi_multiple::fmt_pi_multiple;
+use crate::calc_base::rational::Rational;
+use crate::calc_base::settings::CalcSettings;
+use crate::calc_base::value::Value;
+use crate::s;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{Signed, Zero};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Počet platných číslic semilogaritmického zápisu čísla, které nemá vlastní přesnost
+/// (zlomek, reálné číslo). Odpovídá přesnosti f64.
+const SCI_DIGITS: u32 = 17;
+
+/// Nejdelší vypisovaná desetinná část periodického čísla. Perioda zlomku může mít až
+/// (jmenovatel - 1) číslic, delší rozvoj se zkrátí a zakončí výpustkou.
+const MAX_REPEATING_DIGITS: usize = 1000;
+
+/// Způsob výpisu čísel. Nastavuje se pro celé sezení (CalcContext::set_number_format,
+/// v konzoli příkaz :format), nebo pro jedinou hodnotu funkcí se stejným názvem, která vrátí
+/// text (hex(255), fixed(x, 2)...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberFormat {
+    /// Zlomek jako 5 / 3, reálné číslo s desetinnou tečkou
+    #[default]
+    Default,
+    /// Celé číslo v šestnáctkové soustavě: 0xFF
+    Hex,
+    /// Celé číslo ve dvojkové soustavě: 0b1010
+    Bin,
+    /// Celé číslo v osmičkové soustavě: 0o17
+    Oct,
+    /// Zlomek jako smíšené číslo: 1 2/3
+    Mixed,
+    /// Desetinné číslo s vyznačenou periodou: 0.(142857)
+    Repeating,
+    /// Pevný počet desetinných míst: fixed 2 zapíše 3.14
+    Fixed(u32),
+    /// Daný počet platných číslic: sig 4 zapíše 3.142
+    Significant(u32),
+    /// Semilogaritmický tvar 1.2345e6, volitelně s daným počtem platných číslic
+    Scientific(Option<u32>),
+    /// Inženýrský tvar s exponentem dělitelným třemi: 12.345e3
+    Engineering(Option<u32>),
+}
+
+impl NumberFormat {
+    /// Ověří počet číslic formátu: desetinných míst může být 0 až MAX_DIGITS, platných
+    /// číslic 1 až MAX_DIGITS
+    pub fn check_digits(&self) -> Result<(), CalcError> {
+        let (digits, min) = match *self {
+            NumberFormat::Fixed(digits) => (digits, 0),
+            NumberFormat::Significant(digits)
+            | NumberFormat::Scientific(Some(digits))
+            | NumberFormat::Engineering(Some(digits)) => (digits, 1),
+            _ => return Ok(()),
+        };
+        if !(min..=MAX_DIGITS).contains(&digits) {
+            return Err(CalcError::EvaluateErr(ErrorKind::DigitsOutOfRange {
+                subject: Some(s!(self.name())),
+                min,
+                max: MAX_DIGITS,
+                got: digits.to_string(),
+            }));
+        }
+        Ok(())
+    }
+
+    /// Název formátu, stejný jako název funkce, která hodnotu v tomto formátu vypíše
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumberFormat::Default => "default",
+            NumberFormat::Hex => "hex",
+            NumberFormat::Bin => "bin",
+            NumberFormat::Oct => "oct",
+            NumberFormat::Mixed => "mixed",
+            NumberFormat::Repeating => "repeating",
+            NumberFormat::Fixed(_) => "fixed",
+            NumberFormat::Significant(_) => "sig",
+            NumberFormat::Scientific(_) => "sci",
+            NumberFormat::Engineering(_) => "eng",
+        }
+    }
+
+    /// Zapíše číslo v tomto formátu. Vrátí None, pokud hodnota není číslo nebo pro ni
+    /// formát nemá smysl (zlomek v šestnáctkové soustavě).
+    fn apply(&self, value: &Value, settings: &CalcSettings) -> Option<String> {
+        match *self {
+            NumberFormat::Default => Some(value.to_plain_string()),
+            NumberFormat::Hex => Some(fmt_radix(&to_integer(value)?, 16, "0x")),
+            NumberFormat::Bin => Some(fmt_radix(&to_integer(value)?, 2, "0b")),
+            NumberFormat::Oct => Some(fmt_radix(&to_integer(value)?, 8, "0o")),
+            NumberFormat::Mixed => Some(fmt_mixed(&to_rational(value, settings)?)),
+            NumberFormat::Repeating => {
+                let expansion =
+                    to_rational(value, settings)?.decimal_expansion(MAX_REPEATING_DIGITS);
+                Some(expansion.to_string())
+            }
+            NumberFormat::Fixed(places) => Some(fmt_fixed(&to_rational(value, settings)?, places)),
+            NumberFormat::Significant(digits) => {
+                let decimal = Decimal::from_rational(&to_rational(value, settings)?, digits);
+                Some(fmt_significant(&decimal, digits))
+            }
+            NumberFormat::Scientific(digits) => {
+                Some(fmt_exponential(&to_decimal(value, digits, settings)?, 1))
+            }
+            NumberFormat::Engineering(digits) => {
+                Some(fmt_exponential(&to_decimal(value, digits, settings)?, 3))
+            }
+        }
+    }
+}
+
+impl Display for NumberFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumberFormat::Fixed(digits)
+            | NumberFormat::Significant(digits)
+            | NumberFormat::Scientific(Some(digits))
+            | NumberFormat::Engineering(Some(digits)) => write!(f, "{} {digits}", self.name()),
+            _ => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+/// Formát zapsaný jako v příkazu :format, např. "hex", "fixed 2", "sci", "sci 5"
+impl FromStr for NumberFormat {
+    type Err = CalcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut words = s.split_whitespace();
+        let name = words.next().unwrap_or_default().to_lowercase();
+        let digits =
+            match (words.next(), words.next()) {
+                (None, _) => None,
+                (Some(digits), None) => Some(digits.parse::<u32>().map_err(|_| {
+                    CalcError::ParseErr(ErrorKind::NotDigitCount { text: s!(digits) })
+                })?),
+                (Some(_), Some(_)) => {
+                    return Err(CalcError::ParseErr(ErrorKind::FormatTooManyParts {
+                        format: s!(s),
+                    }))
+                }
+            };
+        let format = match (name.as_str(), digits) {
+            ("default", None) => NumberFormat::Default,
+            ("hex", None) => NumberFormat::Hex,
+            ("bin", None) => NumberFormat::Bin,
+            ("oct", None) => NumberFormat::Oct,
+            ("mixed", None) => NumberFormat::Mixed,
+            ("repeating", None) => NumberFormat::Repeating,
+            ("fixed", Some(digits)) => NumberFormat::Fixed(digits),
+            ("sig", Some(digits)) => NumberFormat::Significant(digits),
+            ("sci", digits) => NumberFormat::Scientific(digits),
+            ("eng", digits) => NumberFormat::Engineering(digits),
+            _ => {
+                return Err(CalcError::ParseErr(ErrorKind::UnknownFormat {
+                    format: s!(s),
+                }))
+            }
+        };
+        format.check_digits()?;
+        Ok(format)
+    }
+}
+
+impl Value {
+    /// Hodnota s označením typu jako Display, ale čísla jsou zapsaná podle nastavení
+    /// (formát čísel, oddělování tisíců, desetinná čárka). Násobky π, odmocniny a komplexní
+    /// čísla se vypíší ve svém tvaru, jen s oddělenými tisíci a desetinným oddělovačem.
+    pub fn format_with(&self, settings: &CalcSettings) -> String {
+        let tag = match self {
+            Value::Integer(_) => "integer",
+            Value::BigInt(_) => "big integer",
+            Value::Rational(_) => "rational",
+            Value::Real(_) => "real",
+            Value::Decimal(_) => "decimal",
+            Value::List(_) => "list",
+            Value::Text(_) => return self.to_string(),
+            // V režimu desetinných čísel se násobek π přiblíží na nastavený počet číslic
+            Value::PiMultiple(k) => {
+                let approx = settings
+                    .decimal_digits
+                    .and_then(|digits| Decimal::from_value(self, digits));
+                let text = match approx {
+                    Some(approx) => format!("{} ≈ {approx}\t(pi multiple)", fmt_pi_multiple(k)),
+                    None => self.to_string(),
+                };
+                return finish_number(text, settings);
+            }
+            _ => return finish_number(self.to_string(), settings),
+        };
+        format!("{}\t({tag})", self.to_plain_string_with(settings))
+    }
+
+    /// Hodnota bez označení typu (viz to_plain_string), čísla jsou zapsaná podle nastavení
+    pub fn to_plain_string_with(&self, settings: &CalcSettings) -> String {
+        let text = match self {
+            Value::List(items) => {
+                let items: Vec<_> = items
+                    .iter()
+                    .map(|item| item.to_plain_string_with(settings))
+                    .collect();
+                let separator = format!("{} ", settings.locale.param_separator());
+                return format!("[{}]", items.join(&separator));
+            }
+            Value::Text(_) => return self.to_plain_string(),
+            Value::Integer(_)
+            | Value::BigInt(_)
+            | Value::Rational(_)
+            | Value::Real(_)
+            | Value::Decimal(_) => settings
+                .number_format
+                .apply(self, settings)
+                .unwrap_or_else(|| self.to_plain_string()),
+            _ => self.to_plain_string(),
+        };
+        finish_number(text, settings)
+    }
+}
+
+/// Oddělí v zapsaném čísle tisíce a převede desetinnou tečku podle nastavení
+fn finish_number(text: String, settings: &CalcSettings) -> String {
+    let text = if settings.digit_grouping {
+        group_thousands(&text)
+    } else {
+        text
+    };
+    settings.locale.localize(&text)
+}
+
+/// Vypíše číslo v daném formátu jako text: hex(255) = "0xFF". Tisíce a desetinný
+/// oddělovač se zapíší podle nastavení. Funkce hex, bin a oct přijímají jen celá čísla (viz ParamType::Integer).
+pub fn format_func(
+    format: NumberFormat,
+    value: &Value,
+    settings: &CalcSettings,
+) -> Result<Value, CalcError> {
+    format.check_digits()?;
+    let text = format.apply(value, settings).ok_or_else(|| {
+        CalcError::FuncCallErr(ErrorKind::InvalidCall {
+            func: s!(format.name()),
+            args: value.to_plain_string(),
+        })
+    })?;
+    Ok(Value::Text(finish_number(text, settings)))
+}
+
+/// Vypíše číslo jako text s oddělenými tisíci ve formátu podle nastavení:
+/// group(1234567) = "1 234 567". Zlomek se ve výchozím formátu vypíše desetinným rozvojem,
+/// tisíce se oddělí jen v celé části: group(-1234567.891) = "-1 234 567.891".
+pub fn group(params: &[Value], settings: &CalcSettings) -> Result<Value, CalcError> {
+    let format = match (settings.number_format, &params[0]) {
+        (NumberFormat::Default, Value::Rational(q)) if q.to_bigint().is_none() => {
+            NumberFormat::Repeating
+        }
+        (format, _) => format,
+    };
+    let grouped = CalcSettings {
+        digit_grouping: true,
+        ..settings.clone()
+    };
+    format_func(format, &params[0], &grouped)
+}
+
+/// Počet číslic z volitelného druhého parametru funkce: sci(x), sci(x, číslice)
+pub fn optional_digits(func: &str, params: &[Value]) -> Result<Option<u32>, CalcError> {
+    match params {
+        [_] => Ok(None),
+        [_, digits] => Ok(Some(required_digits(func, digits)?)),
+        _ => Err(CalcError::FuncCallErr(ErrorKind::ArityMismatch {
+            name: s!(func),
+            expected: Arity::Range(1, 2),
+            got: params.len(),
+        })),
+    }
+}
+
+/// Počet číslic z parametru funkce: fixed(x, místa). Rozsah ověří NumberFormat::check_digits.
+pub fn required_digits(func: &str, digits: &Value) -> Result<u32, CalcError> {
+    match digits {
+        Value::Integer(d) => u32::try_from(*d).ok(),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        CalcError::EvaluateErr(ErrorKind::DigitsOutOfRange {
+            subject: Some(s!(func)),
+            min: 0,
+            max: MAX_DIGITS,
+            got: digits.to_plain_string(),
+        })
+    })
+}
+
+/// Celé číslo, i když je uložené jako zlomek se jmenovatelem 1
+fn to_integer(value: &Value) -> Option<BigInt> {
+    match value {
+        Value::Integer(i) => Some(BigInt::from(*i)),
+        Value::BigInt(i) => Some(i.clone()),
+        Value::Rational(q) => q.to_bigint(),
+        _ => None,
+    }
+}
+
+/// Přesná hodnota čísla jako zlomek. Reálné číslo se převede podle svého nejkratšího
+/// desetinného zápisu (0.1 na 1/10, ne na zlomek se jmenovatelem 2^55). Násobek π
+/// a odmocnina se vyčíslí s přesností režimu desetinných čísel (jinak DEFAULT_DIGITS).
+fn to_rational(value: &Value, settings: &CalcSettings) -> Option<Rational> {
+    match value {
+        Value::Integer(i) => Some(Rational::from_int(*i)),
+        Value::BigInt(i) => Some(Rational::from_bigint(i.clone())),
+        Value::Rational(q) => Some(q.clone().reduce_move()),
+        Value::Real(r) if r.is_finite() => Rational::from_str(&format!("{r:e}")).ok(),
+        Value::Decimal(d) => Some(d.to_rational()),
+        Value::PiMultiple(_) | Value::Surd(_) => {
+            let digits = settings.decimal_digits.unwrap_or(DEFAULT_DIGITS);
+            Decimal::from_value(value, digits).map(|d| d.to_rational())
+        }
+        _ => None,
+    }
+}
+
+/// Číslo zaokrouhlené pro semilogaritmický zápis. Bez zadaného počtu číslic si desetinné
+/// číslo ponechá svou přesnost a ostatní čísla dostanou přesnost režimu desetinných čísel,
+/// nebo SCI_DIGITS.
+fn to_decimal(value: &Value, digits: Option<u32>, settings: &CalcSettings) -> Option<Decimal> {
+    let digits = match (digits, value) {
+        (Some(digits), _) => digits,
+        (None, Value::Decimal(d)) => d.digits(),
+        (None, Value::Real(_)) => SCI_DIGITS,
+        (None, _) => settings.decimal_digits.unwrap_or(SCI_DIGITS),
+    };
+    Some(Decimal::from_rational(
+        &to_rational(value, settings)?,
+        digits,
+    ))
+}
+
+/// Celé číslo v soustavě o daném základu, s předponou za znaménkem: -0xFF
+fn fmt_radix(i: &BigInt, radix: u32, prefix: &str) -> String {
+    let sign = if i.is_negative() { "-" } else { "" };
+    format!(
+        "{sign}{prefix}{}",
+        i.magnitude().to_str_radix(radix).to_uppercase()
+    )
+}
+
+/// Smíšené číslo: 5/3 = 1 2/3, -5/3 = -1 2/3. Zlomek menší než 1 zůstane zlomkem.
+fn fmt_mixed(q: &Rational) -> String {
+    let (whole, rest) = q.numerator.div_rem(&q.denominator);
+    if rest.is_zero() {
+        whole.to_string()
+    } else if whole.is_zero() {
+        q.to_compact_string()
+    } else {
+        format!("{whole} {}/{}", rest.abs(), q.denominator)
+    }
+}
+
+/// Číslo zaokrouhlené na daný počet desetinných míst (polovina od nuly): 2/3 = 0.667
+fn fmt_fixed(q: &Rational, places: u32) -> String {
+    let scale = BigInt::from(10).pow(places);
+    let (mut scaled, remainder) = (q.numerator.abs() * scale).div_rem(&q.denominator);
+    if remainder * 2 >= q.denominator {
+        scaled += 1;
+    }
+    let sign = if q.is_negative() && !scaled.is_zero() {
+        "-"
+    } else {
+        ""
+    };
+    let digits = format!("{scaled:0>width$}", width = places as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - places as usize);
+    if fraction.is_empty() {
+        format!("{sign}{whole}")
+    } else {
+        format!("{sign}{whole}.{fraction}")
+    }
+}
+
+/// Číslo s daným počtem platných číslic včetně nul na konci: 2/3 na 3 číslice = 0.667,
+/// 1/2 = 0.500. Velmi velká a malá čísla se zapíší v semilogaritmickém tvaru.
+fn fmt_significant(d: &Decimal, digits: u32) -> String {
+    let sign = if d.is_negative() { "-" } else { "" };
+    let mut mantissa = d.significant_digits();
+    let len = digits as usize;
+    if mantissa.len() < len {
+        mantissa += &"0".repeat(len - mantissa.len());
+    }
+    let point = if d.is_zero() { 1 } else { d.magnitude() };
+    if point > i64::from(digits).max(21) || point < -5 {
+        return format!("{sign}{}", exponential(&mantissa, point, 1));
+    }
+    if point >= len as i64 {
+        format!("{sign}{mantissa}{}", "0".repeat(point as usize - len))
+    } else if point > 0 {
+        let (whole, fraction) = mantissa.split_at(point as usize);
+        format!("{sign}{whole}.{fraction}")
+    } else {
+        format!("{sign}0.{}{mantissa}", "0".repeat((-point) as usize))
+    }
+}
+
+/// Semilogaritmický zápis s exponentem dělitelným krokem: krok 1 je vědecký tvar
+/// (1.2345e4), krok 3 inženýrský (12.345e3)
+fn fmt_exponential(d: &Decimal, step: i64) -> String {
+    if d.is_zero() {
+        return s!("0e0");
+    }
+    let sign = if d.is_negative() { "-" } else { "" };
+    format!(
+        "{sign}{}",
+        exponential(&d.significant_digits(), d.magnitude(), step)
+    )
+}
+
+/// Zápis číslic mantisy s řádem point jako m.mmm e exponent, exponent je násobek kroku
+fn exponential(digits: &str, point: i64, step: i64) -> String {
+    let exponent = (point - 1).div_euclid(step) * step;
+    let whole_len = (point - exponent) as usize;
+    let mut digits = digits.to_string();
+    if digits.len() < whole_len {
+        digits += &"0".repeat(whole_len - digits.len());
+    }
+    let (whole, fraction) = digits.split_at(whole_len);
+    if fraction.is_empty() {
+        format!("{whole}e{exponent}")
+    } else {
+        format!("{whole}.{fraction}e{exponent}")
+    }
+}
+
+/// Oddělí tisíce v celé části každého čísla v textu mezerou: 1234567.891 = 1 234 567.891.
+/// Takový zápis jde znovu zadat jako číslo. Číslice za desetinnou tečkou, v periodě,
+/// v exponentu a po písmenu (0xFFFF, proměnná x12345) se neoddělují.
+pub fn group_thousands(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len() + text.len() / 3);
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let end = i + chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+        let before = i.checked_sub(1).map(|j| chars[j]);
+        let is_exponent = matches!(before, Some('+' | '-'))
+            && i.checked_sub(2)
+                .is_some_and(|j| matches!(chars[j], 'e' | 'E'));
+        let is_integer_part = !is_exponent
+            && !before.is_some_and(|c| c.is_alphanumeric() || matches!(c, '.' | '(' | '_'));
+        for (k, &c) in chars[i..end].iter().enumerate() {
+            if is_integer_part && k > 0 && (end - i - k) % 3 == 0 {
+                result.push(' ');
+            }
+            result.push(c);
+        }
+        i = end;
+    }
+    result
+}
@@ -0,0 +1,99 @@
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::rational::Rational;
+use crate::calc_base::value::Value;
+use crate::s;
+use num_traits::Zero;
+use std::f64::consts::PI;
+
+/// Číslo ve tvaru k·π^power, kde k je zlomek. Zlomky (power = 0) a násobky π (power = 1)
+/// se tak dají sčítat, násobit i dělit přesně: pi/6 + pi/3 = pi/2, (2*pi)/pi = 2.
+/// Výsledek s jinou mocninou π (např. pi*pi) přesně vyjádřit nejde.
+#[derive(Debug, Clone)]
+pub struct PiTerm {
+    pub coef: Rational,
+    pub power: i32,
+}
+
+impl PiTerm {
+    /// Převede zlomek nebo násobek π. Pro jiné hodnoty vrátí None.
+    pub fn from_value(value: &Value) -> Option<PiTerm> {
+        let (coef, power) = match value {
+            Value::Integer(i) => (Rational::from_int(*i), 0),
+            Value::BigInt(i) => (Rational::from_bigint(i.clone()), 0),
+            Value::Rational(q) => (q.clone(), 0),
+            Value::PiMultiple(k) => (k.clone(), 1),
+            _ => return None,
+        };
+        Some(PiTerm { coef, power })
+    }
+
+    /// Převede výsledek zpět na hodnotu. Pokud to není zlomek ani násobek π, vrátí None.
+    pub fn into_value(self) -> Option<Value> {
+        let value = match self.power {
+            _ if self.coef.numerator.is_zero() => Value::Integer(0),
+            0 => Value::Rational(self.coef),
+            1 => Value::PiMultiple(self.coef),
+            _ => return None,
+        };
+        value.simplify_type_move().ok()
+    }
+
+    pub fn add(&self, other: &PiTerm) -> Option<PiTerm> {
+        if self.coef.numerator.is_zero() {
+            return Some(other.clone());
+        } else if other.coef.numerator.is_zero() {
+            return Some(self.clone());
+        } else if self.power != other.power {
+            return None;
+        }
+        Some(PiTerm {
+            coef: self.coef.clone() + other.coef.clone(),
+            power: self.power,
+        })
+    }
+
+    pub fn sub(&self, other: &PiTerm) -> Option<PiTerm> {
+        self.add(&PiTerm {
+            coef: -other.coef.clone(),
+            power: other.power,
+        })
+    }
+
+    pub fn mul(&self, other: &PiTerm) -> Option<PiTerm> {
+        Some(PiTerm {
+            coef: self.coef.clone() * other.coef.clone(),
+            power: self.power + other.power,
+        })
+    }
+
+    pub fn div(&self, other: &PiTerm) -> Option<PiTerm> {
+        if other.coef.numerator.is_zero() {
+            return None;
+        }
+        Some(PiTerm {
+            coef: self.coef.clone() / other.coef.clone(),
+            power: self.power - other.power,
+        })
+    }
+}
+
+/// Reálná hodnota k·π
+pub fn pi_multiple_to_real(k: &Rational) -> Option<f64> {
+    Some(k.to_real()? * PI)
+}
+
+/// Zápis k·π, se kterým jde výsledek znovu zadat jako výraz: pi, -pi, 1/6*pi
+pub fn fmt_pi_multiple(k: &Rational) -> String {
+    match k.to_compact_string().as_str() {
+        "1" => String::from("pi"),
+        "-1" => String::from("-pi"),
+        k => format!("{k}*pi"),
+    }
+}
+
+/// Chyba, když se násobek π nepodaří převést na reálné číslo
+pub fn pi_to_real_err() -> CalcError {
+    CalcError::EvaluateErr(ErrorKind::NotReal {
+        value: s!("PiMultiple"),
+    })
+}
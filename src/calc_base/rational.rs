@@ -1,15 +1,16 @@
-use crate::base::CalcError;
+use crate::base::{CalcError, ErrorKind};
 use crate::s;
 use num_bigint::BigInt;
 use num_integer::Integer;
-use num_traits::{Signed, ToPrimitive, Zero};
-use regex::*;
+use num_traits::{Float, One, Signed, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
 use std::ops::{Add, Div, Mul, Neg, Sub};
 use std::str::FromStr;
 
 // Racionální číslo (zlomek) je chápáno jako dvojice celých čísel. Proto je počítání s ním dokonale přesné.
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default)]
 pub struct Rational {
     pub numerator: BigInt,
     pub denominator: BigInt,
@@ -28,8 +29,7 @@ impl Rational {
     }
 
     pub fn is_negative(&self) -> bool {
-        let z = BigInt::zero();
-        self.numerator < z || self.denominator < z && !(self.numerator < z && self.denominator < z)
+        !self.numerator.is_zero() && self.numerator.is_negative() != self.denominator.is_negative()
     }
 
     pub fn to_real(&self) -> Option<f64> {
@@ -50,12 +50,24 @@ impl Rational {
         }
     }
 
-    pub fn new(mut numerator: i64, mut denominator: i64) -> Rational {
-        if numerator < 0 && denominator < 0 {
-            numerator = -numerator;
-            denominator = -denominator;
+    /// Reálné číslo převede na zlomek se stejnou hodnotou. Každé konečné f64 je zlomek se
+    /// jmenovatelem 2^n, takže převod je přesný. Pro NaN a nekonečno vrátí None.
+    pub fn from_real(r: f64) -> Option<Rational> {
+        if !r.is_finite() {
+            return None;
         }
+        let (mantissa, exponent, sign) = Float::integer_decode(r);
+        let numerator = BigInt::from(mantissa) * sign;
+        return Some(if exponent >= 0 {
+            Rational::from_bigint(numerator << exponent as usize)
+        } else {
+            Rational::new_bigint(numerator, BigInt::one() << (-exponent) as usize)
+        });
+    }
 
+    /// Zlomek z čitatele a jmenovatele. Znaménko upraví až reduce_move na BigInt, protože
+    /// -i64::MIN se do i64 nevejde.
+    pub fn new(numerator: i64, denominator: i64) -> Rational {
         Rational {
             numerator: BigInt::from(numerator),
             denominator: BigInt::from(denominator),
@@ -78,7 +90,12 @@ impl Rational {
         }
     }
 
+    /// Zkrátí zlomek a znaménko přesune do čitatele
     pub fn reduce_move(mut self) -> Self {
+        if self.denominator.is_negative() {
+            self.numerator = -self.numerator;
+            self.denominator = -self.denominator;
+        }
         let gcd = self.numerator.gcd(&self.denominator);
         if gcd.abs() > BigInt::from(1) {
             self.numerator = &self.numerator / &gcd;
@@ -96,16 +113,20 @@ impl Rational {
         };
     }
 
+    /// Zaokrouhlí zlomek nahoru na celé číslo
+    pub fn ceil(&self) -> Rational {
+        Rational::from_bigint(Integer::div_ceil(&self.numerator, &self.denominator))
+    }
+
     pub fn pow_int(&self, exponent: i64) -> Rational {
+        let exp = pow_exponent(exponent);
         if exponent >= 0 {
-            let exp = exponent as u32;
             Rational {
                 numerator: self.numerator.pow(exp),
                 denominator: self.denominator.pow(exp),
             }
             .reduce_move()
         } else {
-            let exp = (-exponent) as u32;
             Rational {
                 numerator: self.denominator.pow(exp),
                 denominator: self.numerator.pow(exp),
@@ -115,52 +136,283 @@ impl Rational {
     }
 }
 
+/// Absolutní hodnota celočíselného exponentu pro funkce pow. Exponent větší než u32 má
+/// smysl jen u základu 0, 1 nebo -1 (jinak je výsledek příliš velký, viz check_pow_size),
+/// u kterých záleží jen na tom, jestli je exponent nulový a jestli je sudý.
+pub(crate) fn pow_exponent(exponent: i64) -> u32 {
+    let abs = exponent.unsigned_abs();
+    u32::try_from(abs).unwrap_or(2 + (abs % 2) as u32)
+}
+
+/// Největší povolený exponent v zápisu čísla (1e10000). Větší čísla by se zbytečně dlouho
+/// počítala.
+const MAX_LITERAL_EXPONENT: u32 = 10_000;
+
 /// Každé číslo napsané posloupností číslic je racionální, např. -52.464864686
 /// Není dobré pracovat s takovými čísly jako s f64, protože se ztratí přesnost.
 impl FromStr for Rational {
     type Err = CalcError;
 
+    /// Číselný literál s desetinnou tečkou, viz Rational::parse_literal
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Například: -52.708 = -52708 / 1000
-        let num_regex = Regex::new(r"^(?<p1>\d+)((\.)(?<p2>\d+))?$").unwrap();
-        let captures = num_regex.captures(s).ok_or(CalcError::ParseErr(s!(
-            "Nepodařilo se extrahovat části čísla regulárním výrazem"
-        )))?;
-
-        // část racionálního čísla před desetinnou tečkou
-        let p1_str = captures
-            .name("p1")
-            .ok_or(CalcError::ParseErr(s!("Racionální číslo má mít tvar #.#")))?
-            .as_str()
-            .trim();
-
-        // část racionálního čísla po desetinné tečce
-        let p2_str = captures
-            .name("p2")
-            .ok_or(CalcError::ParseErr(s!("Racionální číslo má mít tvar #.#")))?
-            .as_str()
-            .trim()
-            .trim_end_matches('0');
-        return if p2_str.is_empty() {
-            // Byly to jen nuly
-            let numerator = p1_str
-                .parse::<BigInt>()
-                .map_err(CalcError::ParseBigIntErr)?;
-            Ok(Rational::from_bigint(numerator))
-        } else {
-            let p1 = p1_str
-                .parse::<BigInt>()
-                .map_err(CalcError::ParseBigIntErr)?; // část racionálního čísla před desetinnou tečkou
-            let p2 = p2_str
-                .parse::<BigInt>()
-                .map_err(CalcError::ParseBigIntErr)?; // část racionálního čísla po desetinné tečce
-
-            // Víme, co je před a po desetinné tečce. Převést to na zlomek!
-            let mul = BigInt::from(10).pow(p2_str.chars().count() as u32);
-            let numerator = p1 * &mul + p2;
-            let denumerator = mul;
-            Ok(Rational::new_bigint(numerator, denumerator))
+        Rational::parse_literal(s, '.')
+    }
+}
+
+impl Rational {
+    /// Přesně načte číselný literál. Literál může začínat znaménkem a je to buď celé číslo
+    /// v šestnáctkové, dvojkové nebo osmičkové soustavě (0xFF, 0b1010, 0o17), nebo desetinné
+    /// číslo s volitelným exponentem (1.5e-3, .5, 5., 1.50E2). Desetinný oddělovač je tečka,
+    /// nebo čárka v české lokalizaci. Číslice mohou být oddělené podtržítkem nebo mezerou
+    /// (1_000_000, 1 000 000). Neplatný literál je chyba, nikdy se nenačte nepřesně.
+    pub fn parse_literal(s: &str, decimal_separator: char) -> Result<Rational, CalcError> {
+        let literal = s.trim();
+        let (negative, unsigned) = match literal.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, literal.strip_prefix('+').unwrap_or(literal)),
+        };
+        let unsigned = strip_digit_separators(unsigned).ok_or_else(|| {
+            CalcError::ParseErr(ErrorKind::MisplacedDigitSeparator {
+                literal: s!(literal),
+            })
+        })?;
+        let value = match parse_radix_integer(&unsigned) {
+            Some(integer) => Rational::from_bigint(integer),
+            None => parse_decimal(&unsigned, decimal_separator, literal)?,
         };
+        Ok(if negative { -value } else { value })
+    }
+}
+
+/// Desetinné číslo bez znaménka a oddělovačů číslic: celá část, desetinná část a exponent,
+/// např. -52.708 = -52708 / 1000, 1.5e-3 = 15 / 10000. Jedna z částí před a za desetinným
+/// oddělovačem může chybět (.5, 5.), obě ne.
+fn parse_decimal(text: &str, separator: char, literal: &str) -> Result<Rational, CalcError> {
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (text, None),
+    };
+    let (whole, fraction) = mantissa.split_once(separator).unwrap_or((mantissa, ""));
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if fraction.contains(separator) {
+        return Err(CalcError::ParseErr(ErrorKind::MultipleDecimalSeparators {
+            literal: s!(literal),
+        }));
+    }
+    if !is_digits(whole) || !is_digits(fraction) {
+        return Err(CalcError::ParseErr(ErrorKind::InvalidNumber {
+            literal: s!(literal),
+        }));
+    }
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(CalcError::ParseErr(ErrorKind::NumberWithoutDigits {
+            literal: s!(literal),
+        }));
+    }
+
+    let exponent: i64 = match exponent {
+        Some(exponent) => {
+            let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            if digits.is_empty() || !is_digits(digits) {
+                return Err(CalcError::ParseErr(ErrorKind::NonIntegerExponent {
+                    literal: s!(literal),
+                }));
+            }
+            exponent
+                .parse()
+                .ok()
+                .filter(|exp: &i64| exp.unsigned_abs() <= u64::from(MAX_LITERAL_EXPONENT))
+                .ok_or_else(|| {
+                    CalcError::ParseErr(ErrorKind::ExponentTooLarge {
+                        literal: s!(literal),
+                        max: MAX_LITERAL_EXPONENT,
+                    })
+                })?
+        }
+        None => 0,
+    };
+
+    // Nuly na konci desetinné části nic nemění
+    let fraction = fraction.trim_end_matches('0');
+    let numerator = match format!("{whole}{fraction}").as_str() {
+        "" => BigInt::zero(),
+        digits => digits
+            .parse::<BigInt>()
+            .map_err(CalcError::ParseBigIntErr)?,
+    };
+    let exponent = exponent - fraction.len() as i64;
+    let power = BigInt::from(10).pow(exponent.unsigned_abs() as u32);
+    return Ok(if exponent >= 0 {
+        Rational::from_bigint(numerator * power)
+    } else {
+        Rational::new_bigint(numerator, power)
+    });
+}
+
+/// Odstraní z čísla oddělovače číslic: podtržítko mezi dvěma číslicemi (1_000_000, 0xFF_FF)
+/// a mezeru před trojicí číslic (1 000 000). Číslice se posuzují podle soustavy literálu,
+/// takže podtržítko u exponentu (1_e5) nebo předpony (0x_FF) je chyba. Pokud je oddělovač
+/// jinde, vrátí None.
+fn strip_digit_separators(s: &str) -> Option<String> {
+    let radix = match s.get(..2) {
+        Some("0x" | "0X") => 16,
+        Some("0b" | "0B") => 2,
+        Some("0o" | "0O") => 8,
+        _ => 10,
+    };
+    let chars: Vec<char> = s.chars().collect();
+    let mut result = String::with_capacity(s.len());
+    for (i, &c) in chars.iter().enumerate() {
+        match c {
+            '_' => {
+                let before = i.checked_sub(1).map(|j| chars[j]);
+                let after = chars.get(i + 1);
+                if !before.is_some_and(|c| c.is_digit(radix))
+                    || !after.is_some_and(|c| c.is_digit(radix))
+                {
+                    return None;
+                }
+            }
+            ' ' => {
+                let group = chars[i + 1..].iter().take_while(|c| c.is_ascii_digit());
+                if i == 0 || !chars[i - 1].is_ascii_digit() || group.count() != 3 {
+                    return None;
+                }
+            }
+            c => result.push(c),
+        }
+    }
+    Some(result)
+}
+
+/// Celé číslo s předponou soustavy: 0xFF, 0b1010, 0o17
+fn parse_radix_integer(s: &str) -> Option<BigInt> {
+    let (prefix, digits) = s.split_at_checked(2)?;
+    let radix = match prefix {
+        "0x" | "0X" => 16,
+        "0b" | "0B" => 2,
+        "0o" | "0O" => 8,
+        _ => return None,
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    BigInt::parse_bytes(digits.as_bytes(), radix)
+}
+
+/// Zlomky se porovnávají podle hodnoty, ne podle zápisu: 1/2 == 2/4 == -1/-2
+impl PartialEq for Rational {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Rational {}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // a/b < c/d  <=>  a*d < c*b, pokud jsou oba jmenovatelé kladní
+        let (a, b) = self.with_positive_denominator();
+        let (c, d) = other.with_positive_denominator();
+        (a * d).cmp(&(c * b))
+    }
+}
+
+impl Rational {
+    /// Čitatel a jmenovatel upravené tak, aby jmenovatel byl kladný
+    fn with_positive_denominator(&self) -> (BigInt, BigInt) {
+        if self.denominator.is_negative() {
+            (-&self.numerator, -&self.denominator)
+        } else {
+            (self.numerator.clone(), self.denominator.clone())
+        }
+    }
+}
+
+impl Rational {
+    /// Zápis bez mezer (3/4), se kterým jde výsledek znovu zadat jako součást výrazu.
+    /// Celé číslo se zapíše bez jmenovatele.
+    pub fn to_compact_string(&self) -> String {
+        let (numerator, denominator) = self.clone().reduce_move().with_positive_denominator();
+        if denominator.is_one() {
+            numerator.to_string()
+        } else {
+            format!("{numerator}/{denominator}")
+        }
+    }
+}
+
+impl Rational {
+    /// Přesný desetinný rozvoj s nejvýše max_digits číslicemi za desetinnou tečkou. Rozvoj
+    /// zlomku je buď konečný (3/8 = 0.375), nebo od nějakého místa periodický (1/6 = 0.1(6)).
+    /// Perioda se pozná podle toho, že se při dělení zopakuje zbytek. Na rozdíl od to_real
+    /// funguje i pro čitatele a jmenovatele, kteří se nevejdou do f64.
+    pub fn decimal_expansion(&self, max_digits: usize) -> DecimalExpansion {
+        let (numerator, denominator) = self.with_positive_denominator();
+        let (whole, mut remainder) = numerator.abs().div_rem(&denominator);
+        let mut fraction = String::new();
+        // Pozice v rozvoji, na které se zbytek objevil poprvé
+        let mut seen: HashMap<BigInt, usize> = HashMap::new();
+        let mut period_start = None;
+        while !remainder.is_zero() && fraction.len() < max_digits {
+            if let Some(&start) = seen.get(&remainder) {
+                period_start = Some(start);
+                break;
+            }
+            seen.insert(remainder.clone(), fraction.len());
+            let (digit, rest) = (remainder * BigInt::from(10)).div_rem(&denominator);
+            fraction += &digit.to_string();
+            remainder = rest;
+        }
+        // Zbytek se mohl zopakovat právě po poslední povolené číslici
+        if period_start.is_none() && !remainder.is_zero() {
+            period_start = seen.get(&remainder).copied();
+        }
+        DecimalExpansion {
+            negative: self.is_negative(),
+            whole,
+            truncated: period_start.is_none() && !remainder.is_zero(),
+            fraction,
+            period_start,
+        }
+    }
+}
+
+/// Desetinný rozvoj zlomku (viz Rational::decimal_expansion). Vypisuje se s periodou
+/// v závorce (0.1(6)), useknutý rozvoj končí výpustkou (0.142…).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecimalExpansion {
+    pub negative: bool,
+    /// Celá část bez znaménka
+    pub whole: BigInt,
+    /// Číslice za desetinnou tečkou, u periodického rozvoje včetně jedné periody
+    pub fraction: String,
+    /// Pozice v číslicích za tečkou, kde začíná perioda. None u konečného nebo useknutého
+    /// rozvoje.
+    pub period_start: Option<usize>,
+    /// Rozvoj je delší, než kolik číslic se smělo spočítat, a je useknutý
+    pub truncated: bool,
+}
+
+impl Display for DecimalExpansion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.negative { "-" } else { "" };
+        write!(f, "{sign}{}", self.whole)?;
+        match self.period_start {
+            _ if self.fraction.is_empty() => Ok(()),
+            Some(start) => {
+                let (before, period) = self.fraction.split_at(start);
+                write!(f, ".{before}({period})")
+            }
+            None if self.truncated => write!(f, ".{}…", self.fraction),
+            None => write!(f, ".{}", self.fraction),
+        }
     }
 }
 
@@ -204,6 +456,7 @@ impl Sub for Rational {
 impl Div for Rational {
     type Output = Self;
 
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn div(self, rhs: Self) -> Self::Output {
         self * rhs.inverse()
     }
@@ -0,0 +1,23 @@
+use crate::calc_base::locale::Locale;
+use crate::calc_base::number_format::NumberFormat;
+use crate::calc_base::trig_funcs::AngleUnit;
+
+/// Nastavení kalkulátoru, které platí pro celé sezení (např. pro všechny řádky v konzoli).
+/// Je uložené v kontextu a dostávají ho i vestavěné funkce, jejichž výsledek na něm závisí
+/// (viz FuncDescriptor::new_with_settings).
+#[derive(Debug, Clone, Default)]
+pub struct CalcSettings {
+    /// Jednotka úhlu pro funkce bez přípony jednotky (sin, asin, atan2...). Funkce sind,
+    /// sinpi apod. mají jednotku pevnou a nastavení se jich netýká.
+    pub angle_unit: AngleUnit,
+    /// Režim desetinných čísel: počet platných číslic, se kterými se počítají výsledky, které
+    /// nejdou vyjádřit přesně (ln, goniometrické funkce, konstanta e, pi*pi...). None znamená,
+    /// že se takové výsledky počítají jako reálná čísla (f64).
+    pub decimal_digits: Option<u32>,
+    /// Formát, ve kterém se vypisují výsledky (viz Value::format_with)
+    pub number_format: NumberFormat,
+    /// Oddělovat ve výpisu tisíce mezerou (1 234 567)
+    pub digit_grouping: bool,
+    /// Desetinný oddělovač a oddělovač parametrů ve výrazech i ve výpisu výsledků
+    pub locale: Locale,
+}
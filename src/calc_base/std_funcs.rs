@@ -1,69 +1,111 @@
-use crate::base::CalcError;
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::complex::Complex;
+use crate::calc_base::decimal::{Decimal, DEFAULT_DIGITS, MAX_DIGITS};
+use crate::calc_base::func_registry::Arity;
+use crate::calc_base::pi_multiple::{pi_multiple_to_real, pi_to_real_err};
 use crate::calc_base::rational::Rational;
+use crate::calc_base::settings::CalcSettings;
+use crate::calc_base::surd::{surd_to_real_err, Surd};
 use crate::calc_base::value::Value;
-use crate::{rat, s};
+use crate::s;
 use num_bigint::BigInt;
-use num_traits::{FromPrimitive, One, Zero};
+use num_traits::{One, Zero};
 use num_traits::{Signed, ToPrimitive};
 use std::cmp::Ordering;
-use std::f64::consts::PI;
 
-pub fn ln(x: Value) -> Result<Value, CalcError> {
-    // let real = x.as_real()?;
-    // Ok(Value::Real(real.ln()))
+/// Funkci nejde zavolat s parametry těchto typů, např. fact(Rational)
+fn invalid_call(func: &str, arg_types: &str) -> CalcError {
+    CalcError::FuncCallErr(ErrorKind::InvalidCall {
+        func: s!(func),
+        args: s!(arg_types),
+    })
+}
+
+/// Parametry funkce nesplňují podmínku, např. nck(n, k) vyžaduje n >= k
+fn requirement(call: &str, condition: &str) -> CalcError {
+    CalcError::FuncCallErr(ErrorKind::Requirement {
+        call: s!(call),
+        condition: s!(condition),
+    })
+}
+
+fn not_real(value_type: &str) -> CalcError {
+    CalcError::EvaluateErr(ErrorKind::NotReal {
+        value: s!(value_type),
+    })
+}
+
+/// Přirozený logaritmus. V režimu desetinných čísel (digits) a pro desetinný parametr je
+/// výsledek desetinné číslo, jinak reálné.
+pub fn ln(x: Value, digits: Option<u32>) -> Result<Value, CalcError> {
+    // Logaritmus záporného čísla je komplexní: ln(-x) = ln(x) + πi, reálná část se spočítá
+    // s přesností ln(x). Nula se počítá komplexně, aby ln(0) skončil chybou místo -inf.
+    if x.is_negative_number() {
+        return Complex::new(ln((-x)?, digits)?, Value::PiMultiple(Rational::from_int(1)))
+            .into_value();
+    }
+    if let Some(c) = Complex::from_value(&x).filter(Complex::is_zero) {
+        return c.ln()?.into_value();
+    }
+    if let Some(digits) = Decimal::precision_for(&[&x], digits) {
+        if let Some(result) = Decimal::working(&x, digits).and_then(|d| d.ln()) {
+            return Ok(Value::Decimal(result.with_digits(digits)));
+        }
+    }
     match x {
-        Value::Nothing => Err(CalcError::FuncCallErr(s!(
-            "ln(Nothing) není platné volání funkce"
-        ))),
+        Value::Nothing => Err(invalid_call("ln", "Nothing")),
         Value::Integer(i) => Ok(Value::Real((i as f64).ln())),
         Value::BigInt(i) => Ok(Value::Real(
-            i.to_f64()
-                .ok_or(CalcError::EvaluateErr(s!(
-                    "ln: Nepodařilo se převést BigInt na reálné číslo"
-                )))?
-                .ln(),
+            i.to_f64().ok_or_else(|| not_real("BigInt"))?.ln(),
         )),
         Value::Rational(q) => Ok(Value::Real(
-            q.to_real()
-                .ok_or(CalcError::EvaluateErr(s!(
-                    "ln: Nepodařilo se převést zlomek  na reálné číslo"
-                )))?
-                .ln(),
+            q.to_real().ok_or_else(|| not_real("Rational"))?.ln(),
         )),
         Value::Real(r) => Ok(Value::Real(r.ln())),
-        Value::Text(_) => Err(CalcError::FuncCallErr(s!(
-            "ln(Text) není platné volání funkce"
-        ))),
-        Value::Bool(_) => Err(CalcError::FuncCallErr(s!(
-            "ln(Bool) není platné volání funkce"
-        ))),
+        Value::Decimal(d) => Ok(Value::Real(d.to_f64().ln())),
+        Value::PiMultiple(k) => Ok(Value::Real(
+            pi_multiple_to_real(&k).ok_or_else(pi_to_real_err)?.ln(),
+        )),
+        Value::Surd(x) => Ok(Value::Real(x.to_real().ok_or_else(surd_to_real_err)?.ln())),
+        Value::Complex(c) => c.ln()?.into_value(),
+        Value::Text(_) => Err(invalid_call("ln", "Text")),
+        Value::Bool(_) => Err(invalid_call("ln", "Bool")),
+        Value::List(_) => Err(invalid_call("ln", "List")),
     }
 }
 
 pub fn abs(x: Value) -> Result<Value, CalcError> {
     match x {
-        Value::Nothing => Err(CalcError::FuncCallErr(s!(
-            "abs(Nothing) není platné volání funkce"
-        ))),
-        Value::Integer(i) => Ok(Value::Integer(i.abs())),
+        Value::Nothing => Err(invalid_call("abs", "Nothing")),
+        Value::Integer(i) => Ok(match i.checked_abs() {
+            Some(abs) => Value::Integer(abs),
+            None => Value::BigInt(BigInt::from(i).abs()),
+        }),
         Value::BigInt(i) => Ok(Value::BigInt(i.abs())),
         Value::Rational(q) => Ok(Value::Rational(q.abs())),
         Value::Real(r) => Ok(Value::Real(r.abs())),
-        Value::Text(_) => Err(CalcError::FuncCallErr(s!(
-            "abs(Text) není platné volání funkce"
-        ))),
-        Value::Bool(_) => Err(CalcError::FuncCallErr(s!(
-            "abs(Bool) není platné volání funkce"
-        ))),
+        Value::Decimal(d) => Ok(Value::Decimal(d.abs())),
+        Value::PiMultiple(k) => Ok(Value::PiMultiple(k.abs())),
+        Value::Surd(x) => Ok(Value::Surd(x.abs())),
+        Value::Complex(c) => c.abs(),
+        Value::Text(_) => Err(invalid_call("abs", "Text")),
+        Value::Bool(_) => Err(invalid_call("abs", "Bool")),
+        Value::List(_) => Err(invalid_call("abs", "List")),
     }
 }
 
 pub fn comb(n: Value, k: Value, repetition: Value) -> Result<Value, CalcError> {
     macro_rules! param_type_error {
         () => {
-            Err(CalcError::FuncCallErr(s!(
-                "Funkce comb(n,k,repetition) očekává dva celočíselné parametry a jeden bool"
-            )))
+            Err(invalid_call(
+                "comb",
+                &format!(
+                    "{}, {}, {}",
+                    n.type_name(),
+                    k.type_name(),
+                    repetition.type_name()
+                ),
+            ))
         };
     }
 
@@ -71,17 +113,11 @@ pub fn comb(n: Value, k: Value, repetition: Value) -> Result<Value, CalcError> {
         if let Value::Integer(k_int) = k {
             if let Value::Bool(rep_bool) = repetition {
                 if n_int < 0 {
-                    Err(CalcError::FuncCallErr(s!(
-                        "Chyba: comb(n,k,repetition): n musí být >= 0"
-                    )))
+                    Err(requirement("comb(n, k, repetition)", "n >= 0"))
                 } else if k_int < 0 {
-                    Err(CalcError::FuncCallErr(s!(
-                        "Chyba: comb(n,k,repetition): k musí být >= 0"
-                    )))
+                    Err(requirement("comb(n, k, repetition)", "k >= 0"))
                 } else if n_int < k_int {
-                    Err(CalcError::FuncCallErr(s!(
-                        "Chyba: comb(n,k,repetition) vyžaduje: n >= k"
-                    )))
+                    Err(requirement("comb(n, k, repetition)", "n >= k"))
                 } else {
                     comb_internal(n_int, k_int, rep_bool)
                 }
@@ -97,17 +133,10 @@ pub fn comb(n: Value, k: Value, repetition: Value) -> Result<Value, CalcError> {
 }
 
 pub fn comb_internal(n: i64, k: i64, repetition: bool) -> Result<Value, CalcError> {
-    let result = if repetition {
+    if repetition {
         nck_internal(n + k - 1, k)
     } else {
         nck_internal(n, k)
-    };
-
-    match result {
-        Ok(result_value) => Ok(result_value),
-        Err(e) => Err(CalcError::EvaluateErr(format!(
-            "Funkce comb využívá funkci nck, která skončila s chybou: {e}"
-        ))),
     }
 }
 
@@ -115,9 +144,10 @@ pub fn comb_internal(n: i64, k: i64, repetition: bool) -> Result<Value, CalcErro
 pub fn nck(n: Value, k: Value) -> Result<Value, CalcError> {
     macro_rules! param_type_error {
         () => {
-            Err(CalcError::FuncCallErr(s!(
-                "Funkce nck(n,k) očekává celočíselné parametry"
-            )))
+            Err(invalid_call(
+                "nck",
+                &format!("{}, {}", n.type_name(), k.type_name()),
+            ))
         };
     }
     if let Value::Integer(n_int) = n {
@@ -133,17 +163,11 @@ pub fn nck(n: Value, k: Value) -> Result<Value, CalcError> {
 
 fn nck_internal(n: i64, k: i64) -> Result<Value, CalcError> {
     if n < 0 {
-        Err(CalcError::FuncCallErr(s!(
-            "Chyba: nck(n,k): n musí být >= 0"
-        )))
+        Err(requirement("nck(n, k)", "n >= 0"))
     } else if k < 0 {
-        Err(CalcError::FuncCallErr(s!(
-            "Chyba: nck(n,k): k musí být >= 0"
-        )))
+        Err(requirement("nck(n, k)", "k >= 0"))
     } else if n < k {
-        Err(CalcError::FuncCallErr(s!(
-            "Chyba: nck(n,k) vyžaduje: n >= k"
-        )))
+        Err(requirement("nck(n, k)", "n >= k"))
     } else if n == k || n == 0 {
         Ok(Value::Integer(1))
     } else {
@@ -152,11 +176,7 @@ fn nck_internal(n: i64, k: i64) -> Result<Value, CalcError> {
         let n_min_k = n - k;
         let k = if n_min_k < k { n_min_k } else { k };
 
-        let denominator = fact(Value::Integer(k)).map_err(|e| {
-            CalcError::EvaluateErr(format!(
-                "Funkce nck využívá faktoriál, který skončil s chybou: {e}"
-            ))
-        })?;
+        let denominator = fact(Value::Integer(k))?;
 
         let mut numerator = BigInt::from(n);
         for i in (n - k + 1)..n {
@@ -170,16 +190,15 @@ pub fn fact(val: Value) -> Result<Value, CalcError> {
     const FACT_MAX: i64 = 100;
 
     match val {
-        Value::Nothing => Err(CalcError::FuncCallErr(s!(
-            "fact(Nothing) není platné volání funkce"
-        ))),
+        Value::Nothing => Err(invalid_call("fact", "Nothing")),
         Value::Integer(n) => {
             return if n == 0 {
                 Ok(Value::Integer(1))
             } else if n < 0 {
-                Err(CalcError::FuncCallErr(format!(
-                    "fact({n}) není platné volání funkce. Očekává se nezáporné číslo"
-                )))
+                Err(CalcError::EvaluateErr(ErrorKind::DomainError {
+                    func: s!("fact"),
+                    arg: n.to_string(),
+                }))
             } else if n <= FACT_MAX {
                 let mut val = BigInt::from(n);
                 for i in 2..n {
@@ -187,241 +206,222 @@ pub fn fact(val: Value) -> Result<Value, CalcError> {
                 }
                 Value::BigInt(val).simplify_type_move()
             } else {
-                Err(CalcError::FuncCallErr(format!(
-                    "Nelze faktoriálovat čísla větší než {FACT_MAX}"
-                )))
+                Err(CalcError::EvaluateErr(ErrorKind::Overflow))
             }
         }
         Value::BigInt(n) => {
             return if let Some(int) = n.to_i64() {
                 fact(Value::Integer(int))
+            } else if n.is_negative() {
+                Err(CalcError::EvaluateErr(ErrorKind::DomainError {
+                    func: s!("fact"),
+                    arg: n.to_string(),
+                }))
             } else {
-                Err(CalcError::FuncCallErr(s!("fact(BigInt) není platné volání funkce. Není povoleno faktoriálovat velká čísla")))
+                Err(CalcError::EvaluateErr(ErrorKind::Overflow))
             }
         }
-        Value::Rational(_) => Err(CalcError::FuncCallErr(s!(
-            "fact(Rational) není platné volání funkce"
-        ))),
-        Value::Real(_) => Err(CalcError::FuncCallErr(s!(
-            "fact(Real) není platné volání funkce"
-        ))),
-        Value::Text(_) => Err(CalcError::FuncCallErr(s!(
-            "fact(Text) není platné volání funkce"
-        ))),
-        Value::Bool(_) => Err(CalcError::FuncCallErr(s!(
-            "fact(Bool) není platné volání funkce"
-        ))),
+        Value::Rational(_) => Err(invalid_call("fact", "Rational")),
+        Value::Real(_) => Err(invalid_call("fact", "Real")),
+        Value::Decimal(_) => Err(invalid_call("fact", "Decimal")),
+        Value::PiMultiple(_) => Err(invalid_call("fact", "PiMultiple")),
+        Value::Surd(_) => Err(invalid_call("fact", "Surd")),
+        Value::Complex(_) => Err(invalid_call("fact", "Complex")),
+        Value::Text(_) => Err(invalid_call("fact", "Text")),
+        Value::Bool(_) => Err(invalid_call("fact", "Bool")),
+        Value::List(_) => Err(invalid_call("fact", "List")),
     }
 }
 
-/// Sinus - radiány
-pub fn sin(rads: Value) -> Result<Value, CalcError> {
-    // Radiány bývají iracionální číslo. Kromě hodnoty 0.
-    match rads {
-        Value::Nothing => Err(CalcError::FuncCallErr(s!(
-            "sin(Nothing) není platné volání funkce"
-        ))),
-        Value::Integer(i) => {
-            return if i == 0 {
-                Ok(Value::Integer(0))
-            } else {
-                Ok(Value::Real((i as f64).sin()))
-            }
-        }
-        Value::BigInt(i) => sin(Value::Integer((i % 360_i64).to_i64().unwrap())),
-        Value::Rational(r) => Ok(Value::Real(
-            r.to_real()
-                .ok_or(CalcError::EvaluateErr(s!(
-                    "Nepodařilo se zlomek převést na reálné číslo"
-                )))?
-                .sin(),
-        )),
-        Value::Real(r) => Ok(Value::Real(r.sin())),
-        Value::Text(_) => Err(CalcError::FuncCallErr(s!(
-            "sin(Text) není platné volání funkce"
-        ))),
-        Value::Bool(_) => Err(CalcError::FuncCallErr(s!(
-            "sin(Bool) není platné volání funkce"
-        ))),
-    }
+/// Největší z parametrů. Má-li největší hodnotu více parametrů, vrátí se první z nich.
+pub fn max(params: &[Value]) -> Result<Value, CalcError> {
+    let idx = extreme_idx(params, Ordering::Greater)?;
+    Ok(params[idx].clone())
 }
 
-/// Sinus - radiány. Do parametru se ale nemusí dosazovat iracionální koeficient pí.
-/// Pomáhá to udržet přesnost. Např. pi/2 se nedá reprezentovat přesně, ale 1/2 ano.
-pub fn sinpi(rads_pi: Value) -> Result<Value, CalcError> {
-    fn sinpi_err() -> CalcError {
-        CalcError::FuncCallErr(
-            "Funkce sinpi vyžaduje jako parametr: int, bigint, real, nebo rational".into(),
-        )
-    }
+/// Nejmenší z parametrů. Má-li nejmenší hodnotu více parametrů, vrátí se první z nich.
+pub fn min(params: &[Value]) -> Result<Value, CalcError> {
+    let idx = extreme_idx(params, Ordering::Less)?;
+    Ok(params[idx].clone())
+}
 
-    match rads_pi {
-        Value::Nothing => Err(sinpi_err()),
-        Value::Integer(i) => sinpi_rat(Rational::new(i, 1)),
-        Value::BigInt(bi) => sinpi_rat(Rational::from_bigint(bi)),
-        Value::Rational(r) => sinpi_rat(r),
-        Value::Real(re) => Ok(Value::Real((re * PI).sin())),
-        Value::Text(_) => Err(sinpi_err()),
-        Value::Bool(_) => Err(sinpi_err()),
-    }
+/// Pořadí (od 1) největšího z parametrů
+pub fn argmax(params: &[Value]) -> Result<Value, CalcError> {
+    Ok(Value::Integer(
+        extreme_idx(params, Ordering::Greater)? as i64 + 1,
+    ))
 }
 
-fn sinpi_rat(rat: Rational) -> Result<Value, CalcError> {
-    todo!()
-    // let rat = rat.reduce_move();
-
-    // if rat == Rational::new(1, 6) {
-    //     Ok(rat!(1 / 2))
-    // } else if rat == Rational::new(1, 2) {
-    //     Ok(Value::Integer(1))
-    // } else if rat == Rational::new(3, 2) {
-    //     Ok(Value::Integer(-1))
-    // } else if rat.denominator == BigInt::one() {
-    //     let a = rat.numerator % 2;
-    //     if (a == BigInt::ZERO) {
-    //         Ok(Value::Integer(1))
-    //     } else if (a == BigInt::one()) {
-    //     }
-    // } else {
-    //     let re = rat.to_real().ok_or(CalcError::ConvertToDoubleErr)?;
-    //     Ok(Value::Real((re * PI).sin()))
-    // }
+/// Pořadí (od 1) nejmenšího z parametrů
+pub fn argmin(params: &[Value]) -> Result<Value, CalcError> {
+    Ok(Value::Integer(
+        extreme_idx(params, Ordering::Less)? as i64 + 1,
+    ))
 }
 
-/// Sinus - stupně
-pub fn sind(degs: Value) -> Result<Value, CalcError> {
-    match degs {
-        Value::Nothing => Err(CalcError::FuncCallErr(s!(
-            "sind(Nothing) není platné volání funkce"
-        ))),
-        Value::Integer(i) => Ok(sin_values_match_deg(i % 360)),
-        Value::BigInt(i) => Ok(sin_values_match_deg((i % 360_i64).to_i64().unwrap())),
-        Value::Rational(r) => Ok(Value::Real(
-            r.to_real()
-                .ok_or(CalcError::EvaluateErr(s!(
-                    "Nepodařilo se zlomek převést na reálné číslo"
-                )))?
-                .to_radians()
-                .sin(),
-        )),
-        Value::Real(r) => Ok(Value::Real(r.to_radians().sin())),
-        Value::Text(_) => Err(CalcError::FuncCallErr(s!(
-            "sind(Text) není platné volání funkce"
-        ))),
-        Value::Bool(_) => Err(CalcError::FuncCallErr(s!(
-            "sind(Bool) není platné volání funkce"
-        ))),
+/// Omezí hodnotu x na interval [low, high]
+pub fn clamp(x: Value, low: Value, high: Value) -> Result<Value, CalcError> {
+    if compare_params(&low, &high)? == Ordering::Greater {
+        let condition = format!(
+            "low <= high (low = {}, high = {})",
+            low.to_plain_string(),
+            high.to_plain_string()
+        );
+        return Err(requirement("clamp(x, low, high)", &condition));
     }
+    return Ok(if compare_params(&x, &low)? == Ordering::Less {
+        low
+    } else if compare_params(&x, &high)? == Ordering::Greater {
+        high
+    } else {
+        x
+    });
 }
 
-fn sin_values_match_deg(deg_0_360: i64) -> Value {
-    match deg_0_360 {
-        // TODO: Doplnit odmocniny ze dvou, tří. Az se zavede novy typ value
-        0 => Value::Integer(0),
-        30 => Value::Rational(Rational::new(1, 2)),
-        90 => Value::Integer(1),
-        150 => Value::Rational(Rational::new(1, 2)),
-        180 => Value::Integer(0),
-        210 => Value::Rational(Rational::new(-1, 2)),
-        270 => Value::Integer(-1),
-        330 => Value::Rational(Rational::new(-1, 2)),
-        _ => Value::Real((deg_0_360 as f64).to_radians().sin()),
+/// Seřadí parametry vzestupně. Stejné hodnoty si zachovají původní pořadí.
+pub fn sort(params: &[Value]) -> Result<Value, CalcError> {
+    // Předem se ověří, že jsou všechny hodnoty porovnatelné (parametry jsou čísla, takže
+    // neporovnatelné je jen NaN), samotné řazení už pak neselže
+    for param in params {
+        compare_params(param, param)?;
     }
+    let mut sorted = params.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    Ok(Value::List(sorted))
 }
 
-fn cos_values_match_deg(deg_0_360: i64) -> Value {
-    match deg_0_360 {
-        // TODO: Doplnit odmocniny ze dvou, tří. Az se zavede novy typ value
-        0 => Value::Integer(1),
-        60 => rat!(1 / 2),
-        90 => Value::Integer(0),
-        120 => rat!(-1 / 2),
-        180 => Value::Integer(-1),
-        240 => rat!(-1 / 2),
-        270 => Value::Integer(0),
-        300 => rat!(1 / 2),
-        _ => Value::Real((deg_0_360 as f64).to_radians().sin()),
+/// Index prvního parametru, který je největší (wanted = Greater) nebo nejmenší (Less)
+fn extreme_idx(params: &[Value], wanted: Ordering) -> Result<usize, CalcError> {
+    if params.is_empty() {
+        return Err(CalcError::FuncCallErr(ErrorKind::ArityMismatch {
+            name: s!(if wanted == Ordering::Greater {
+                "max"
+            } else {
+                "min"
+            }),
+            expected: Arity::AtLeast(1),
+            got: 0,
+        }));
     }
+    let mut best = 0;
+    for (idx, param) in params.iter().enumerate() {
+        if compare_params(param, &params[best])? == wanted {
+            best = idx;
+        }
+    }
+    Ok(best)
 }
 
-pub fn max(params: &Vec<Value>) -> Result<Value, CalcError> {
-    // TODO: Bude implementováno, až se budou dát hodnoty porovnávat!
-    // Ok(Value::Integer(params.len() as i64))
-    todo!()
+/// Přesné porovnání dvou parametrů funkce. NaN ani hodnoty různých druhů porovnat nelze.
+fn compare_params(a: &Value, b: &Value) -> Result<Ordering, CalcError> {
+    a.partial_cmp(b).ok_or_else(|| {
+        CalcError::EvaluateErr(ErrorKind::NotComparable {
+            left: a.to_plain_string(),
+            right: b.to_plain_string(),
+        })
+    })
 }
 
-pub fn sqrt(val: Value) -> Result<Value, CalcError> {
+/// Druhá odmocnina. Přesná, pokud to jde (i ve tvaru b·√n), jinak v režimu desetinných čísel
+/// (digits) a pro desetinný parametr desetinná, jinak reálná.
+pub fn sqrt(val: Value, digits: Option<u32>) -> Result<Value, CalcError> {
+    // Odmocnina záporného čísla je imaginární: sqrt(-x) = sqrt(x)·i, přesná, pokud je
+    // přesná sqrt(x)
+    if val.is_negative_number() {
+        return sqrt((-val)?, digits)? * Value::Complex(Complex::i());
+    }
     return match val {
-        Value::Nothing => Err(CalcError::FuncCallErr(s!(
-            "sqrt(Nothing) není platné volání funkce"
-        ))),
-        Value::Integer(input_i) => {
-            let i_real = input_i as f64;
-            let result_real = i_real.sqrt();
-            let result_int = result_real as i64;
-            return if result_int.pow(2u32) == input_i {
-                Ok(Value::Integer(result_int))
-            } else {
-                Ok(Value::Real(result_real))
-            };
+        Value::Nothing => Err(invalid_call("sqrt", "Nothing")),
+        Value::Integer(i) => sqrt(Value::Rational(Rational::from_int(i)), digits),
+        Value::BigInt(i) => sqrt(Value::Rational(Rational::from_bigint(i)), digits),
+        // Odmocnina zlomku, který není druhou mocninou, je iracionální, zůstane ale přesná
+        // ve tvaru b·√n. Jen když nejde odmocňované číslo rozložit, počítá se nepřesně.
+        Value::Rational(q) => match Surd::sqrt(&q) {
+            Some(root) => Ok(root),
+            None => inexact_sqrt(Value::Rational(q), digits),
+        },
+        Value::Real(r) => Ok(Value::Real(r.sqrt())),
+        x @ (Value::Decimal(_) | Value::PiMultiple(_) | Value::Surd(_)) => inexact_sqrt(x, digits),
+        Value::Complex(c) => c.sqrt().into_value(),
+        Value::Text(_) => Err(invalid_call("sqrt", "Text")),
+        Value::Bool(_) => Err(invalid_call("sqrt", "Bool")),
+        Value::List(_) => Err(invalid_call("sqrt", "List")),
+    };
+}
+
+/// Odmocnina nezáporného čísla, kterou nejde spočítat přesně
+fn inexact_sqrt(x: Value, digits: Option<u32>) -> Result<Value, CalcError> {
+    if let Some(digits) = Decimal::precision_for(&[&x], digits) {
+        if let Some(root) = Decimal::working(&x, digits).and_then(|d| d.sqrt()) {
+            return Ok(Value::Decimal(root.with_digits(digits)));
         }
-        Value::BigInt(input_i) => {
-            let i_real = input_i.to_f64().ok_or(CalcError::EvaluateErr(s!(
-                "Nepodařilo se odmocnit velké celé číslo"
-            )))?;
-            let result_real = i_real.sqrt();
-            let result_int = BigInt::from_f64(result_real);
-
-            if let Some(res_int) = result_int {
-                if res_int.pow(2u32) == input_i {
-                    return Ok(Value::BigInt(res_int));
-                }
-            }
-            return Ok(Value::Real(result_real));
+    }
+    Ok(Value::Real(x.as_real()?.sqrt()))
+}
+
+/// Počet platných číslic reálného čísla (f64)
+const REAL_DIGITS: u32 = 17;
+
+/// Převede číslo na desetinné: dec(x) s přesností z režimu desetinných čísel (jinak
+/// DEFAULT_DIGITS), dec(x, číslice) se zadanou přesností. Reálné číslo má jen REAL_DIGITS
+/// platných číslic, víc jich ani jako desetinné mít nebude.
+pub fn dec(params: &[Value], settings: &CalcSettings) -> Result<Value, CalcError> {
+    let digits = match params {
+        [_] => settings.decimal_digits.unwrap_or(DEFAULT_DIGITS),
+        [_, Value::Integer(d)] if (1..=i64::from(MAX_DIGITS)).contains(d) => *d as u32,
+        [_, d] => {
+            return Err(CalcError::EvaluateErr(ErrorKind::DigitsOutOfRange {
+                subject: Some(s!("dec")),
+                min: 1,
+                max: MAX_DIGITS,
+                got: d.to_plain_string(),
+            }))
         }
-        Value::Rational(rat) => {
-            let num = sqrt(Value::BigInt(rat.numerator.clone()))?;
-            let den = sqrt(Value::BigInt(rat.denominator.clone()))?;
-            return num / den; // Může znovu vzniknout zlomek, nebo i celé nebo reálné číslo
+        _ => {
+            return Err(CalcError::FuncCallErr(ErrorKind::ArityMismatch {
+                name: s!("dec"),
+                expected: Arity::Range(1, 2),
+                got: params.len(),
+            }))
         }
-        Value::Real(r) => Ok(Value::Real(r.sqrt())),
-        Value::Text(_) => Err(CalcError::FuncCallErr(s!(
-            "sqrt(Text) není platné volání funkce"
-        ))),
-        Value::Bool(_) => Err(CalcError::FuncCallErr(s!(
-            "sqrt(Bool) není platné volání funkce"
-        ))),
     };
+    let decimal = match &params[0] {
+        Value::Real(r) => Rational::from_real(*r)
+            .map(|q| Decimal::from_rational(&q, digits.min(REAL_DIGITS)).with_digits(digits)),
+        Value::Decimal(d) => Some(d.with_digits(digits)),
+        x => Decimal::from_value(x, digits),
+    };
+    decimal.map(Value::Decimal).ok_or_else(|| {
+        CalcError::FuncCallErr(ErrorKind::InvalidCall {
+            func: s!("dec"),
+            args: params[0].to_plain_string(),
+        })
+    })
 }
 
 pub fn cista_mzda(hruba: Value) -> Result<Value, CalcError> {
     match hruba {
-        Value::Nothing => Err(CalcError::FuncCallErr(s!(
-            "cista(Nothing) není platné volání funkce"
-        ))),
+        Value::Nothing => Err(invalid_call("cista", "Nothing")),
         Value::Integer(int) => Ok(Value::Rational(cista_mzda_impl(Rational::new(int, 1))?)),
         Value::BigInt(big_int) => Ok(Value::Rational(cista_mzda_impl(Rational {
             numerator: big_int,
             denominator: BigInt::one(),
         })?)),
         Value::Rational(rational) => Ok(Value::Rational(cista_mzda_impl(rational)?)),
-        Value::Real(_) => Err(CalcError::FuncCallErr(s!(
-            "cista(Real) není platné volání funkce"
-        ))),
-        Value::Text(_) => Err(CalcError::FuncCallErr(s!(
-            "cista(Text) není platné volání funkce"
-        ))),
-        Value::Bool(_) => Err(CalcError::FuncCallErr(s!(
-            "cista(Bool) není platné volání funkce"
-        ))),
+        Value::Real(_) => Err(invalid_call("cista", "Real")),
+        Value::Decimal(_) => Err(invalid_call("cista", "Decimal")),
+        Value::PiMultiple(_) => Err(invalid_call("cista", "PiMultiple")),
+        Value::Surd(_) => Err(invalid_call("cista", "Surd")),
+        Value::Complex(_) => Err(invalid_call("cista", "Complex")),
+        Value::Text(_) => Err(invalid_call("cista", "Text")),
+        Value::Bool(_) => Err(invalid_call("cista", "Bool")),
+        Value::List(_) => Err(invalid_call("cista", "List")),
     }
 }
 
 fn cista_mzda_impl(hruba: Rational) -> Result<Rational, CalcError> {
     if hruba.is_negative() || hruba.numerator == BigInt::zero() {
-        return Err(CalcError::FuncCallErr(s!(
-            "Funkce 'cista' nemůže spočítat čistou mzdu ze záporného čísla ani z nuly"
-        )));
+        return Err(requirement("cista(x)", "x > 0"));
     }
 
     // sazby pojistného
@@ -434,20 +434,18 @@ fn cista_mzda_impl(hruba: Rational) -> Result<Rational, CalcError> {
     let zp = r0045 * hruba.clone(); // zdravotní
 
     // daňový základ = hrubá mzda zaokrouhlená na stovky nahoru
-    let dz =
-        ((hruba.to_real().expect("Selhal převod hodnoty na f64") / 100.0).ceil() * 100.0) as i64;
-    let dz = Rational::new(dz, 1);
+    let hundreds = Rational::new(100, 1);
+    let dz = (hruba.clone() / hundreds.clone()).ceil() * hundreds;
 
     // hranice pro 23% daň (měsíčně, 2025 ~161 000 Kč)
     let hranice_23 = Rational::new(161000, 1);
 
     // daň před slevami
-    let dan: Rational;
-    if compare_positive_rationals(hruba.clone(), hranice_23.clone()) != Ordering::Greater {
-        dan = r015 * dz;
+    let dan = if hruba <= hranice_23 {
+        r015 * dz
     } else {
-        dan = r015 * hranice_23.clone() + r023 * (dz - hranice_23.clone());
-    }
+        r015 * hranice_23.clone() + r023 * (dz - hranice_23.clone())
+    };
 
     // sleva na poplatníka (měsíční)
     let sleva = Rational::new(2570, 1);
@@ -459,14 +457,3 @@ fn cista_mzda_impl(hruba: Rational) -> Result<Rational, CalcError> {
     // čistá mzda
     Ok(hruba - sp - zp - dan_po_sleve)
 }
-
-fn compare_positive_rationals(a: Rational, b: Rational) -> Ordering {
-    assert!(a.numerator > Default::default());
-    assert!(b.numerator > Default::default());
-    assert!(a.denominator > Default::default());
-    assert!(b.denominator > Default::default());
-
-    let left = a.numerator * b.denominator;
-    let right = b.numerator * a.denominator;
-    return left.cmp(&right);
-}
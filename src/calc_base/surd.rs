@@ -0,0 +1,303 @@
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::rational::Rational;
+use crate::calc_base::value::Value;
+use crate::s;
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::Neg;
+
+/// Do jaké hodnoty se hledají dělitelé při rozkladu odmocňovaného čísla. Čísla, která se
+/// do té doby nepodaří rozložit, se odmocní nepřesně.
+const TRIAL_DIVISION_LIMIT: u64 = 100_000;
+
+/// Největší exponent, se kterým se odmocnina mocní přesně
+const MAX_EXACT_EXPONENT: i64 = 1024;
+
+/// Kvadratická iracionalita a + b·√n, kde a, b jsou zlomky a n > 1 je celé číslo bez
+/// čtvercových dělitelů (√8 se zapíše jako 2·√2). Vzniká odmocněním zlomku, který není
+/// druhou mocninou, a vrací ji i sind(45) = √2/2. Sčítání, odčítání, násobení i dělení
+/// čísel se stejným n je přesné, s jiným n se počítá nepřesně.
+#[derive(Debug, Clone)]
+pub struct Surd {
+    pub a: Rational,
+    pub b: Rational,
+    pub n: BigInt,
+}
+
+impl Surd {
+    /// Číslo a + b·√n. Volající musí zaručit, že n nemá čtvercové dělitele.
+    pub fn new(a: Rational, b: Rational, n: i64) -> Surd {
+        Surd {
+            a,
+            b,
+            n: BigInt::from(n),
+        }
+    }
+
+    /// Zlomek jako a + 0·√1, aby se s ním dalo počítat spolu s odmocninami
+    fn from_rational(q: Rational) -> Surd {
+        Surd {
+            a: q,
+            b: Rational::zero(),
+            n: BigInt::one(),
+        }
+    }
+
+    /// Převede číslo na tvar a + b·√n. Reálné a komplexní číslo ani jiné hodnoty převést nelze.
+    pub fn from_value(value: &Value) -> Option<Surd> {
+        match value {
+            Value::Integer(i) => Some(Surd::from_rational(Rational::from_int(*i))),
+            Value::BigInt(i) => Some(Surd::from_rational(Rational::from_bigint(i.clone()))),
+            Value::Rational(q) => Some(Surd::from_rational(q.clone())),
+            Value::Surd(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    /// Převede číslo na co nejjednodušší hodnotu, bez odmocniny je to zlomek nebo celé číslo.
+    pub fn into_value(self) -> Result<Value, CalcError> {
+        if self.b.numerator.is_zero() || self.n.is_one() {
+            return Value::Rational(self.a + self.b).simplify_type_move();
+        }
+        Ok(Value::Surd(Surd {
+            a: self.a.reduce_move(),
+            b: self.b.reduce_move(),
+            n: self.n,
+        }))
+    }
+
+    /// Druhá odmocnina nezáporného zlomku: √(p/q) = s1/s2 · √(m1/m2) = s1/(s2·m2) · √(m1·m2),
+    /// kde p = s1²·m1, q = s2²·m2. Pokud nejde čísla rozložit, vrátí None.
+    pub fn sqrt(q: &Rational) -> Option<Value> {
+        if q.is_negative() {
+            return None;
+        }
+        let q = q.clone().reduce_move();
+        let (numerator, denominator) = (q.numerator.abs(), q.denominator.abs());
+        let (s1, m1) = split_square(&numerator)?;
+        let (s2, m2) = split_square(&denominator)?;
+        Surd {
+            a: Rational::zero(),
+            b: Rational::new_bigint(s1, s2 * &m2),
+            n: m1 * m2,
+        }
+        .into_value()
+        .ok()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.a.numerator.is_zero() && self.b.numerator.is_zero()
+    }
+
+    pub fn to_real(&self) -> Option<f64> {
+        Some(self.a.to_real()? + self.b.to_real()? * self.n.to_f64()?.sqrt())
+    }
+
+    /// Odmocniny mají stejný základ (nebo některé z čísel odmocninu nemá), takže výsledek
+    /// operace má opět tvar a + b·√n
+    fn common_radicand(&self, other: &Surd) -> Option<BigInt> {
+        if self.b.numerator.is_zero() || self.n.is_one() {
+            Some(other.n.clone())
+        } else if other.b.numerator.is_zero() || other.n.is_one() || self.n == other.n {
+            Some(self.n.clone())
+        } else {
+            None
+        }
+    }
+
+    pub fn add(&self, other: &Surd) -> Option<Surd> {
+        Some(Surd {
+            a: self.a.clone() + other.a.clone(),
+            b: self.b.clone() + other.b.clone(),
+            n: self.common_radicand(other)?,
+        })
+    }
+
+    pub fn sub(&self, other: &Surd) -> Option<Surd> {
+        self.add(&-other.clone())
+    }
+
+    /// (a + b√n)(c + d√n) = (ac + bdn) + (ad + bc)√n. Dvě odmocniny bez racionální části
+    /// lze vynásobit i s různým n: b√n · d√m = bd·g · √(n/g · m/g), kde g = nsd(n, m).
+    pub fn mul(&self, other: &Surd) -> Option<Surd> {
+        if let Some(n) = self.common_radicand(other) {
+            let n_rat = Rational::from_bigint(n.clone());
+            return Some(Surd {
+                a: self.a.clone() * other.a.clone() + self.b.clone() * other.b.clone() * n_rat,
+                b: self.a.clone() * other.b.clone() + self.b.clone() * other.a.clone(),
+                n,
+            });
+        }
+        if self.a.numerator.is_zero() && other.a.numerator.is_zero() {
+            let gcd = self.n.gcd(&other.n);
+            return Some(Surd {
+                a: Rational::zero(),
+                b: self.b.clone() * other.b.clone() * Rational::from_bigint(gcd.clone()),
+                n: (&self.n / &gcd) * (&other.n / &gcd),
+            });
+        }
+        None
+    }
+
+    /// Dělení rozšířením sdruženým číslem: 1/(c + d√n) = (c - d√n) / (c² - d²n)
+    pub fn div(&self, other: &Surd) -> Option<Surd> {
+        let conjugate = Surd {
+            a: other.a.clone(),
+            b: -other.b.clone(),
+            n: other.n.clone(),
+        };
+        let norm = other.mul(&conjugate)?.a;
+        if norm.numerator.is_zero() {
+            return None;
+        }
+        let inverse = Surd {
+            a: conjugate.a / norm.clone(),
+            b: conjugate.b / norm,
+            n: conjugate.n,
+        };
+        self.mul(&inverse)
+    }
+
+    /// Mocnina na celé číslo. Pro jiný exponent vrátí None.
+    pub fn pow(&self, exponent: &Surd) -> Option<Surd> {
+        if !exponent.b.numerator.is_zero() && !exponent.n.is_one() {
+            return None;
+        }
+        let exponent = exponent.a.to_bigint()?.to_i64()?;
+        if exponent.abs() > MAX_EXACT_EXPONENT {
+            return None;
+        }
+        let mut result = Surd::from_rational(Rational::from_int(1));
+        let mut base = self.clone();
+        let mut n = exponent.unsigned_abs();
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.mul(&base)?;
+            }
+            base = base.mul(&base)?;
+            n >>= 1;
+        }
+        if exponent < 0 {
+            return Surd::from_rational(Rational::from_int(1)).div(&result);
+        }
+        Some(result)
+    }
+
+    /// Přesné uspořádání. U čísel s různými odmocninami se porovnávají reálné hodnoty.
+    pub fn compare(&self, other: &Surd) -> Option<Ordering> {
+        match self.sub(other) {
+            Some(diff) => Some(diff.sign()),
+            None => self.to_real()?.partial_cmp(&other.to_real()?),
+        }
+    }
+
+    /// Znaménko čísla p + q√n: pokud mají p a q různá znaménka, rozhodne porovnání p² a q²n
+    fn sign(&self) -> Ordering {
+        let sign_a = self.a.cmp(&Rational::zero());
+        let sign_b = self.b.cmp(&Rational::zero());
+        if sign_b == Ordering::Equal || sign_a == sign_b {
+            return if sign_a == Ordering::Equal {
+                sign_b
+            } else {
+                sign_a
+            };
+        }
+        if sign_a == Ordering::Equal {
+            return sign_b;
+        }
+        let a_squared = self.a.clone() * self.a.clone();
+        let b_squared = self.b.clone() * self.b.clone() * Rational::from_bigint(self.n.clone());
+        match a_squared.cmp(&b_squared) {
+            Ordering::Greater => sign_a,
+            Ordering::Less => sign_b,
+            Ordering::Equal => Ordering::Equal,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.sign() == Ordering::Less
+    }
+
+    pub fn abs(&self) -> Surd {
+        if self.is_negative() {
+            -self.clone()
+        } else {
+            self.clone()
+        }
+    }
+}
+
+impl Neg for Surd {
+    type Output = Surd;
+
+    fn neg(self) -> Surd {
+        Surd {
+            a: -self.a,
+            b: -self.b,
+            n: self.n,
+        }
+    }
+}
+
+impl Display for Surd {
+    /// Zapisuje se tak, aby šel výsledek znovu zadat jako výraz: 1/2 + 3/2*sqrt(2)
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let root = match self.b.abs().to_compact_string().as_str() {
+            "1" => format!("sqrt({})", self.n),
+            b => format!("{b}*sqrt({})", self.n),
+        };
+        match (self.a.numerator.is_zero(), self.b.is_negative()) {
+            (true, false) => write!(f, "{root}"),
+            (true, true) => write!(f, "-{root}"),
+            (false, false) => write!(f, "{} + {root}", self.a.to_compact_string()),
+            (false, true) => write!(f, "{} - {root}", self.a.to_compact_string()),
+        }
+    }
+}
+
+/// Rozloží kladné celé číslo x na s²·m, kde m nemá čtvercové dělitele. Dělitele se zkoušejí
+/// do TRIAL_DIVISION_LIMIT. Zbytek, jehož všechny prvočinitele jsou větší než p, je buď
+/// prvočíslo, nebo (je-li menší než p³) součin dvou prvočísel. Jinak vrátí None.
+fn split_square(x: &BigInt) -> Option<(BigInt, BigInt)> {
+    if x.sign() != Sign::Plus {
+        return None;
+    }
+    let mut rest = x.clone();
+    let (mut square, mut free) = (BigInt::one(), BigInt::one());
+    let mut p = BigInt::from(2);
+    while p <= BigInt::from(TRIAL_DIVISION_LIMIT) && &p * &p <= rest {
+        let mut count = 0;
+        while (&rest % &p).is_zero() {
+            rest /= &p;
+            count += 1;
+        }
+        square *= p.pow(count / 2);
+        if count % 2 == 1 {
+            free *= &p;
+        }
+        p += if p == BigInt::from(2) { 1 } else { 2 };
+    }
+    if rest.is_one() {
+        return Some((square, free));
+    }
+    if &p * &p > rest {
+        return Some((square, free * rest));
+    }
+    if rest < p.pow(3) {
+        let root = rest.sqrt();
+        return if &root * &root == rest {
+            Some((square * root, free))
+        } else {
+            Some((square, free * rest))
+        };
+    }
+    None
+}
+
+/// Chyba, když se odmocnina nepodaří převést na reálné číslo
+pub fn surd_to_real_err() -> CalcError {
+    CalcError::EvaluateErr(ErrorKind::NotReal { value: s!("Surd") })
+}
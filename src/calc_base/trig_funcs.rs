@@ -0,0 +1,653 @@
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::complex::Complex;
+use crate::calc_base::decimal::{Decimal, GUARD_DIGITS};
+use crate::calc_base::rational::Rational;
+use crate::calc_base::surd::Surd;
+use crate::calc_base::value::Value;
+use crate::rat;
+use crate::s;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::ToPrimitive;
+use std::f64::consts::PI;
+use std::fmt::Display;
+
+/// Jednotka úhlu. Goniometrické funkce (sin, sind, sinpi) v ní úhel dostávají, cyklometrické
+/// (asin, asind, asinpi) v ní úhel vracejí. Funkce bez přípony (sin, asin) používají jednotku
+/// z nastavení kontextu, výchozí jsou radiány.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AngleUnit {
+    /// Radiány, přesně lze zadat násobek pí: sin(pi/6)
+    #[default]
+    Rad,
+    /// Stupně: sind(30)
+    Deg,
+    /// Grady (gony), pravý úhel má 100 gradů
+    Grad,
+    /// Násobky π radiánů: sinpi(1/6) = sin(pi/6)
+    PiFraction,
+}
+
+impl AngleUnit {
+    /// Jednotky, které lze zadat názvem v nastavení i jako příponu čísla (30deg, 0.5rad)
+    const NAMED: [(&'static str, AngleUnit); 3] = [
+        ("rad", AngleUnit::Rad),
+        ("deg", AngleUnit::Deg),
+        ("grad", AngleUnit::Grad),
+    ];
+
+    /// Jednotka podle názvu: rad, deg nebo grad (nerozlišuje velikost písmen)
+    pub fn from_name(name: &str) -> Option<AngleUnit> {
+        Self::NAMED
+            .into_iter()
+            .find(|(unit_name, _)| unit_name.eq_ignore_ascii_case(name.trim()))
+            .map(|(_, unit)| unit)
+    }
+
+    /// Rozdělí číselný literál s příponou jednotky (30deg) na číslo a jednotku
+    pub fn split_literal(literal: &str) -> Option<(&str, AngleUnit)> {
+        let number_end = literal.trim_end_matches(char::is_alphabetic).len();
+        let (number, suffix) = literal.split_at(number_end);
+        Some((number, AngleUnit::from_name(suffix)?))
+    }
+
+    /// Počet stupňů v jednotce, pokud je to zlomek
+    fn degrees_per_unit(self) -> Option<Rational> {
+        match self {
+            AngleUnit::Rad => None,
+            AngleUnit::Deg => Some(Rational::from_int(1)),
+            AngleUnit::Grad => Some(Rational::new(9, 10)),
+            AngleUnit::PiFraction => Some(Rational::from_int(180)),
+        }
+    }
+
+    /// Úhel jako přesný počet stupňů, pokud to jde (násobek pí v radiánech, zlomek
+    /// v ostatních jednotkách)
+    fn exact_degrees(self, angle: &Value) -> Option<Rational> {
+        match (self.degrees_per_unit(), angle) {
+            (None, Value::PiMultiple(k)) => Some(k.clone() * Rational::from_int(180)),
+            (None, Value::Integer(0)) => Some(Rational::zero()),
+            (None, _) => None,
+            (Some(degrees), _) => Some(exact_rational(angle)? * degrees),
+        }
+    }
+
+    /// Úhel v této jednotce převedený na radiány
+    fn to_radians(self, angle: f64) -> f64 {
+        match self {
+            AngleUnit::Rad => angle,
+            AngleUnit::Deg => angle.to_radians(),
+            AngleUnit::Grad => angle * PI / 200.0,
+            AngleUnit::PiFraction => angle * PI,
+        }
+    }
+
+    /// Úhel v radiánech převedený na tuto jednotku
+    fn radians_to_unit(self, rads: f64) -> Value {
+        Value::Real(match self {
+            AngleUnit::Rad => rads,
+            AngleUnit::Deg => rads.to_degrees(),
+            AngleUnit::Grad => rads * 200.0 / PI,
+            AngleUnit::PiFraction => rads / PI,
+        })
+    }
+
+    /// Desetinný úhel v této jednotce převedený na radiány
+    fn decimal_to_radians(self, angle: &Decimal) -> Decimal {
+        match self.degrees_per_unit() {
+            None => angle.clone(),
+            Some(degrees) => {
+                let half_turns =
+                    Decimal::from_rational(&(degrees / Rational::from_int(180)), angle.digits());
+                angle.mul(&half_turns).mul(&Decimal::pi(angle.digits()))
+            }
+        }
+    }
+
+    /// Desetinný úhel v radiánech převedený na tuto jednotku
+    fn decimal_radians_to_unit(self, rads: &Decimal) -> Decimal {
+        match self.degrees_per_unit() {
+            None => rads.clone(),
+            Some(degrees) => {
+                let unit_rads = Decimal::pi(rads.digits()).mul(&Decimal::from_rational(
+                    &(degrees / Rational::from_int(180)),
+                    rads.digits(),
+                ));
+                rads.checked_div(&unit_rads).unwrap_or_else(|| rads.clone())
+            }
+        }
+    }
+
+    /// Přesný úhel ve stupních převedený na tuto jednotku
+    fn exact_degrees_to_unit(self, degrees: Rational) -> Result<Value, CalcError> {
+        match self.degrees_per_unit() {
+            None => Value::PiMultiple(degrees / Rational::from_int(180)),
+            Some(unit) => Value::Rational(degrees / unit),
+        }
+        .simplify_type_move()
+    }
+
+    /// Úhel zapsaný i s jednotkou do chybových hlášení: pi/2, 90°, 100 grad, 1/2·π
+    fn fmt_angle(self, angle: &Value) -> String {
+        let angle = angle.to_plain_string();
+        match self {
+            AngleUnit::Rad => angle,
+            AngleUnit::Deg => format!("{angle}°"),
+            AngleUnit::Grad => format!("{angle} grad"),
+            AngleUnit::PiFraction => format!("{angle}·π"),
+        }
+    }
+}
+
+impl Display for AngleUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AngleUnit::Rad => write!(f, "radiány"),
+            AngleUnit::Deg => write!(f, "stupně"),
+            AngleUnit::Grad => write!(f, "grady"),
+            AngleUnit::PiFraction => write!(f, "násobky π"),
+        }
+    }
+}
+
+/// Převede úhel z jedné jednotky do jiné. Pokud jde úhel vyjádřit přesně v obou jednotkách
+/// (30° = pi/6), výsledek je přesný.
+pub fn convert_angle(angle: Value, from: AngleUnit, to: AngleUnit) -> Result<Value, CalcError> {
+    if from == to {
+        return Ok(angle);
+    }
+    match (from.exact_degrees(&angle), &angle) {
+        (Some(degrees), _) => to.exact_degrees_to_unit(degrees),
+        (None, Value::Decimal(d)) => {
+            let rads = from.decimal_to_radians(&d.with_digits(d.digits() + GUARD_DIGITS));
+            Ok(Value::Decimal(
+                to.decimal_radians_to_unit(&rads).with_digits(d.digits()),
+            ))
+        }
+        (None, _) => Ok(to.radians_to_unit(from.to_radians(angle.as_real()?))),
+    }
+}
+
+/// Goniometrická funkce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Circular {
+    Sin,
+    Cos,
+    Tan,
+    Cot,
+    Sec,
+    Csc,
+}
+
+impl Circular {
+    fn name(self) -> &'static str {
+        match self {
+            Circular::Sin => "sin",
+            Circular::Cos => "cos",
+            Circular::Tan => "tan",
+            Circular::Cot => "cot",
+            Circular::Sec => "sec",
+            Circular::Csc => "csc",
+        }
+    }
+
+    /// Hodnota funkce spočítaná ze sinu a kosinu úhlu. Pokud by se dělilo nulou, funkce
+    /// v tomto úhlu není definována a vrátí se None.
+    fn eval_from_sin_cos(self, sin: Value, cos: Value) -> Result<Option<Value>, CalcError> {
+        let (numerator, denominator) = match self {
+            Circular::Sin => return Ok(Some(sin)),
+            Circular::Cos => return Ok(Some(cos)),
+            Circular::Tan => (sin, cos),
+            Circular::Cot => (cos, sin),
+            Circular::Sec => (Value::Integer(1), cos),
+            Circular::Csc => (Value::Integer(1), sin),
+        };
+        if is_exact_zero(&denominator) {
+            return Ok(None);
+        }
+        Ok(Some((numerator / denominator)?))
+    }
+
+    fn real(self, rads: f64) -> f64 {
+        match self {
+            Circular::Sin => rads.sin(),
+            Circular::Cos => rads.cos(),
+            Circular::Tan => rads.tan(),
+            Circular::Cot => 1.0 / rads.tan(),
+            Circular::Sec => 1.0 / rads.cos(),
+            Circular::Csc => 1.0 / rads.sin(),
+        }
+    }
+
+    fn complex(self, z: &Complex) -> Result<Complex, CalcError> {
+        match self {
+            Circular::Sin => Ok(z.sin()),
+            Circular::Cos => Ok(z.cos()),
+            Circular::Tan => z.tan(),
+            Circular::Cot => z.cos().checked_div(z.sin()),
+            Circular::Sec => Complex::one().checked_div(z.cos()),
+            Circular::Csc => Complex::one().checked_div(z.sin()),
+        }
+    }
+}
+
+/// Goniometrická funkce úhlu v dané jednotce. Úhly, které odpovídají celému počtu stupňů
+/// z tabulky (násobky 30° a 45°), dávají přesný výsledek: sin(pi/6) = 1/2, tand(60) = sqrt(3).
+/// Komplexní úhel lze zadat jen v radiánech. Ostatní úhly se v režimu desetinných čísel
+/// (digits) a pro desetinný úhel počítají jako desetinná čísla, jinak jako reálná.
+pub fn circular(
+    func: Circular,
+    angle: Value,
+    unit: AngleUnit,
+    digits: Option<u32>,
+) -> Result<Value, CalcError> {
+    let undefined = || {
+        CalcError::EvaluateErr(ErrorKind::DomainError {
+            func: s!(func.name()),
+            arg: unit.fmt_angle(&angle),
+        })
+    };
+    // Přesně zadaný úhel se zmenší do intervalu [0°, 360°) ještě jako zlomek. Velký úhel
+    // převedený na f64 nebo desetinné číslo by ztratil přesnost: sind(10^30) = sind(280).
+    let exact_degrees = unit.exact_degrees(&angle).map(|deg| degrees_0_360(&deg));
+    if let Some(deg) = exact_degrees
+        .as_ref()
+        .and_then(Rational::to_bigint)
+        .and_then(|deg| deg.to_i64())
+        .filter(|deg| deg % 30 == 0 || deg % 45 == 0)
+    {
+        return func
+            .eval_from_sin_cos(sin_values_match_deg(deg), cos_values_match_deg(deg))?
+            .ok_or_else(undefined);
+    }
+    if let Value::Complex(z) = &angle {
+        return match unit {
+            AngleUnit::Rad => func.complex(z)?.into_value(),
+            _ => Err(CalcError::FuncCallErr(ErrorKind::ComplexAngleUnit { unit })),
+        };
+    }
+    let (reduced, reduced_unit) = match exact_degrees {
+        Some(deg) => (Value::Rational(deg), AngleUnit::Deg),
+        None => (angle.clone(), unit),
+    };
+    if let Some(digits) = Decimal::precision_for(&[&reduced], digits) {
+        let sin_cos = Decimal::working(&reduced, digits)
+            .and_then(|angle| reduced_unit.decimal_to_radians(&angle).sin_cos());
+        if let Some((sin, cos)) = sin_cos {
+            let value = func
+                .eval_from_sin_cos(Value::Decimal(sin), Value::Decimal(cos))?
+                .ok_or_else(undefined)?;
+            return Ok(match value {
+                Value::Decimal(d) => Value::Decimal(d.with_digits(digits)),
+                value => value,
+            });
+        }
+    }
+    Ok(Value::Real(
+        func.real(reduced_unit.to_radians(reduced.as_real()?)),
+    ))
+}
+
+/// Cyklometrická funkce (inverzní ke goniometrické)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InverseCircular {
+    Asin,
+    Acos,
+    Atan,
+    Acot,
+    Asec,
+    Acsc,
+}
+
+impl InverseCircular {
+    fn name(self) -> &'static str {
+        match self {
+            InverseCircular::Asin => "asin",
+            InverseCircular::Acos => "acos",
+            InverseCircular::Atan => "atan",
+            InverseCircular::Acot => "acot",
+            InverseCircular::Asec => "asec",
+            InverseCircular::Acsc => "acsc",
+        }
+    }
+
+    /// Leží x v definičním oboru funkce?
+    fn accepts(self, x: f64) -> bool {
+        match self {
+            InverseCircular::Asin | InverseCircular::Acos => x.abs() <= 1.0,
+            InverseCircular::Asec | InverseCircular::Acsc => x.abs() >= 1.0,
+            InverseCircular::Atan | InverseCircular::Acot => true,
+        }
+    }
+
+    /// Přesný úhel ve stupních, pokud je x hodnota z tabulky
+    fn exact_degrees(self, x: &Value) -> Option<Rational> {
+        let right_angle = Rational::from_int(90);
+        let inverse = || (Value::Integer(1) / x.clone()).ok();
+        match self {
+            InverseCircular::Asin => asin_table(x),
+            InverseCircular::Acos => Some(right_angle - asin_table(x)?),
+            InverseCircular::Atan => atan_table(x),
+            InverseCircular::Acot => Some(right_angle - atan_table(x)?),
+            InverseCircular::Asec => Some(right_angle - asin_table(&inverse()?)?),
+            InverseCircular::Acsc => asin_table(&inverse()?),
+        }
+    }
+
+    fn real(self, x: f64) -> f64 {
+        match self {
+            InverseCircular::Asin => x.asin(),
+            InverseCircular::Acos => x.acos(),
+            InverseCircular::Atan => x.atan(),
+            InverseCircular::Acot => PI / 2.0 - x.atan(),
+            InverseCircular::Asec => (1.0 / x).acos(),
+            InverseCircular::Acsc => (1.0 / x).asin(),
+        }
+    }
+
+    /// Úhel v radiánech jako desetinné číslo, pokud ho lze spočítat
+    fn decimal(self, x: &Decimal) -> Option<Decimal> {
+        let one = Decimal::one(x.digits());
+        match self {
+            InverseCircular::Asin => x.asin(),
+            InverseCircular::Acos => x.acos(),
+            InverseCircular::Atan => Some(x.atan()),
+            InverseCircular::Acot => Some(Decimal::pi(x.digits()).div_int(2).sub(&x.atan())),
+            InverseCircular::Asec => one.checked_div(x)?.acos(),
+            InverseCircular::Acsc => one.checked_div(x)?.asin(),
+        }
+    }
+}
+
+/// Cyklometrická funkce, výsledný úhel je v dané jednotce. Pro hodnoty z tabulky je
+/// výsledek přesný: asin(1/2) = pi/6, asind(1/2) = 30, asinpi(1/2) = 1/6.
+pub fn inverse_circular(
+    func: InverseCircular,
+    x: Value,
+    unit: AngleUnit,
+    digits: Option<u32>,
+) -> Result<Value, CalcError> {
+    let real = x.as_real()?;
+    if !func.accepts(real) {
+        return Err(CalcError::EvaluateErr(ErrorKind::DomainError {
+            func: s!(func.name()),
+            arg: x.to_plain_string(),
+        }));
+    }
+    if let Some(deg) = func.exact_degrees(&x) {
+        return unit.exact_degrees_to_unit(deg);
+    }
+    if let Some(digits) = Decimal::precision_for(&[&x], digits) {
+        if let Some(rads) = Decimal::working(&x, digits).and_then(|x| func.decimal(&x)) {
+            let angle = unit.decimal_radians_to_unit(&rads);
+            return Ok(Value::Decimal(angle.with_digits(digits)));
+        }
+    }
+    Ok(unit.radians_to_unit(func.real(real)))
+}
+
+/// Úhel bodu [x, y] od kladné poloosy x v intervalu (-180°, 180°]
+pub fn atan2(y: Value, x: Value, unit: AngleUnit, digits: Option<u32>) -> Result<Value, CalcError> {
+    let (y_real, x_real) = (y.as_real()?, x.as_real()?);
+    let digits = Decimal::precision_for(&[&y, &x], digits);
+    let exact = if x_real == 0.0 {
+        Some(Rational::from_int(match y_real {
+            _ if y_real > 0.0 => 90,
+            _ if y_real < 0.0 => -90,
+            _ => 0,
+        }))
+    } else {
+        (y.clone() / x.clone())
+            .ok()
+            .and_then(|ratio| atan_table(&ratio))
+            .map(|deg| {
+                let half_turn = Rational::from_int(180);
+                match (x_real < 0.0, y_real < 0.0) {
+                    (false, _) => deg,
+                    (true, false) => deg + half_turn,
+                    (true, true) => deg - half_turn,
+                }
+            })
+    };
+    if let Some(deg) = exact {
+        return unit.exact_degrees_to_unit(deg);
+    }
+    if let Some(digits) = digits {
+        if let (Some(y), Some(x)) = (Decimal::working(&y, digits), Decimal::working(&x, digits)) {
+            // x není nula, ta má přesný výsledek
+            let mut rads = y.checked_div(&x).map_or_else(|| y.clone(), |t| t.atan());
+            if x.is_negative() {
+                let half_turn = Decimal::pi(rads.digits());
+                rads = if y.is_negative() {
+                    rads.sub(&half_turn)
+                } else {
+                    rads.add(&half_turn)
+                };
+            }
+            let angle = unit.decimal_radians_to_unit(&rads);
+            return Ok(Value::Decimal(angle.with_digits(digits)));
+        }
+    }
+    Ok(unit.radians_to_unit(y_real.atan2(x_real)))
+}
+
+/// Hyperbolická funkce a funkce k ní inverzní
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hyperbolic {
+    Sinh,
+    Cosh,
+    Tanh,
+    Coth,
+    Sech,
+    Csch,
+    Asinh,
+    Acosh,
+    Atanh,
+    Acoth,
+    Asech,
+    Acsch,
+}
+
+impl Hyperbolic {
+    fn name(self) -> &'static str {
+        match self {
+            Hyperbolic::Sinh => "sinh",
+            Hyperbolic::Cosh => "cosh",
+            Hyperbolic::Tanh => "tanh",
+            Hyperbolic::Coth => "coth",
+            Hyperbolic::Sech => "sech",
+            Hyperbolic::Csch => "csch",
+            Hyperbolic::Asinh => "asinh",
+            Hyperbolic::Acosh => "acosh",
+            Hyperbolic::Atanh => "atanh",
+            Hyperbolic::Acoth => "acoth",
+            Hyperbolic::Asech => "asech",
+            Hyperbolic::Acsch => "acsch",
+        }
+    }
+
+    /// Leží x v definičním oboru funkce?
+    fn accepts(self, x: f64) -> bool {
+        match self {
+            Hyperbolic::Coth | Hyperbolic::Csch | Hyperbolic::Acsch => x != 0.0,
+            Hyperbolic::Acosh => x >= 1.0,
+            Hyperbolic::Atanh => x.abs() < 1.0,
+            Hyperbolic::Acoth => x.abs() > 1.0,
+            Hyperbolic::Asech => x > 0.0 && x <= 1.0,
+            _ => true,
+        }
+    }
+
+    /// Přesná hodnota v nule (u acosh a asech v jedničce)
+    fn exact(self, x: &Value) -> Option<Value> {
+        match (self, x) {
+            (Hyperbolic::Cosh | Hyperbolic::Sech, Value::Integer(0)) => Some(Value::Integer(1)),
+            (
+                Hyperbolic::Sinh | Hyperbolic::Tanh | Hyperbolic::Asinh | Hyperbolic::Atanh,
+                Value::Integer(0),
+            ) => Some(Value::Integer(0)),
+            (Hyperbolic::Acosh | Hyperbolic::Asech, Value::Integer(1)) => Some(Value::Integer(0)),
+            _ => None,
+        }
+    }
+
+    fn real(self, x: f64) -> f64 {
+        match self {
+            Hyperbolic::Sinh => x.sinh(),
+            Hyperbolic::Cosh => x.cosh(),
+            Hyperbolic::Tanh => x.tanh(),
+            Hyperbolic::Coth => 1.0 / x.tanh(),
+            Hyperbolic::Sech => 1.0 / x.cosh(),
+            Hyperbolic::Csch => 1.0 / x.sinh(),
+            Hyperbolic::Asinh => x.asinh(),
+            Hyperbolic::Acosh => x.acosh(),
+            Hyperbolic::Atanh => x.atanh(),
+            Hyperbolic::Acoth => (1.0 / x).atanh(),
+            Hyperbolic::Asech => (1.0 / x).acosh(),
+            Hyperbolic::Acsch => (1.0 / x).asinh(),
+        }
+    }
+
+    /// Hodnota jako desetinné číslo, pokud ji lze spočítat (exp velkého x nelze)
+    fn decimal(self, x: &Decimal) -> Option<Decimal> {
+        let one = Decimal::one(x.digits());
+        let sinh = || Some(x.exp()?.sub(&(-x.clone()).exp()?).div_int(2));
+        let cosh = || Some(x.exp()?.add(&(-x.clone()).exp()?).div_int(2));
+        // asinh(-x) = -asinh(x), kladný argument se počítá bez odčítání blízkých čísel
+        let asinh = |x: &Decimal| {
+            let abs = x.abs();
+            let result = abs.add(&abs.mul(&abs).add(&one).sqrt()?).ln()?;
+            Some(if x.is_negative() { -result } else { result })
+        };
+        let acosh = |x: &Decimal| x.add(&x.mul(x).sub(&one).sqrt()?).ln();
+        let atanh = |x: &Decimal| Some(one.add(x).checked_div(&one.sub(x))?.ln()?.div_int(2));
+        match self {
+            Hyperbolic::Sinh => sinh(),
+            Hyperbolic::Cosh => cosh(),
+            Hyperbolic::Tanh => sinh()?.checked_div(&cosh()?),
+            Hyperbolic::Coth => cosh()?.checked_div(&sinh()?),
+            Hyperbolic::Sech => one.checked_div(&cosh()?),
+            Hyperbolic::Csch => one.checked_div(&sinh()?),
+            Hyperbolic::Asinh => asinh(x),
+            Hyperbolic::Acosh => acosh(x),
+            Hyperbolic::Atanh => atanh(x),
+            Hyperbolic::Acoth => atanh(&one.checked_div(x)?),
+            Hyperbolic::Asech => acosh(&one.checked_div(x)?),
+            Hyperbolic::Acsch => asinh(&one.checked_div(x)?),
+        }
+    }
+}
+
+/// Hyperbolická funkce. Parametr není úhel, proto nemá varianty ve stupních. V režimu
+/// desetinných čísel (digits) a pro desetinný parametr je výsledek desetinné číslo.
+pub fn hyperbolic(func: Hyperbolic, x: Value, digits: Option<u32>) -> Result<Value, CalcError> {
+    let real = x.as_real()?;
+    if !func.accepts(real) {
+        return Err(CalcError::EvaluateErr(ErrorKind::DomainError {
+            func: s!(func.name()),
+            arg: x.to_plain_string(),
+        }));
+    }
+    if let Some(exact) = func.exact(&x) {
+        return Ok(exact);
+    }
+    if let Some(digits) = Decimal::precision_for(&[&x], digits) {
+        // Pro malé x se odčítají blízká čísla (e^x - e^-x), proto se počítá s více číslicemi
+        let extra = Decimal::from_value(&x, digits).map_or(0, |x| (-x.magnitude()).max(0) as u32);
+        if let Some(result) = Decimal::working(&x, digits + extra).and_then(|x| func.decimal(&x)) {
+            return Ok(Value::Decimal(result.with_digits(digits)));
+        }
+    }
+    Ok(Value::Real(func.real(real)))
+}
+
+/// Číslo, které lze vyjádřit zlomkem
+fn exact_rational(value: &Value) -> Option<Rational> {
+    match value {
+        Value::Integer(i) => Some(Rational::from_int(*i)),
+        Value::BigInt(i) => Some(Rational::from_bigint(i.clone())),
+        Value::Rational(q) => Some(q.clone()),
+        _ => None,
+    }
+}
+
+fn is_exact_zero(value: &Value) -> bool {
+    matches!(value, Value::Integer(0))
+}
+
+/// Úhel ve stupních převedený do intervalu [0, 360). Počítá se přesně, i pro velké úhly.
+fn degrees_0_360(degrees: &Rational) -> Rational {
+    let degrees = degrees.clone().reduce_move();
+    let full_turn = &degrees.denominator * BigInt::from(360);
+    Rational::new_bigint(degrees.numerator.mod_floor(&full_turn), degrees.denominator)
+}
+
+/// Úhel z intervalu [-90°, 90°], jehož sinus je x, pokud je x hodnota z tabulky
+fn asin_table(x: &Value) -> Option<Rational> {
+    [-90, -60, -45, -30, 0, 30, 45, 60, 90]
+        .into_iter()
+        .find(|deg: &i64| sin_values_match_deg(deg.rem_euclid(360)) == *x)
+        .map(Rational::from_int)
+}
+
+/// Úhel z intervalu (-90°, 90°), jehož tangens je x, pokud je x hodnota z tabulky
+fn atan_table(x: &Value) -> Option<Rational> {
+    [-60, -45, -30, 0, 30, 45, 60]
+        .into_iter()
+        .find(|deg: &i64| {
+            let deg = deg.rem_euclid(360);
+            let tan = Circular::Tan
+                .eval_from_sin_cos(sin_values_match_deg(deg), cos_values_match_deg(deg));
+            matches!(tan, Ok(Some(tan)) if tan == *x)
+        })
+        .map(Rational::from_int)
+}
+
+fn sin_values_match_deg(deg_0_360: i64) -> Value {
+    match deg_0_360 {
+        0 => Value::Integer(0),
+        30 => rat!(1 / 2),
+        45 => half_sqrt(1, 2),
+        60 => half_sqrt(1, 3),
+        90 => Value::Integer(1),
+        120 => half_sqrt(1, 3),
+        135 => half_sqrt(1, 2),
+        150 => rat!(1 / 2),
+        180 => Value::Integer(0),
+        210 => rat!(-1 / 2),
+        225 => half_sqrt(-1, 2),
+        240 => half_sqrt(-1, 3),
+        270 => Value::Integer(-1),
+        300 => half_sqrt(-1, 3),
+        315 => half_sqrt(-1, 2),
+        330 => rat!(-1 / 2),
+        _ => Value::Real((deg_0_360 as f64).to_radians().sin()),
+    }
+}
+
+fn cos_values_match_deg(deg_0_360: i64) -> Value {
+    match deg_0_360 {
+        0 => Value::Integer(1),
+        30 => half_sqrt(1, 3),
+        45 => half_sqrt(1, 2),
+        60 => rat!(1 / 2),
+        90 => Value::Integer(0),
+        120 => rat!(-1 / 2),
+        135 => half_sqrt(-1, 2),
+        150 => half_sqrt(-1, 3),
+        180 => Value::Integer(-1),
+        210 => half_sqrt(-1, 3),
+        225 => half_sqrt(-1, 2),
+        240 => rat!(-1 / 2),
+        270 => Value::Integer(0),
+        300 => rat!(1 / 2),
+        315 => half_sqrt(1, 2),
+        330 => half_sqrt(1, 3),
+        _ => Value::Real((deg_0_360 as f64).to_radians().cos()),
+    }
+}
+
+/// Hodnota ±√n/2, kterou nabývají goniometrické funkce v násobcích 30° a 45°
+fn half_sqrt(sign: i64, n: i64) -> Value {
+    Value::Surd(Surd::new(Rational::zero(), Rational::new(sign, 2), n))
+}
@@ -1,5 +1,12 @@
-use crate::base::CalcError;
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::complex::Complex;
+use crate::calc_base::decimal::Decimal;
+use crate::calc_base::lexer::{self, Token, TokenKind};
+use crate::calc_base::locale::Locale;
+use crate::calc_base::pi_multiple::{fmt_pi_multiple, pi_multiple_to_real, pi_to_real_err};
 use crate::calc_base::rational::Rational;
+use crate::calc_base::settings::CalcSettings;
+use crate::calc_base::surd::{surd_to_real_err, Surd};
 use crate::calc_base::*;
 use crate::s;
 use num_traits::cast::ToPrimitive;
@@ -8,18 +15,30 @@ use std::fmt::Display;
 
 /// Hodnota, se kterou se pracuje při výpočtu matematického výrazu, může mít různé typy.
 /// Jsou na ní definovány matematické operace +,-,*,/, pow
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum Value {
+    #[default]
     Nothing,
     Integer(i64),
     BigInt(BigInt),
     Rational(Rational),
     Real(f64),
+    /// Desetinné číslo se zvolenou přesností (funkce dec, režim desetinných čísel)
+    Decimal(Decimal),
+    /// Racionální násobek π (např. pi/6), na reálné číslo se převádí až při výpisu
+    PiMultiple(Rational),
+    /// Odmocnina a + b·√n, přesná jako zlomek (např. sqrt(2), sind(45))
+    Surd(Surd),
+    /// Komplexní číslo, přesné (části jsou zlomky), nebo nepřesné (části jsou f64)
+    Complex(Complex),
     Text(String),
     Bool(bool),
+    /// Více hodnot najednou, např. výsledek funkce sort. Se seznamem se nedá počítat.
+    List(Vec<Value>),
 }
 
 // Zkrácený zápis vytvoření racionálního čísla
+#[doc(hidden)]
 #[macro_export]
 macro_rules! rat {
     ($a:literal / $b:literal) => {
@@ -31,10 +50,7 @@ macro_rules! rat {
 pub mod consts {
     use crate::calc_base::value::Value;
 
-    pub static PI: Value = Value::Real(std::f64::consts::PI);
     pub static E: Value = Value::Real(std::f64::consts::E);
-    pub static SQRT2: Value = Value::Real(std::f64::consts::SQRT_2);
-    pub static SQRT3: Value = Value::Real(1.73205080756887729352744634150587236694280525381038);
     pub static I64MAX: Value = Value::Integer(i64::MAX);
     pub static I64MIN: Value = Value::Integer(i64::MIN);
 }
@@ -50,88 +66,131 @@ mod system_consts {
 }
 
 /// Pokud je name platný název konstanty, vrátí se její hodnota, jinak se vrátí None
-fn is_named_const(name: &str) -> Option<Value> {
+pub fn is_named_const(name: &str) -> Option<Value> {
     return match name.trim().to_lowercase().as_str() {
-        "pi" => Some(consts::PI.clone()),
+        "pi" => Some(Value::PiMultiple(Rational::from_int(1))),
         "e" => Some(consts::E.clone()),
-        "sqrt2" => Some(consts::SQRT2.clone()),
-        "sqrt3" => Some(consts::SQRT3.clone()),
+        "sqrt2" => Some(Value::Surd(Surd::new(
+            Rational::zero(),
+            Rational::from_int(1),
+            2,
+        ))),
+        "sqrt3" => Some(Value::Surd(Surd::new(
+            Rational::zero(),
+            Rational::from_int(1),
+            3,
+        ))),
         "i64max" => Some(consts::I64MAX.clone()),
         "i64min" => Some(consts::I64MIN.clone()),
+        "i" => Some(Value::Complex(Complex::i())),
         _ => None,
     };
 }
 
-fn value_is_string_literal(expr: &str) -> Option<&str> {
-    let expr = expr.trim();
-    let mut is_in_string = false;
+/// Hodnota pojmenované konstanty podle nastavení. V režimu desetinných čísel je e desetinné
+/// číslo s nastavenou přesností, ostatní konstanty jsou přesné a zůstávají stejné.
+pub fn named_const(name: &str, settings: &CalcSettings) -> Option<Value> {
+    match (is_named_const(name)?, settings.decimal_digits) {
+        (Value::Real(_), Some(digits)) => Some(Value::Decimal(Decimal::e(digits))),
+        (value, _) => Some(value),
+    }
+}
 
-    let len = expr.chars().count();
-    return if len >= 2 && expr.starts_with('"') && expr.ends_with('"') {
-        for (idx, c) in expr.chars().enumerate() {
-            if c == '"' {
-                is_in_string = !is_in_string;
-            }
+/// Začíná text jako číselný literál? Tedy číslicí nebo desetinnou tečkou, případně až po
+/// znaménku (-5, +.5). Takový text se musí načíst jako číslo, ne jako proměnná.
+fn is_number_literal(text: &str) -> bool {
+    let unsigned = text.strip_prefix(['+', '-']).unwrap_or(text);
+    unsigned
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_digit() || c == '.')
+}
 
-            if idx < len - 1 && !is_in_string {
-                return None;
-            }
-        }
-        Some(&expr[1..expr.len() - 1])
-    } else {
-        None
-    };
+/// Pokud je celý výraz jediný textový literál, vrátí jeho obsah (bez uvozovek)
+fn value_is_string_literal(expr: &str) -> Option<String> {
+    match lexer::tokenize(expr, Locale::default()).ok()?.as_slice() {
+        [Token {
+            kind: TokenKind::Text(text),
+            ..
+        }] => Some(text.clone()),
+        _ => None,
+    }
 }
 
 impl Value {
-    #[allow(unused)]
+    /// Název typu hodnoty pro chybová hlášení
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Nothing => "Nothing",
+            Value::Integer(_) => "Integer",
+            Value::BigInt(_) => "BigInt",
+            Value::Rational(_) => "Rational",
+            Value::Real(_) => "Real",
+            Value::Decimal(_) => "Decimal",
+            Value::PiMultiple(_) => "PiMultiple",
+            Value::Surd(_) => "Surd",
+            Value::Complex(_) => "Complex",
+            Value::Text(_) => "Text",
+            Value::Bool(_) => "Bool",
+            Value::List(_) => "List",
+        }
+    }
+
+    /// Hodnota do chybového hlášení i s typem: Text "abc"
+    pub(crate) fn describe(&self) -> String {
+        format!("{} {}", self.type_name(), self.to_plain_string())
+    }
+
+    /// Převede číslo na f64. Hodnota, která není reálné číslo (text, komplexní číslo...),
+    /// skončí chybou.
     pub fn as_real(&self) -> Result<f64, CalcError> {
+        let not_real = || {
+            CalcError::EvaluateErr(ErrorKind::NotReal {
+                value: self.describe(),
+            })
+        };
         match self {
-            Value::Nothing => Err(CalcError::EvaluateErr(s!(
-                "Hodnota Nothing nelze převést na reálné číslo!"
-            ))),
             Value::Integer(i) => Ok(*i as f64),
-            Value::BigInt(i) => Ok(i.to_f64().ok_or(CalcError::EvaluateErr(s!(
-                "ln: Nepodařilo se převést BigInt na reálné číslo"
-            )))?),
-            Value::Rational(q) => Ok(q.to_real().ok_or(CalcError::EvaluateErr(s!(
-                "ln: Nepodařilo se převést zlomek na reálné číslo"
-            )))?),
+            Value::BigInt(i) => i.to_f64().ok_or_else(not_real),
+            Value::Rational(q) => q.to_real().ok_or_else(not_real),
             Value::Real(r) => Ok(*r),
-            Value::Text(_) => Err(CalcError::EvaluateErr(s!(
-                "Hodnota Text nelze převést na reálné číslo!"
-            ))),
-            Value::Bool(_) => Err(CalcError::EvaluateErr(s!(
-                "Hodnota Bool nelze převést na reálné číslo!"
-            ))),
+            Value::Decimal(d) => Ok(d.to_f64()),
+            Value::PiMultiple(k) => pi_multiple_to_real(k).ok_or_else(pi_to_real_err),
+            Value::Surd(x) => x.to_real().ok_or_else(surd_to_real_err),
+            Value::Complex(c) => match c.to_f64_parts() {
+                (re, 0.0) => Ok(re),
+                _ => Err(not_real()),
+            },
+            Value::Nothing | Value::Text(_) | Value::Bool(_) | Value::List(_) => Err(not_real()),
         }
     }
 
-    pub fn parse<'expr>(value: &str) -> Result<Self, CalcError> {
+    pub fn parse(value: &str) -> Result<Self, CalcError> {
         let value = value.trim();
         if let Some(string_value) = value_is_string_literal(value) {
-            return Ok(Value::Text(s!(string_value)));
+            return Ok(Value::Text(string_value));
         } else if let Ok(integer) = value.parse::<i64>() {
             return Ok(Value::Integer(integer));
-        } else if let Ok(biginteger) = value.parse::<BigInt>() {
-            return Ok(Value::BigInt(biginteger).simplify_type_move()?);
-        } else if let Ok(ratio) = value.parse::<Rational>() {
-            return Ok(Value::Rational(ratio).simplify_type_move()?);
+        } else if is_number_literal(value) {
+            // Číselný literál se vždy načte přesně, neplatný literál je chyba
+            return Value::Rational(value.parse::<Rational>()?).simplify_type_move();
         } else if let Some(val_const) = is_named_const(value) {
             return Ok(val_const);
         } else if let Ok(boolean) = value.parse::<bool>() {
             return Ok(Value::Bool(boolean));
-        } else if let Ok(real) = value.parse::<f64>() {
-            //Reálná čísla by neměla být parsovatelná z konzole.
-            println!(
-                "Varování! Vstup z konzole se načetl jako reálné číslo. Zpravidla se načítá \
-            racionální číslo. Výsledek nemusí být úplně přesný!"
-            );
-            return Ok(Value::Real(real));
         }
-        Err(CalcError::EvaluateErr(format!(
-            "Výraz '{value}' není platná hodnota."
-        )))
+        Err(CalcError::EvaluateErr(ErrorKind::InvalidValue {
+            text: s!(value),
+        }))
+    }
+
+    /// Číselný literál z tokenu lexeru, zapsaný s desetinným oddělovačem podle locale
+    pub fn parse_number(literal: &str, locale: Locale) -> Result<Self, CalcError> {
+        Value::Rational(Rational::parse_literal(
+            literal,
+            locale.decimal_separator(),
+        )?)
+        .simplify_type_move()
     }
 
     // V některých případech lze považovat BigInteger za Integer. Někdy je zase zlomek
@@ -142,6 +201,17 @@ impl Value {
             Value::Integer(_) => self,
             Value::Text(_) => self,
             Value::Bool(_) => self,
+            Value::Decimal(_) => self,
+            Value::PiMultiple(k) if k.numerator.is_zero() => Value::Integer(0),
+            Value::PiMultiple(k) => Value::PiMultiple(k.reduce_move()),
+            Value::Surd(x) => x.into_value()?,
+            Value::Complex(c) => c.into_value()?,
+            Value::List(items) => Value::List(
+                items
+                    .into_iter()
+                    .map(Value::simplify_type_move)
+                    .collect::<Result<_, _>>()?,
+            ),
             Value::Real(r) => {
                 if r == 0.0 {
                     Value::Integer(0)
@@ -177,22 +247,47 @@ impl Value {
     }
 }
 
-impl Default for Value {
-    fn default() -> Self {
-        Value::Nothing
-    }
-}
-
 impl Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Value::Nothing => write!(f, "{}", "{}"),
+            Value::Nothing => write!(f, "{{}}"),
             Value::Integer(x) => write!(f, "{x}\t(integer)"),
             Value::BigInt(x) => write!(f, "{x}\t(big integer)"),
             Value::Rational(x) => write!(f, "{x}\t(rational)"),
             Value::Real(x) => write!(f, "{x}\t(real)"),
+            Value::Decimal(x) => write!(f, "{x}\t(decimal)"),
+            Value::PiMultiple(k) => match pi_multiple_to_real(k) {
+                Some(r) => write!(f, "{} ≈ {r}\t(pi multiple)", fmt_pi_multiple(k)),
+                None => write!(f, "{}\t(pi multiple)", fmt_pi_multiple(k)),
+            },
+            Value::Surd(x) => write!(f, "{x}\t(surd)"),
+            Value::Complex(x) => write!(f, "{x}\t(complex)"),
             Value::Text(x) => write!(f, "\"{x}\""),
             Value::Bool(x) => write!(f, "{x}"),
+            Value::List(_) => write!(f, "{}\t(list)", self.to_plain_string()),
+        }
+    }
+}
+
+impl Value {
+    /// Hodnota bez označení typu, jak se vypisuje uvnitř seznamu, např. [1, 3 / 2, 2.5]
+    pub fn to_plain_string(&self) -> String {
+        match self {
+            Value::Nothing => s!("{}"),
+            Value::Integer(x) => x.to_string(),
+            Value::BigInt(x) => x.to_string(),
+            Value::Rational(x) => x.to_string(),
+            Value::Real(x) => x.to_string(),
+            Value::Decimal(x) => x.to_string(),
+            Value::PiMultiple(k) => fmt_pi_multiple(k),
+            Value::Surd(x) => x.to_string(),
+            Value::Complex(x) => x.to_string(),
+            Value::Text(x) => format!("\"{x}\""),
+            Value::Bool(x) => x.to_string(),
+            Value::List(items) => {
+                let items: Vec<_> = items.iter().map(Value::to_plain_string).collect();
+                format!("[{}]", items.join(", "))
+            }
         }
     }
 }
@@ -1,8 +1,12 @@
-use crate::base::CalcError;
-use crate::calc_base::rational::Rational;
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::complex::Complex;
+use crate::calc_base::decimal::{Decimal, GUARD_DIGITS};
+use crate::calc_base::pi_multiple::{fmt_pi_multiple, PiTerm};
+use crate::calc_base::rational::{pow_exponent, Rational};
+use crate::calc_base::settings::CalcSettings;
+use crate::calc_base::surd::Surd;
 use crate::calc_base::value::Value;
 use crate::calc_base::*;
-use crate::s;
 use num_traits::cast::ToPrimitive;
 use num_traits::Zero;
 use std::ops::{Add, Mul, Neg, Sub};
@@ -23,16 +27,168 @@ impl Neg for Value {
             Value::BigInt(x) => Ok(Value::BigInt(-x)),
             Value::Rational(x) => Ok(Value::Rational(-x)),
             Value::Real(x) => Ok(Value::Real(-x)),
-            Value::Text(x) => Err(CalcError::EvaluateErr(format!(
-                "Na text {x} nelze aplikovat unární mínus"
-            ))),
-            Value::Bool(x) => Err(CalcError::EvaluateErr(format!(
-                "Na boolean {x} nelze aplikovat unární mínus"
-            ))),
+            Value::Decimal(x) => Ok(Value::Decimal(-x)),
+            Value::PiMultiple(x) => Ok(Value::PiMultiple(-x)),
+            Value::Surd(x) => Ok(Value::Surd(-x)),
+            Value::Complex(x) => Ok(Value::Complex((-x)?)),
+            Value::Text(x) => Err(invalid_operand('-', &Value::Text(x))),
+            Value::Bool(x) => Err(invalid_operand('-', &Value::Bool(x))),
+            Value::List(_) => Err(list_oper_err()),
         };
     }
 }
 
+/// Se seznamem hodnot (např. výsledkem funkce sort) nelze počítat
+fn list_oper_err() -> CalcError {
+    CalcError::EvaluateErr(ErrorKind::ListArithmetic)
+}
+
+/// Binární operátor nejde použít na hodnoty těchto typů
+fn invalid_operands(op: char, lhs: &Value, rhs: &Value) -> CalcError {
+    CalcError::EvaluateErr(ErrorKind::TypeMismatch {
+        op,
+        left_type: lhs.type_name(),
+        right_type: rhs.type_name(),
+    })
+}
+
+/// Operátor nejde použít na hodnotu tohoto typu
+fn invalid_operand(op: char, operand: &Value) -> CalcError {
+    CalcError::EvaluateErr(ErrorKind::UnsupportedOperand {
+        op,
+        operand_type: operand.type_name(),
+    })
+}
+
+/// Největší povolený počet bitů výsledku celočíselné mocniny (asi 315 000 číslic). Větší
+/// mocnina by se počítala dlouho a zabrala by hodně paměti.
+const MAX_POW_BITS: u64 = 1 << 20;
+
+/// Ověří, že mocnina čísla s daným počtem bitů na celočíselný exponent nebude příliš velká.
+/// Mocniny 0, 1 a -1 jsou malé vždy.
+fn check_pow_size(base_bits: u64, exponent: i64) -> Result<(), CalcError> {
+    if base_bits > 1 && base_bits.saturating_mul(exponent.unsigned_abs()) > MAX_POW_BITS {
+        return Err(CalcError::EvaluateErr(ErrorKind::Overflow));
+    }
+    Ok(())
+}
+
+/// Operace, ve které vystupuje komplexní číslo. Oba operandy se převedou na komplexní čísla
+/// a výsledek se zjednoduší (bez imaginární části je to obyčejné číslo).
+fn complex_oper(
+    lhs: Value,
+    rhs: Value,
+    op: char,
+    oper: fn(Complex, Complex) -> Result<Complex, CalcError>,
+) -> Result<Value, CalcError> {
+    match (&lhs, &rhs) {
+        (Value::Nothing, _) | (_, Value::Nothing) => return Ok(Value::Nothing),
+        (Value::List(_), _) | (_, Value::List(_)) => return Err(list_oper_err()),
+        _ => {}
+    }
+    match (Complex::from_value(&lhs), Complex::from_value(&rhs)) {
+        (Some(a), Some(b)) => oper(a, b)?.into_value(),
+        _ => Err(invalid_operands(op, &lhs, &rhs)),
+    }
+}
+
+/// Operace, ve které vystupuje odmocnina (a + b·√n) a zlomek nebo celé číslo, se spočítá
+/// přesně funkcí exact. Pokud to nejde (jiné n nebo reálné číslo), spočítá se nepřesně
+/// funkcí inexact.
+fn surd_oper(
+    lhs: Value,
+    rhs: Value,
+    op: char,
+    exact: fn(&Surd, &Surd) -> Option<Surd>,
+    inexact: fn(f64, f64) -> f64,
+) -> Result<Value, CalcError> {
+    match (&lhs, &rhs) {
+        (Value::Nothing, _) | (_, Value::Nothing) => return Ok(Value::Nothing),
+        (Value::List(_), _) | (_, Value::List(_)) => return Err(list_oper_err()),
+        _ => {}
+    }
+    if let (Some(a), Some(b)) = (Surd::from_value(&lhs), Surd::from_value(&rhs)) {
+        if let Some(result) = exact(&a, &b) {
+            return result.into_value();
+        }
+    }
+    match (lhs.as_real(), rhs.as_real()) {
+        (Ok(a), Ok(b)) => Ok(Value::Real(inexact(a, b))),
+        _ => Err(invalid_operands(op, &lhs, &rhs)),
+    }
+}
+
+/// Operace, ve které vystupuje násobek π. Pokud je výsledek opět násobek π nebo zlomek
+/// (pi/6 + pi/3, 2*pi/pi), spočítá se přesně funkcí exact, jinak se počítá nepřesně funkcí
+/// inexact.
+fn pi_oper(
+    lhs: Value,
+    rhs: Value,
+    op: char,
+    exact: fn(&PiTerm, &PiTerm) -> Option<PiTerm>,
+    inexact: fn(f64, f64) -> f64,
+) -> Result<Value, CalcError> {
+    match (&lhs, &rhs) {
+        (Value::Nothing, _) | (_, Value::Nothing) => return Ok(Value::Nothing),
+        (Value::List(_), _) | (_, Value::List(_)) => return Err(list_oper_err()),
+        _ => {}
+    }
+    if let (Some(a), Some(b)) = (PiTerm::from_value(&lhs), PiTerm::from_value(&rhs)) {
+        if let Some(result) = exact(&a, &b).and_then(PiTerm::into_value) {
+            return Ok(result);
+        }
+    }
+    match (lhs.as_real(), rhs.as_real()) {
+        (Ok(a), Ok(b)) => Ok(Value::Real(inexact(a, b))),
+        _ => Err(invalid_operands(op, &lhs, &rhs)),
+    }
+}
+
+/// Operace, ve které vystupuje desetinné číslo. Přesné operandy se převedou na desetinná
+/// čísla a výsledek se spočítá funkcí exact s přesností desetinného operandu (u dvou
+/// desetinných s menší z nich). Pokud to nejde (reálné číslo, exact vrátí None), spočítá se
+/// nepřesně funkcí inexact.
+fn decimal_oper(
+    lhs: Value,
+    rhs: Value,
+    op: char,
+    exact: fn(&Decimal, &Decimal) -> Option<Decimal>,
+    inexact: fn(f64, f64) -> f64,
+) -> Result<Value, CalcError> {
+    match (&lhs, &rhs) {
+        (Value::Nothing, _) | (_, Value::Nothing) => return Ok(Value::Nothing),
+        (Value::List(_), _) | (_, Value::List(_)) => return Err(list_oper_err()),
+        _ => {}
+    }
+    let digits = [&lhs, &rhs]
+        .into_iter()
+        .filter_map(|x| match x {
+            Value::Decimal(d) => Some(d.digits()),
+            _ => None,
+        })
+        .min();
+    // Přesný operand se převede s číslicemi navíc, aby nepřidal další zaokrouhlovací chybu
+    if let Some(digits) = digits {
+        if let (Some(a), Some(b)) = (
+            Decimal::from_value(&lhs, digits + GUARD_DIGITS),
+            Decimal::from_value(&rhs, digits + GUARD_DIGITS),
+        ) {
+            if let Some(result) = exact(&a, &b) {
+                return Ok(Value::Decimal(result));
+            }
+        }
+    }
+    match (lhs.as_real(), rhs.as_real()) {
+        (Ok(a), Ok(b)) => Ok(Value::Real(inexact(a, b))),
+        _ => Err(invalid_operands(op, &lhs, &rhs)),
+    }
+}
+
+/// Komplexní mocnina, viz Complex::pow
+fn complex_pow(base: Value, exponent: &Value) -> Result<Value, CalcError> {
+    complex_oper(base, exponent.clone(), '^', |a, b| a.pow(&b))
+}
+
 fn simplify_result_type(x: Result<Value, CalcError>) -> Result<Value, CalcError> {
     x?.simplify_type_move()
 }
@@ -55,12 +211,31 @@ impl Sub<Value> for Value {
                 Value::BigInt(y) => Ok(Value::BigInt(BigInt::from(x) - y)),
                 Value::Rational(y) => Ok(Value::Rational(Rational::from_int(x) - y)),
                 Value::Real(y) => Ok(Value::Real(x as f64 - y)),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze odčítat celé číslo {x} a text {y}"
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze odčítat celé číslo {x} a boolean {y}"
-                ))),
+                Value::Text(y) => Err(invalid_operands('-', &Value::Integer(x), &Value::Text(y))),
+                Value::Bool(y) => Err(invalid_operands('-', &Value::Integer(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::Integer(x), Value::Complex(y), '-', |a, b| a.sub(b))
+                }
+                Value::Surd(y) => {
+                    surd_oper(Value::Integer(x), Value::Surd(y), '-', Surd::sub, |a, b| {
+                        a - b
+                    })
+                }
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Integer(x),
+                    Value::PiMultiple(y),
+                    '-',
+                    PiTerm::sub,
+                    |a, b| a - b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Integer(x),
+                    Value::Decimal(y),
+                    '-',
+                    |a, b| Some(a.sub(b)),
+                    |a, b| a - b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::BigInt(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -68,12 +243,31 @@ impl Sub<Value> for Value {
                 Value::BigInt(y) => Ok(Value::BigInt(x - y)),
                 Value::Rational(y) => Ok(Value::Rational(Rational::from_bigint(x) - y)),
                 Value::Real(y) => Ok(Value::Real(bi_to_real(&x)? - y)),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze odčítat velké celé číslo {x} a text {y}"
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze odčítat velké celé číslo {x} a boolean {y}"
-                ))),
+                Value::Text(y) => Err(invalid_operands('-', &Value::BigInt(x), &Value::Text(y))),
+                Value::Bool(y) => Err(invalid_operands('-', &Value::BigInt(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::BigInt(x), Value::Complex(y), '-', |a, b| a.sub(b))
+                }
+                Value::Surd(y) => {
+                    surd_oper(Value::BigInt(x), Value::Surd(y), '-', Surd::sub, |a, b| {
+                        a - b
+                    })
+                }
+                Value::PiMultiple(y) => pi_oper(
+                    Value::BigInt(x),
+                    Value::PiMultiple(y),
+                    '-',
+                    PiTerm::sub,
+                    |a, b| a - b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::BigInt(x),
+                    Value::Decimal(y),
+                    '-',
+                    |a, b| Some(a.sub(b)),
+                    |a, b| a - b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::Rational(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -81,12 +275,33 @@ impl Sub<Value> for Value {
                 Value::BigInt(y) => Ok(Value::Rational(x - Rational::from_bigint(y))),
                 Value::Rational(y) => Ok(Value::Rational(x - y)),
                 Value::Real(y) => Ok(Value::Real(to_real(&x)? - y)),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze odčítat racionální číslo {x} a text {y}"
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze odčítat racionální číslo {x} a boolean {y}"
-                ))),
+                Value::Text(y) => Err(invalid_operands('-', &Value::Rational(x), &Value::Text(y))),
+                Value::Bool(y) => Err(invalid_operands('-', &Value::Rational(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::Rational(x), Value::Complex(y), '-', |a, b| a - b)
+                }
+                Value::Surd(y) => surd_oper(
+                    Value::Rational(x),
+                    Value::Surd(y),
+                    '-',
+                    Surd::sub,
+                    |a, b| a - b,
+                ),
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Rational(x),
+                    Value::PiMultiple(y),
+                    '-',
+                    PiTerm::sub,
+                    |a, b| a - b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Rational(x),
+                    Value::Decimal(y),
+                    '-',
+                    |a, b| Some(a.sub(b)),
+                    |a, b| a - b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::Real(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -94,19 +309,72 @@ impl Sub<Value> for Value {
                 Value::BigInt(y) => Ok(Value::Real(x - bi_to_real(&y)?)),
                 Value::Rational(y) => Ok(Value::Real(x - to_real(&y)?)),
                 Value::Real(y) => Ok(Value::Real(x - y)),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze odčítat reálné číslo {x} a text {y}"
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze odčítat reálné číslo {x} a boolean {y}"
-                ))),
-            },
-            Value::Text(x) => Err(CalcError::EvaluateErr(format!(
-                "Na text {x} nelze aplikovat operátor minus"
-            ))),
-            Value::Bool(x) => Err(CalcError::EvaluateErr(format!(
-                "Na boolean {x} nelze aplikovat operátor minus"
-            ))),
+                Value::Text(y) => Err(invalid_operands('-', &Value::Real(x), &Value::Text(y))),
+                Value::Bool(y) => Err(invalid_operands('-', &Value::Real(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::Real(x), Value::Complex(y), '-', |a, b| a.sub(b))
+                }
+                Value::Surd(y) => {
+                    surd_oper(Value::Real(x), Value::Surd(y), '-', Surd::sub, |a, b| a - b)
+                }
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Real(x),
+                    Value::PiMultiple(y),
+                    '-',
+                    PiTerm::sub,
+                    |a, b| a - b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Real(x),
+                    Value::Decimal(y),
+                    '-',
+                    |a, b| Some(a.sub(b)),
+                    |a, b| a - b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
+            },
+            Value::Text(x) => Err(invalid_operand('-', &Value::Text(x))),
+            Value::Bool(x) => Err(invalid_operand('-', &Value::Bool(x))),
+            Value::Complex(x) => complex_oper(Value::Complex(x), rhs, '-', |a, b| a - b),
+            Value::Surd(x) => match rhs {
+                Value::Complex(y) => {
+                    complex_oper(Value::Surd(x), Value::Complex(y), '-', |a, b| a - b)
+                }
+                Value::Decimal(y) => decimal_oper(
+                    Value::Surd(x),
+                    Value::Decimal(y),
+                    '-',
+                    |a, b| Some(a.sub(b)),
+                    |a, b| a - b,
+                ),
+                rhs => surd_oper(Value::Surd(x), rhs, '-', Surd::sub, |a, b| a - b),
+            },
+            Value::PiMultiple(x) => match rhs {
+                Value::Complex(y) => {
+                    complex_oper(Value::PiMultiple(x), Value::Complex(y), '-', |a, b| a - b)
+                }
+                Value::Decimal(y) => decimal_oper(
+                    Value::PiMultiple(x),
+                    Value::Decimal(y),
+                    '-',
+                    |a, b| Some(a.sub(b)),
+                    |a, b| a - b,
+                ),
+                rhs => pi_oper(Value::PiMultiple(x), rhs, '-', PiTerm::sub, |a, b| a - b),
+            },
+            Value::Decimal(x) => match rhs {
+                Value::Complex(y) => {
+                    complex_oper(Value::Decimal(x), Value::Complex(y), '-', |a, b| a - b)
+                }
+                rhs => decimal_oper(
+                    Value::Decimal(x),
+                    rhs,
+                    '-',
+                    |a, b| Some(a.sub(b)),
+                    |a, b| a - b,
+                ),
+            },
+            Value::List(_) => Err(list_oper_err()),
         };
         return simplify_result_type(result);
     }
@@ -114,18 +382,18 @@ impl Sub<Value> for Value {
 
 fn to_real(q: &Rational) -> Result<f64, CalcError> {
     return match q.to_real() {
-        None => Err(CalcError::EvaluateErr(format!(
-            "Zlomek '{q}' se nepodařilo převést na reálné číslo"
-        ))),
+        None => Err(CalcError::EvaluateErr(ErrorKind::NotReal {
+            value: format!("Rational {q}"),
+        })),
         Some(r) => Ok(r),
     };
 }
 
 fn bi_to_real(q: &BigInt) -> Result<f64, CalcError> {
     return match q.to_f64() {
-        None => Err(CalcError::EvaluateErr(format!(
-            "Velké celé číslo '{q}' se nepodařilo převést na reálné číslo"
-        ))),
+        None => Err(CalcError::EvaluateErr(ErrorKind::NotReal {
+            value: format!("BigInt {q}"),
+        })),
         Some(r) => Ok(r),
     };
 }
@@ -139,34 +407,48 @@ impl std::ops::Div<Value> for Value {
             Value::Nothing => {}
             Value::Integer(i) => {
                 if i.is_zero() {
-                    return Err(CalcError::EvaluateErr(s!("Nelze dělit celým číslem 0")));
+                    return Err(CalcError::EvaluateErr(ErrorKind::DivisionByZero));
                 }
             }
             Value::BigInt(i) => {
                 if i.is_zero() {
-                    return Err(CalcError::EvaluateErr(s!("Nelze dělit bigintem 0")));
+                    return Err(CalcError::EvaluateErr(ErrorKind::DivisionByZero));
                 }
             }
             Value::Rational(r) => {
                 if r.numerator.is_zero() {
-                    return Err(CalcError::EvaluateErr(s!(
-                        "Nelze dělit racionálním číslem 0"
-                    )));
+                    return Err(CalcError::EvaluateErr(ErrorKind::DivisionByZero));
                 }
             }
             Value::Real(r) => {
                 if r.is_zero() {
-                    return Err(CalcError::EvaluateErr(s!("Nelze dělit reálným číslem 0")));
+                    return Err(CalcError::EvaluateErr(ErrorKind::DivisionByZero));
+                }
+            }
+            Value::Text(_) | Value::Bool(_) => {
+                return Err(invalid_operands('/', &self, &rhs));
+            }
+            Value::PiMultiple(x) => {
+                if x.numerator.is_zero() {
+                    return Err(CalcError::EvaluateErr(ErrorKind::DivisionByZero));
                 }
             }
-            Value::Text(_) => {
-                return Err(CalcError::EvaluateErr(s!("Nelze dělit textovou hodnotu.")));
+            Value::Surd(x) => {
+                if x.is_zero() {
+                    return Err(CalcError::EvaluateErr(ErrorKind::DivisionByZero));
+                }
             }
-            Value::Bool(_) => {
-                return Err(CalcError::EvaluateErr(s!(
-                    "Nelze dělit booleovskou bodnotu"
-                )));
+            Value::Decimal(x) => {
+                if x.is_zero() {
+                    return Err(CalcError::EvaluateErr(ErrorKind::DivisionByZero));
+                }
             }
+            Value::Complex(c) => {
+                if c.is_zero() {
+                    return Err(CalcError::EvaluateErr(ErrorKind::DivisionByZero));
+                }
+            }
+            Value::List(_) => return Err(list_oper_err()),
         };
 
         let result = match self {
@@ -177,12 +459,34 @@ impl std::ops::Div<Value> for Value {
                 Value::BigInt(y) => Ok(div_big_ints(&BigInt::from(x), &y)?), // Dělení celých čísel může vrátit zlomek, nebo i celé číslo!
                 Value::Rational(y) => Ok(Value::Rational(Rational::from_int(x) / y)),
                 Value::Real(y) => Ok(Value::Real(x as f64 / y)),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze dělit celé číslo {x} a text {y}."
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze dělit celé číslo {x} a boolean {y}."
-                ))),
+                Value::Text(y) => Err(invalid_operands('/', &Value::Integer(x), &Value::Text(y))),
+                Value::Bool(y) => Err(invalid_operands('/', &Value::Integer(x), &Value::Bool(y))),
+                Value::Complex(y) => complex_oper(
+                    Value::Integer(x),
+                    Value::Complex(y),
+                    '/',
+                    Complex::checked_div,
+                ),
+                Value::Surd(y) => {
+                    surd_oper(Value::Integer(x), Value::Surd(y), '/', Surd::div, |a, b| {
+                        a / b
+                    })
+                }
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Integer(x),
+                    Value::PiMultiple(y),
+                    '/',
+                    PiTerm::div,
+                    |a, b| a / b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Integer(x),
+                    Value::Decimal(y),
+                    '/',
+                    Decimal::checked_div,
+                    |a, b| a / b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::BigInt(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -190,12 +494,34 @@ impl std::ops::Div<Value> for Value {
                 Value::BigInt(y) => Ok(div_big_ints(&x, &y)?), // Dělení celých čísel může vrátit zlomek, nebo i celé číslo!
                 Value::Rational(y) => Ok(Value::Rational(Rational::from_bigint(x) / y)),
                 Value::Real(y) => Ok(Value::Real(bi_to_real(&x)? / y)),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze dělit velké celé číslo {x} a text {y}."
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze dělit velké celé číslo {x} a boolean {y}."
-                ))),
+                Value::Text(y) => Err(invalid_operands('/', &Value::BigInt(x), &Value::Text(y))),
+                Value::Bool(y) => Err(invalid_operands('/', &Value::BigInt(x), &Value::Bool(y))),
+                Value::Complex(y) => complex_oper(
+                    Value::BigInt(x),
+                    Value::Complex(y),
+                    '/',
+                    Complex::checked_div,
+                ),
+                Value::Surd(y) => {
+                    surd_oper(Value::BigInt(x), Value::Surd(y), '/', Surd::div, |a, b| {
+                        a / b
+                    })
+                }
+                Value::PiMultiple(y) => pi_oper(
+                    Value::BigInt(x),
+                    Value::PiMultiple(y),
+                    '/',
+                    PiTerm::div,
+                    |a, b| a / b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::BigInt(x),
+                    Value::Decimal(y),
+                    '/',
+                    Decimal::checked_div,
+                    |a, b| a / b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::Rational(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -203,12 +529,36 @@ impl std::ops::Div<Value> for Value {
                 Value::BigInt(y) => Ok(Value::Rational(x / Rational::from_bigint(y))),
                 Value::Rational(y) => Ok(Value::Rational(x / y)),
                 Value::Real(y) => Ok(Value::Real(to_real(&x)? / y)),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze dělit racionální číslo {x} a text {y}."
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze dělit racionální číslo {x} a boolean {y}."
-                ))),
+                Value::Text(y) => Err(invalid_operands('/', &Value::Rational(x), &Value::Text(y))),
+                Value::Bool(y) => Err(invalid_operands('/', &Value::Rational(x), &Value::Bool(y))),
+                Value::Complex(y) => complex_oper(
+                    Value::Rational(x),
+                    Value::Complex(y),
+                    '/',
+                    Complex::checked_div,
+                ),
+                Value::Surd(y) => surd_oper(
+                    Value::Rational(x),
+                    Value::Surd(y),
+                    '/',
+                    Surd::div,
+                    |a, b| a / b,
+                ),
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Rational(x),
+                    Value::PiMultiple(y),
+                    '/',
+                    PiTerm::div,
+                    |a, b| a / b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Rational(x),
+                    Value::Decimal(y),
+                    '/',
+                    Decimal::checked_div,
+                    |a, b| a / b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::Real(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -216,19 +566,74 @@ impl std::ops::Div<Value> for Value {
                 Value::BigInt(y) => Ok(Value::Real(x / bi_to_real(&y)?)),
                 Value::Rational(y) => Ok(Value::Real(x / to_real(&y)?)),
                 Value::Real(y) => Ok(Value::Real(x / y)),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze dělit reálné číslo {x} a text {y}."
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze dělit reálné číslo {x} a boolean {y}."
-                ))),
-            },
-            Value::Text(x) => Err(CalcError::EvaluateErr(format!(
-                "Na text {x} neze aplikovat operátor dělení"
-            ))),
-            Value::Bool(_) => Err(CalcError::EvaluateErr(format!(
-                "Na boolean neze aplikovat operátor dělení"
-            ))),
+                Value::Text(y) => Err(invalid_operands('/', &Value::Real(x), &Value::Text(y))),
+                Value::Bool(y) => Err(invalid_operands('/', &Value::Real(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::Real(x), Value::Complex(y), '/', Complex::checked_div)
+                }
+                Value::Surd(y) => {
+                    surd_oper(Value::Real(x), Value::Surd(y), '/', Surd::div, |a, b| a / b)
+                }
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Real(x),
+                    Value::PiMultiple(y),
+                    '/',
+                    PiTerm::div,
+                    |a, b| a / b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Real(x),
+                    Value::Decimal(y),
+                    '/',
+                    Decimal::checked_div,
+                    |a, b| a / b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
+            },
+            Value::Text(x) => Err(invalid_operand('/', &Value::Text(x))),
+            Value::Bool(x) => Err(invalid_operand('/', &Value::Bool(x))),
+            Value::Complex(x) => complex_oper(Value::Complex(x), rhs, '/', Complex::checked_div),
+            Value::Surd(x) => match rhs {
+                Value::Complex(y) => {
+                    complex_oper(Value::Surd(x), Value::Complex(y), '/', Complex::checked_div)
+                }
+                Value::Decimal(y) => decimal_oper(
+                    Value::Surd(x),
+                    Value::Decimal(y),
+                    '/',
+                    Decimal::checked_div,
+                    |a, b| a / b,
+                ),
+                rhs => surd_oper(Value::Surd(x), rhs, '/', Surd::div, |a, b| a / b),
+            },
+            Value::PiMultiple(x) => match rhs {
+                Value::Complex(y) => complex_oper(
+                    Value::PiMultiple(x),
+                    Value::Complex(y),
+                    '/',
+                    Complex::checked_div,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::PiMultiple(x),
+                    Value::Decimal(y),
+                    '/',
+                    Decimal::checked_div,
+                    |a, b| a / b,
+                ),
+                rhs => pi_oper(Value::PiMultiple(x), rhs, '/', PiTerm::div, |a, b| a / b),
+            },
+            Value::Decimal(x) => match rhs {
+                Value::Complex(y) => complex_oper(
+                    Value::Decimal(x),
+                    Value::Complex(y),
+                    '/',
+                    Complex::checked_div,
+                ),
+                rhs => decimal_oper(Value::Decimal(x), rhs, '/', Decimal::checked_div, |a, b| {
+                    a / b
+                }),
+            },
+            Value::List(_) => Err(list_oper_err()),
         };
         return simplify_result_type(result);
     }
@@ -237,22 +642,18 @@ impl std::ops::Div<Value> for Value {
 /// Dělení dvou celých čísel může vrátit zlomek (racio. číslo), nebo celé číslo
 fn div_ints(a: i64, b: i64) -> Result<Value, CalcError> {
     if b == 0 {
-        Err(CalcError::EvaluateErr(s!("Nulou nelze dělit!")))
+        Err(CalcError::EvaluateErr(ErrorKind::DivisionByZero))
     } else {
-        Ok(Value::Rational(Rational::new(a, b))
-            .simplify_type_move()
-            .unwrap())
+        Value::Rational(Rational::new(a, b)).simplify_type_move()
     }
 }
 
 /// Dělení dvou celých čísel může vrátit zlomek (racio. číslo), nebo celé číslo
 fn div_big_ints(a: &BigInt, b: &BigInt) -> Result<Value, CalcError> {
     if b.is_zero() {
-        Err(CalcError::EvaluateErr(s!("Nulou nelze dělit!")))
+        Err(CalcError::EvaluateErr(ErrorKind::DivisionByZero))
     } else {
-        Ok(Value::Rational(Rational::new_bigint(a.clone(), b.clone()))
-            .simplify_type_move()
-            .unwrap())
+        Value::Rational(Rational::new_bigint(a.clone(), b.clone())).simplify_type_move()
     }
 }
 
@@ -274,12 +675,31 @@ impl Mul<Value> for Value {
                 Value::BigInt(y) => Ok(Value::BigInt(x * y)),
                 Value::Rational(y) => Ok(Value::Rational(Rational::from_int(x) * y)),
                 Value::Real(y) => Ok(Value::Real(x as f64 * y)),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit celé číslo {x} a text {y}."
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit celé číslo {x} a boolean {y}."
-                ))),
+                Value::Text(y) => Err(invalid_operands('*', &Value::Integer(x), &Value::Text(y))),
+                Value::Bool(y) => Err(invalid_operands('*', &Value::Integer(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::Integer(x), Value::Complex(y), '*', |a, b| a.mul(b))
+                }
+                Value::Surd(y) => {
+                    surd_oper(Value::Integer(x), Value::Surd(y), '*', Surd::mul, |a, b| {
+                        a * b
+                    })
+                }
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Integer(x),
+                    Value::PiMultiple(y),
+                    '*',
+                    PiTerm::mul,
+                    |a, b| a * b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Integer(x),
+                    Value::Decimal(y),
+                    '*',
+                    |a, b| Some(a.mul(b)),
+                    |a, b| a * b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::BigInt(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -287,12 +707,31 @@ impl Mul<Value> for Value {
                 Value::BigInt(y) => Ok(Value::BigInt(x * y)),
                 Value::Rational(y) => Ok(Value::Rational(Rational::from_bigint(x) * y)),
                 Value::Real(y) => Ok(Value::Real(bi_to_real(&x)? * y)),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit velké celé číslo {x} a text {y}."
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit velké celé číslo {x} a boolean {y}."
-                ))),
+                Value::Text(y) => Err(invalid_operands('*', &Value::BigInt(x), &Value::Text(y))),
+                Value::Bool(y) => Err(invalid_operands('*', &Value::BigInt(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::BigInt(x), Value::Complex(y), '*', |a, b| a.mul(b))
+                }
+                Value::Surd(y) => {
+                    surd_oper(Value::BigInt(x), Value::Surd(y), '*', Surd::mul, |a, b| {
+                        a * b
+                    })
+                }
+                Value::PiMultiple(y) => pi_oper(
+                    Value::BigInt(x),
+                    Value::PiMultiple(y),
+                    '*',
+                    PiTerm::mul,
+                    |a, b| a * b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::BigInt(x),
+                    Value::Decimal(y),
+                    '*',
+                    |a, b| Some(a.mul(b)),
+                    |a, b| a * b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::Rational(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -300,12 +739,33 @@ impl Mul<Value> for Value {
                 Value::BigInt(y) => Ok(Value::Rational(x * Rational::from_bigint(y))),
                 Value::Rational(y) => Ok(Value::Rational(x * y)),
                 Value::Real(y) => Ok(Value::Real(to_real(&x)? * y)),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit racionální číslo {x} a text {y}."
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit racionální číslo {x} a boolean {y}."
-                ))),
+                Value::Text(y) => Err(invalid_operands('*', &Value::Rational(x), &Value::Text(y))),
+                Value::Bool(y) => Err(invalid_operands('*', &Value::Rational(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::Rational(x), Value::Complex(y), '*', |a, b| a.mul(b))
+                }
+                Value::Surd(y) => surd_oper(
+                    Value::Rational(x),
+                    Value::Surd(y),
+                    '*',
+                    Surd::mul,
+                    |a, b| a * b,
+                ),
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Rational(x),
+                    Value::PiMultiple(y),
+                    '*',
+                    PiTerm::mul,
+                    |a, b| a * b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Rational(x),
+                    Value::Decimal(y),
+                    '*',
+                    |a, b| Some(a.mul(b)),
+                    |a, b| a * b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::Real(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -313,35 +773,97 @@ impl Mul<Value> for Value {
                 Value::BigInt(y) => Ok(Value::Real(x * bi_to_real(&y)?)),
                 Value::Rational(y) => Ok(Value::Real(x * to_real(&y)?)),
                 Value::Real(y) => Ok(Value::Real(x * y)),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit reálné číslo {x} a text {y}."
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit reálné číslo {x} a boolean {y}."
-                ))),
-            },
-            Value::Text(x) => Err(CalcError::EvaluateErr(format!(
-                "Na text {x} neze aplikovat operátor násobení"
-            ))),
+                Value::Text(y) => Err(invalid_operands('*', &Value::Real(x), &Value::Text(y))),
+                Value::Bool(y) => Err(invalid_operands('*', &Value::Real(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::Real(x), Value::Complex(y), '*', |a, b| a.mul(b))
+                }
+                Value::Surd(y) => {
+                    surd_oper(Value::Real(x), Value::Surd(y), '*', Surd::mul, |a, b| a * b)
+                }
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Real(x),
+                    Value::PiMultiple(y),
+                    '*',
+                    PiTerm::mul,
+                    |a, b| a * b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Real(x),
+                    Value::Decimal(y),
+                    '*',
+                    |a, b| Some(a.mul(b)),
+                    |a, b| a * b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
+            },
+            Value::Text(x) => Err(invalid_operand('*', &Value::Text(x))),
             Value::Bool(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
-                Value::Integer(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit bool {x} a celé číslo {y}."
-                ))),
-                Value::BigInt(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit bool {x} a velké celé číslo {y}."
-                ))),
-                Value::Rational(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit bool {x} a racionální číslo {y}."
-                ))),
-                Value::Real(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit bool {x} a reálné číslo {y}."
-                ))),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze násobit bool {x} a text {y}."
-                ))),
+                Value::Integer(y) => {
+                    Err(invalid_operands('*', &Value::Bool(x), &Value::Integer(y)))
+                }
+                Value::BigInt(y) => Err(invalid_operands('*', &Value::Bool(x), &Value::BigInt(y))),
+                Value::Rational(y) => {
+                    Err(invalid_operands('*', &Value::Bool(x), &Value::Rational(y)))
+                }
+                Value::Real(y) => Err(invalid_operands('*', &Value::Bool(x), &Value::Real(y))),
+                Value::Text(y) => Err(invalid_operands('*', &Value::Bool(x), &Value::Text(y))),
                 Value::Bool(y) => Ok(Value::Bool(x && y)),
+                Value::Complex(y) => {
+                    Err(invalid_operands('*', &Value::Bool(x), &Value::Complex(y)))
+                }
+                Value::Surd(y) => Err(invalid_operands('*', &Value::Bool(x), &Value::Surd(y))),
+                Value::PiMultiple(y) => Err(invalid_operands(
+                    '*',
+                    &Value::Bool(x),
+                    &Value::PiMultiple(y),
+                )),
+                Value::Decimal(y) => {
+                    Err(invalid_operands('*', &Value::Bool(x), &Value::Decimal(y)))
+                }
+                Value::List(_) => Err(list_oper_err()),
             },
+            Value::Complex(x) => complex_oper(Value::Complex(x), rhs, '*', |a, b| a * b),
+            Value::Surd(x) => match rhs {
+                Value::Complex(y) => {
+                    complex_oper(Value::Surd(x), Value::Complex(y), '*', |a, b| a * b)
+                }
+                Value::Decimal(y) => decimal_oper(
+                    Value::Surd(x),
+                    Value::Decimal(y),
+                    '*',
+                    |a, b| Some(a.mul(b)),
+                    |a, b| a * b,
+                ),
+                rhs => surd_oper(Value::Surd(x), rhs, '*', Surd::mul, |a, b| a * b),
+            },
+            Value::PiMultiple(x) => match rhs {
+                Value::Complex(y) => {
+                    complex_oper(Value::PiMultiple(x), Value::Complex(y), '*', |a, b| a * b)
+                }
+                Value::Decimal(y) => decimal_oper(
+                    Value::PiMultiple(x),
+                    Value::Decimal(y),
+                    '*',
+                    |a, b| Some(a.mul(b)),
+                    |a, b| a * b,
+                ),
+                rhs => pi_oper(Value::PiMultiple(x), rhs, '*', PiTerm::mul, |a, b| a * b),
+            },
+            Value::Decimal(x) => match rhs {
+                Value::Complex(y) => {
+                    complex_oper(Value::Decimal(x), Value::Complex(y), '*', |a, b| a * b)
+                }
+                rhs => decimal_oper(
+                    Value::Decimal(x),
+                    rhs,
+                    '*',
+                    |a, b| Some(a.mul(b)),
+                    |a, b| a * b,
+                ),
+            },
+            Value::List(_) => Err(list_oper_err()),
         };
         return simplify_result_type(result);
     }
@@ -366,9 +888,30 @@ impl Add<Value> for Value {
                 Value::Rational(y) => Ok(Value::Rational(Rational::from_int(x) + y)),
                 Value::Real(y) => Ok(Value::Real(x as f64 + y)),
                 Value::Text(y) => Ok(Value::Text(x.to_string() + &y)),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze sčítat celé číslo {x} a boolean {y}."
-                ))),
+                Value::Bool(y) => Err(invalid_operands('+', &Value::Integer(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::Integer(x), Value::Complex(y), '+', |a, b| a.add(b))
+                }
+                Value::Surd(y) => {
+                    surd_oper(Value::Integer(x), Value::Surd(y), '+', Surd::add, |a, b| {
+                        a + b
+                    })
+                }
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Integer(x),
+                    Value::PiMultiple(y),
+                    '+',
+                    PiTerm::add,
+                    |a, b| a + b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Integer(x),
+                    Value::Decimal(y),
+                    '+',
+                    |a, b| Some(a.add(b)),
+                    |a, b| a + b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::BigInt(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -377,9 +920,30 @@ impl Add<Value> for Value {
                 Value::Rational(y) => Ok(Value::Rational(Rational::from_bigint(x) + y)),
                 Value::Real(y) => Ok(Value::Real(bi_to_real(&x)? + y)),
                 Value::Text(y) => Ok(Value::Text(x.to_string() + &y)),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze sčítat velké celé číslo {x} a boolean {y}."
-                ))),
+                Value::Bool(y) => Err(invalid_operands('+', &Value::BigInt(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::BigInt(x), Value::Complex(y), '+', |a, b| a.add(b))
+                }
+                Value::Surd(y) => {
+                    surd_oper(Value::BigInt(x), Value::Surd(y), '+', Surd::add, |a, b| {
+                        a + b
+                    })
+                }
+                Value::PiMultiple(y) => pi_oper(
+                    Value::BigInt(x),
+                    Value::PiMultiple(y),
+                    '+',
+                    PiTerm::add,
+                    |a, b| a + b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::BigInt(x),
+                    Value::Decimal(y),
+                    '+',
+                    |a, b| Some(a.add(b)),
+                    |a, b| a + b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::Rational(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -388,9 +952,32 @@ impl Add<Value> for Value {
                 Value::Rational(y) => Ok(Value::Rational(x + y)),
                 Value::Real(y) => Ok(Value::Real(to_real(&x)? + y)),
                 Value::Text(y) => Ok(Value::Text(x.to_string() + &y)),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze sčítat racionální číslo {x} a boolean {y}."
-                ))),
+                Value::Bool(y) => Err(invalid_operands('+', &Value::Rational(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::Rational(x), Value::Complex(y), '+', |a, b| a.add(b))
+                }
+                Value::Surd(y) => surd_oper(
+                    Value::Rational(x),
+                    Value::Surd(y),
+                    '+',
+                    Surd::add,
+                    |a, b| a + b,
+                ),
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Rational(x),
+                    Value::PiMultiple(y),
+                    '+',
+                    PiTerm::add,
+                    |a, b| a + b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Rational(x),
+                    Value::Decimal(y),
+                    '+',
+                    |a, b| Some(a.add(b)),
+                    |a, b| a + b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::Real(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -399,9 +986,28 @@ impl Add<Value> for Value {
                 Value::Rational(y) => Ok(Value::Real(x + to_real(&y)?)),
                 Value::Real(y) => Ok(Value::Real(x + y)),
                 Value::Text(y) => Ok(Value::Text(x.to_string() + &y)),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze sčítat reálné číslo {x} a boolean {y}."
-                ))),
+                Value::Bool(y) => Err(invalid_operands('+', &Value::Real(x), &Value::Bool(y))),
+                Value::Complex(y) => {
+                    complex_oper(Value::Real(x), Value::Complex(y), '+', |a, b| a.add(b))
+                }
+                Value::Surd(y) => {
+                    surd_oper(Value::Real(x), Value::Surd(y), '+', Surd::add, |a, b| a + b)
+                }
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Real(x),
+                    Value::PiMultiple(y),
+                    '+',
+                    PiTerm::add,
+                    |a, b| a + b,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Real(x),
+                    Value::Decimal(y),
+                    '+',
+                    |a, b| Some(a.add(b)),
+                    |a, b| a + b,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::Text(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
@@ -411,26 +1017,84 @@ impl Add<Value> for Value {
                 Value::Real(y) => Ok(Value::Text(x + &y.to_string())),
                 Value::Text(y) => Ok(Value::Text(x + &y)),
                 Value::Bool(y) => Ok(Value::Text(x + &y.to_string())),
+                Value::Complex(y) => Ok(Value::Text(x + &y.to_string())),
+                Value::Surd(y) => Ok(Value::Text(x + &y.to_string())),
+                Value::PiMultiple(y) => Ok(Value::Text(x + &fmt_pi_multiple(&y))),
+                Value::Decimal(y) => Ok(Value::Text(x + &y.to_string())),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::Bool(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
-                Value::Integer(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze sčítat bool {x} a celé číslo {y}."
-                ))),
-                Value::BigInt(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze sčítat bool {x} a velké celé číslo {y}."
-                ))),
-                Value::Rational(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze sčítat bool {x} a racionální číslo {y}."
-                ))),
-                Value::Real(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze sčítat bool {x} a reálné číslo {y}."
-                ))),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze sčítat bool {x} a text {y}."
-                ))),
+                Value::Integer(y) => {
+                    Err(invalid_operands('+', &Value::Bool(x), &Value::Integer(y)))
+                }
+                Value::BigInt(y) => Err(invalid_operands('+', &Value::Bool(x), &Value::BigInt(y))),
+                Value::Rational(y) => {
+                    Err(invalid_operands('+', &Value::Bool(x), &Value::Rational(y)))
+                }
+                Value::Real(y) => Err(invalid_operands('+', &Value::Bool(x), &Value::Real(y))),
+                Value::Text(y) => Err(invalid_operands('+', &Value::Bool(x), &Value::Text(y))),
                 Value::Bool(y) => Ok(Value::Bool(x || y)),
+                Value::Complex(y) => {
+                    Err(invalid_operands('+', &Value::Bool(x), &Value::Complex(y)))
+                }
+                Value::Surd(y) => Err(invalid_operands('+', &Value::Bool(x), &Value::Surd(y))),
+                Value::PiMultiple(y) => Err(invalid_operands(
+                    '+',
+                    &Value::Bool(x),
+                    &Value::PiMultiple(y),
+                )),
+                Value::Decimal(y) => {
+                    Err(invalid_operands('+', &Value::Bool(x), &Value::Decimal(y)))
+                }
+                Value::List(_) => Err(list_oper_err()),
+            },
+            Value::Complex(x) => match rhs {
+                Value::Text(y) => Ok(Value::Text(x.to_string() + &y)),
+                rhs => complex_oper(Value::Complex(x), rhs, '+', |a, b| a + b),
+            },
+            Value::Surd(x) => match rhs {
+                Value::Text(y) => Ok(Value::Text(x.to_string() + &y)),
+                Value::Complex(y) => {
+                    complex_oper(Value::Surd(x), Value::Complex(y), '+', |a, b| a + b)
+                }
+                Value::Decimal(y) => decimal_oper(
+                    Value::Surd(x),
+                    Value::Decimal(y),
+                    '+',
+                    |a, b| Some(a.add(b)),
+                    |a, b| a + b,
+                ),
+                rhs => surd_oper(Value::Surd(x), rhs, '+', Surd::add, |a, b| a + b),
+            },
+            Value::PiMultiple(x) => match rhs {
+                Value::Text(y) => Ok(Value::Text(fmt_pi_multiple(&x) + &y)),
+                Value::Complex(y) => {
+                    complex_oper(Value::PiMultiple(x), Value::Complex(y), '+', |a, b| a + b)
+                }
+                Value::Decimal(y) => decimal_oper(
+                    Value::PiMultiple(x),
+                    Value::Decimal(y),
+                    '+',
+                    |a, b| Some(a.add(b)),
+                    |a, b| a + b,
+                ),
+                rhs => pi_oper(Value::PiMultiple(x), rhs, '+', PiTerm::add, |a, b| a + b),
             },
+            Value::Decimal(x) => match rhs {
+                Value::Text(y) => Ok(Value::Text(x.to_string() + &y)),
+                Value::Complex(y) => {
+                    complex_oper(Value::Decimal(x), Value::Complex(y), '+', |a, b| a + b)
+                }
+                rhs => decimal_oper(
+                    Value::Decimal(x),
+                    rhs,
+                    '+',
+                    |a, b| Some(a.add(b)),
+                    |a, b| a + b,
+                ),
+            },
+            Value::List(_) => Err(list_oper_err()),
         };
         return simplify_result_type(result);
     }
@@ -438,77 +1102,259 @@ impl Add<Value> for Value {
 
 impl Value {
     pub fn pow(self, rhs: &Value) -> Result<Value, CalcError> {
+        // Záporné číslo na neceločíselný exponent nemá reálný výsledek, (-4)^(1/2) = 2i
+        if self.is_negative_number() && rhs.is_fractional_number() {
+            return complex_pow(self, rhs);
+        }
         match self {
             Value::Nothing => Ok(Value::Nothing),
             Value::Integer(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
                 Value::Integer(y) => {
+                    check_pow_size(u64::from(i64::BITS - x.unsigned_abs().leading_zeros()), *y)?;
                     if *y >= 0 {
-                        let res = match x.checked_pow(*y as u32) {
-                            None => Value::BigInt(BigInt::from(x).pow(*y as u32)),
+                        let res = match x.checked_pow(pow_exponent(*y)) {
+                            None => Value::BigInt(BigInt::from(x).pow(pow_exponent(*y))),
                             Some(r) => Value::Integer(r),
                         };
                         Ok(res)
                     } else if *y == 0 {
                         return Ok(Value::Integer(1));
                     } else {
-                        let res = match x.checked_pow((-*y) as u32) {
+                        let res = match x.checked_pow(pow_exponent(*y)) {
                             None => {
                                 (Value::Integer(1)
-                                    / Value::BigInt(BigInt::from(x).pow((-*y) as u32)))?
+                                    / Value::BigInt(BigInt::from(x).pow(pow_exponent(*y))))?
                             }
                             Some(r) => (Value::Integer(1) / Value::Integer(r))?,
                         };
                         Ok(res)
                     }
                 }
-                Value::BigInt(_) => Err(CalcError::EvaluateErr(s!(
-                    "Mocnění velkých celých čísel není povoleno"
-                ))),
+                Value::BigInt(_) => Err(CalcError::EvaluateErr(ErrorKind::BigIntPower)),
                 Value::Rational(y) => Ok(Value::Real((x as f64).powf(to_real(y)?))),
                 Value::Real(y) => Ok(Value::Real((x as f64).powf(*y))),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze mocnit celé číslo {x} na text {y}."
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze mocnit celé číslo {x} na boolean {y}."
-                ))),
-            },
-            Value::BigInt(_) => Err(CalcError::EvaluateErr(s!(
-                "Mocnění velkých celých čísel není povoleno"
-            ))),
+                Value::Text(_) => Err(invalid_operands('^', &Value::Integer(x), rhs)),
+                Value::Bool(_) => Err(invalid_operands('^', &Value::Integer(x), rhs)),
+                Value::Complex(_) => complex_pow(Value::Integer(x), rhs),
+                Value::Surd(y) => surd_oper(
+                    Value::Integer(x),
+                    Value::Surd(y.clone()),
+                    '^',
+                    Surd::pow,
+                    f64::powf,
+                ),
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Integer(x),
+                    Value::PiMultiple(y.clone()),
+                    '^',
+                    |_, _| None,
+                    f64::powf,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Integer(x),
+                    Value::Decimal(y.clone()),
+                    '^',
+                    Decimal::pow,
+                    f64::powf,
+                ),
+                Value::List(_) => Err(list_oper_err()),
+            },
+            Value::BigInt(_) => Err(CalcError::EvaluateErr(ErrorKind::BigIntPower)),
             Value::Rational(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
-                Value::Integer(y) => Ok(Value::Rational(x.pow_int(*y))),
-                Value::BigInt(_) => Err(CalcError::EvaluateErr(s!(
-                    "Mocnění velkých celých čísel není povoleno"
-                ))),
+                Value::Integer(y) => {
+                    check_pow_size(x.numerator.bits().max(x.denominator.bits()), *y)?;
+                    Ok(Value::Rational(x.pow_int(*y)))
+                }
+                Value::BigInt(_) => Err(CalcError::EvaluateErr(ErrorKind::BigIntPower)),
                 Value::Rational(y) => Ok(Value::Real(to_real(&x)?.powf(to_real(y)?))),
                 Value::Real(y) => Ok(Value::Real(to_real(&x)?.powf(*y))),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze mocnit celé číslo {x} na text {y}."
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze mocnit celé číslo {x} na boolean {y}."
-                ))),
+                Value::Text(_) => Err(invalid_operands('^', &Value::Rational(x), rhs)),
+                Value::Bool(_) => Err(invalid_operands('^', &Value::Rational(x), rhs)),
+                Value::Complex(_) => complex_pow(Value::Rational(x), rhs),
+                Value::Surd(y) => surd_oper(
+                    Value::Rational(x),
+                    Value::Surd(y.clone()),
+                    '^',
+                    Surd::pow,
+                    f64::powf,
+                ),
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Rational(x),
+                    Value::PiMultiple(y.clone()),
+                    '^',
+                    |_, _| None,
+                    f64::powf,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Rational(x),
+                    Value::Decimal(y.clone()),
+                    '^',
+                    Decimal::pow,
+                    f64::powf,
+                ),
+                Value::List(_) => Err(list_oper_err()),
             },
             Value::Real(x) => match rhs {
                 Value::Nothing => Ok(Value::Nothing),
                 Value::Integer(y) => Ok(Value::Real(x.powf(*y as f64))),
-                Value::BigInt(_) => Err(CalcError::EvaluateErr(s!(
-                    "Mocnění velkých celých čísel není povoleno"
-                ))),
+                Value::BigInt(_) => Err(CalcError::EvaluateErr(ErrorKind::BigIntPower)),
                 Value::Rational(y) => Ok(Value::Real(x.powf(to_real(y)?))),
                 Value::Real(y) => Ok(Value::Real(x.powf(*y))),
-                Value::Text(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze mocnit celé číslo {x} na text {y}."
-                ))),
-                Value::Bool(y) => Err(CalcError::EvaluateErr(format!(
-                    "Nelze mocnit celé číslo {x} na boolean {y}."
-                ))),
-            },
-            Value::Text(x) => Err(CalcError::EvaluateErr(format!("Nelze mocnit text {x}."))),
-            Value::Bool(x) => Err(CalcError::EvaluateErr(format!("Nelze mocnit boolean {x}."))),
+                Value::Text(_) => Err(invalid_operands('^', &Value::Real(x), rhs)),
+                Value::Bool(_) => Err(invalid_operands('^', &Value::Real(x), rhs)),
+                Value::Complex(_) => complex_pow(Value::Real(x), rhs),
+                Value::Surd(y) => surd_oper(
+                    Value::Real(x),
+                    Value::Surd(y.clone()),
+                    '^',
+                    Surd::pow,
+                    f64::powf,
+                ),
+                Value::PiMultiple(y) => pi_oper(
+                    Value::Real(x),
+                    Value::PiMultiple(y.clone()),
+                    '^',
+                    |_, _| None,
+                    f64::powf,
+                ),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Real(x),
+                    Value::Decimal(y.clone()),
+                    '^',
+                    Decimal::pow,
+                    f64::powf,
+                ),
+                Value::List(_) => Err(list_oper_err()),
+            },
+            Value::Text(x) => Err(invalid_operand('^', &Value::Text(x))),
+            Value::Bool(x) => Err(invalid_operand('^', &Value::Bool(x))),
+            Value::Complex(x) => complex_pow(Value::Complex(x), rhs),
+            Value::Surd(x) => match rhs {
+                Value::Complex(_) => complex_pow(Value::Surd(x), rhs),
+                Value::Decimal(y) => decimal_oper(
+                    Value::Surd(x),
+                    Value::Decimal(y.clone()),
+                    '^',
+                    Decimal::pow,
+                    f64::powf,
+                ),
+                _ => surd_oper(Value::Surd(x), rhs.clone(), '^', Surd::pow, f64::powf),
+            },
+            Value::PiMultiple(x) => match rhs {
+                Value::Complex(_) => complex_pow(Value::PiMultiple(x), rhs),
+                Value::Decimal(y) => decimal_oper(
+                    Value::PiMultiple(x),
+                    Value::Decimal(y.clone()),
+                    '^',
+                    Decimal::pow,
+                    f64::powf,
+                ),
+                _ => pi_oper(
+                    Value::PiMultiple(x),
+                    rhs.clone(),
+                    '^',
+                    |_, _| None,
+                    f64::powf,
+                ),
+            },
+            Value::Decimal(x) => match rhs {
+                Value::Complex(_) => complex_pow(Value::Decimal(x), rhs),
+                _ => decimal_oper(Value::Decimal(x), rhs.clone(), '^', Decimal::pow, f64::powf),
+            },
+            Value::List(_) => Err(list_oper_err()),
+        }
+    }
+}
+
+impl Value {
+    /// Aritmetický operátor +, -, *, /, ^ podle nastavení. V režimu desetinných čísel se
+    /// výsledek, který by z přesných operandů vyšel jako reálné číslo (pi*pi, 2^(1/3)),
+    /// spočítá znovu jako desetinné číslo s nastavenou přesností.
+    pub fn arith_oper(
+        self,
+        oper: &str,
+        rhs: Value,
+        settings: &CalcSettings,
+    ) -> Result<Value, CalcError> {
+        fn apply(lhs: Value, oper: &str, rhs: Value) -> Result<Value, CalcError> {
+            match oper {
+                "+" => lhs + rhs,
+                "-" => lhs - rhs,
+                "*" => lhs * rhs,
+                "/" => lhs / rhs,
+                "^" => lhs.pow(&rhs),
+                _ => Err(CalcError::EvaluateErr(ErrorKind::UnknownOperator {
+                    op: oper.to_string(),
+                })),
+            }
+        }
+
+        let decimal_operands = settings
+            .decimal_digits
+            .filter(|_| self.is_exact_number() && rhs.is_exact_number())
+            .map(|digits| (digits, self.clone(), rhs.clone()));
+        let finite_operands = !self.is_infinite_real() && !rhs.is_infinite_real();
+        match (apply(self, oper, rhs)?, decimal_operands) {
+            (Value::Real(_), Some((digits, lhs, rhs))) => {
+                let lhs = Decimal::working(&lhs, digits).ok_or_else(|| {
+                    CalcError::EvaluateErr(ErrorKind::NotDecimal {
+                        value: lhs.describe(),
+                    })
+                })?;
+                match apply(Value::Decimal(lhs), oper, rhs)? {
+                    Value::Decimal(result) => Ok(Value::Decimal(result.with_digits(digits))),
+                    result => Ok(result),
+                }
+            }
+            // Z konečných operandů vyšlo nekonečno, např. 1e300 * 1e300 v f64
+            (Value::Real(result), None) if result.is_infinite() && finite_operands => {
+                Err(CalcError::EvaluateErr(ErrorKind::Overflow))
+            }
+            (result, _) => Ok(result),
+        }
+    }
+
+    /// Vrací true, pokud je hodnota nekonečné reálné číslo (f64)
+    fn is_infinite_real(&self) -> bool {
+        matches!(self, Value::Real(x) if x.is_infinite())
+    }
+
+    /// Vrací true, pokud je hodnota přesné reálné číslo (ne f64 ani desetinné číslo)
+    fn is_exact_number(&self) -> bool {
+        matches!(
+            self,
+            Value::Integer(_)
+                | Value::BigInt(_)
+                | Value::Rational(_)
+                | Value::PiMultiple(_)
+                | Value::Surd(_)
+        )
+    }
+
+    /// Vrací true, pokud je hodnota záporné reálné číslo
+    pub fn is_negative_number(&self) -> bool {
+        match self {
+            Value::Integer(x) => *x < 0,
+            Value::BigInt(x) => x.sign() == Sign::Minus,
+            Value::Rational(x) => x.is_negative(),
+            Value::Real(x) => *x < 0.0,
+            Value::Decimal(x) => x.is_negative(),
+            Value::PiMultiple(x) => x.is_negative(),
+            Value::Surd(x) => x.is_negative(),
+            _ => false,
+        }
+    }
+
+    /// Vrací true, pokud je hodnota reálné číslo, které není celé
+    fn is_fractional_number(&self) -> bool {
+        match self {
+            Value::Rational(x) => x.to_bigint().is_none(),
+            Value::Real(x) => x.fract() != 0.0,
+            Value::Decimal(x) => !x.is_integer(),
+            Value::PiMultiple(_) | Value::Surd(_) => true,
+            _ => false,
         }
     }
 }
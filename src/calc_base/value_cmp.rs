@@ -0,0 +1,227 @@
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::complex::Complex;
+use crate::calc_base::decimal::{Decimal, GUARD_DIGITS};
+use crate::calc_base::rational::Rational;
+use crate::calc_base::surd::Surd;
+use crate::calc_base::value::Value;
+use crate::s;
+use std::cmp::Ordering;
+
+/// Relativní tolerance pro porovnání, ve kterém vystupuje reálné číslo. Reálná čísla vznikají
+/// zaokrouhlením (např. sin(pi) = 1.2e-16), a tak by přesné porovnání skoro nikdy nevyšlo.
+/// Čísla a, b se považují za stejná, pokud |a - b| <= REAL_TOLERANCE * max(1, |a|, |b|),
+/// tj. pro malá čísla jde o absolutní toleranci, pro velká o relativní.
+pub const REAL_TOLERANCE: f64 = 1e-12;
+
+/// Porovnávací operátor: ==, !=, <, <=, >, >=
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOper {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CmpOper {
+    /// Pokud je symbol porovnávací operátor, vrátí ho
+    pub fn from_symbol(symbol: &str) -> Option<CmpOper> {
+        match symbol {
+            "==" => Some(CmpOper::Eq),
+            "!=" => Some(CmpOper::Ne),
+            "<" => Some(CmpOper::Lt),
+            "<=" => Some(CmpOper::Le),
+            ">" => Some(CmpOper::Gt),
+            ">=" => Some(CmpOper::Ge),
+            _ => None,
+        }
+    }
+}
+
+impl Value {
+    /// Vyhodnotí porovnání self oper other. Čísla lze porovnávat všemi operátory, texty
+    /// a booleany jen na rovnost. Hodnoty různých druhů (např. text a číslo) se nikdy
+    /// nerovnají, ale jejich uspořádání (<, >...) je chyba.
+    pub fn compare_by(&self, oper: CmpOper, other: &Value) -> Result<Value, CalcError> {
+        let result = match oper {
+            CmpOper::Eq => self.equals(other)?,
+            CmpOper::Ne => !self.equals(other)?,
+            _ => {
+                let ordering = self.compare(other)?;
+                match oper {
+                    CmpOper::Lt => ordering == Ordering::Less,
+                    CmpOper::Le => ordering != Ordering::Greater,
+                    CmpOper::Gt => ordering == Ordering::Greater,
+                    _ => ordering != Ordering::Less,
+                }
+            }
+        };
+        Ok(Value::Bool(result))
+    }
+
+    /// Rovnost hodnot. Čísla se porovnávají podle metody compare (reálná s tolerancí),
+    /// texty a booleany přímo. NaN se nerovná ničemu, ani sám sobě.
+    pub fn equals(&self, other: &Value) -> Result<bool, CalcError> {
+        match (self, other) {
+            (Value::Nothing, _) | (_, Value::Nothing) => {
+                Err(CalcError::EvaluateErr(ErrorKind::NotComparable {
+                    left: self.to_plain_string(),
+                    right: other.to_plain_string(),
+                }))
+            }
+            (Value::Text(a), Value::Text(b)) => Ok(a == b),
+            (Value::Bool(a), Value::Bool(b)) => Ok(a == b),
+            (Value::Real(r), _) | (_, Value::Real(r)) if r.is_nan() => Ok(false),
+            (Value::Complex(_), _) | (_, Value::Complex(_)) => self.equals_complex(other),
+            _ if self.is_number() && other.is_number() => {
+                Ok(self.compare(other)? == Ordering::Equal)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Uspořádání dvou čísel pro porovnávací operátory. Integer, BigInt a Rational se
+    /// porovnávají přesně. Pokud je aspoň jedno z čísel reálné, čísla, která se liší méně než
+    /// o toleranci REAL_TOLERANCE, se považují za stejná. Desetinné číslo se s přesným
+    /// považuje za stejné, pokud se liší jen v posledních dvou platných číslicích.
+    pub fn compare(&self, other: &Value) -> Result<Ordering, CalcError> {
+        if !self.is_number() || !other.is_number() {
+            return Err(CalcError::EvaluateErr(ErrorKind::NotOrderable {
+                left: s!(self.type_name()),
+                right: s!(other.type_name()),
+            }));
+        }
+        if matches!(self, Value::Real(_)) || matches!(other, Value::Real(_)) {
+            let (a, b) = (self.as_real()?, other.as_real()?);
+            if a == b || (a - b).abs() <= REAL_TOLERANCE * a.abs().max(b.abs()).max(1.0) {
+                return Ok(Ordering::Equal);
+            }
+        } else if let Some(digits) = Decimal::precision_for(&[self, other], None) {
+            let digits = digits + GUARD_DIGITS;
+            if let (Some(a), Some(b)) = (
+                Decimal::from_value(self, digits),
+                Decimal::from_value(other, digits),
+            ) {
+                if a.nearly_equals(&b) {
+                    return Ok(Ordering::Equal);
+                }
+            }
+        }
+        self.partial_cmp(other).ok_or_else(|| {
+            CalcError::EvaluateErr(ErrorKind::NotComparable {
+                left: self.to_plain_string(),
+                right: other.to_plain_string(),
+            })
+        })
+    }
+
+    /// Rovnost, ve které vystupuje komplexní číslo. Porovnávají se zvlášť reálné a zvlášť
+    /// imaginární části, stejně jako u ostatních čísel (nepřesné části s tolerancí).
+    fn equals_complex(&self, other: &Value) -> Result<bool, CalcError> {
+        match (Complex::from_value(self), Complex::from_value(other)) {
+            (Some(a), Some(b)) => {
+                let ((a_re, a_im), (b_re, b_im)) = (a.parts(), b.parts());
+                Ok(a_re.equals(&b_re)? && a_im.equals(&b_im)?)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn is_number(&self) -> bool {
+        matches!(
+            self,
+            Value::Integer(_)
+                | Value::BigInt(_)
+                | Value::Rational(_)
+                | Value::Real(_)
+                | Value::Decimal(_)
+                | Value::PiMultiple(_)
+                | Value::Surd(_)
+        )
+    }
+
+    /// Číslo převede na zlomek se stejnou hodnotou. NaN a nekonečno zlomkem být nemůžou.
+    fn to_exact_rational(&self) -> Option<Rational> {
+        match self {
+            Value::Integer(i) => Some(Rational::from_int(*i)),
+            Value::BigInt(i) => Some(Rational::from_bigint(i.clone())),
+            Value::Rational(q) => Some(q.clone()),
+            Value::Real(r) => Rational::from_real(*r),
+            Value::Decimal(d) => Some(d.to_rational()),
+            _ => None,
+        }
+    }
+
+    /// Číslo převede na tvar a + b·√n se stejnou hodnotou
+    fn to_exact_surd(&self) -> Option<Surd> {
+        match self {
+            Value::Surd(s) => Some(s.clone()),
+            _ => Surd::from_value(&Value::Rational(self.to_exact_rational()?)),
+        }
+    }
+}
+
+/// Přesné uspořádání hodnot, bez tolerance. Čísla všech typů se porovnávají podle hodnoty
+/// (reálné číslo se převede na zlomek, který mu přesně odpovídá), takže uspořádání je úplné
+/// a tranzitivní, což se hodí pro řazení. Texty se řadí podle kódů znaků, booleany
+/// false < true, seznamy lexikograficky. Hodnoty různých druhů, komplexní čísla (kromě
+/// rovnosti) a NaN porovnat nelze.
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Nothing, Value::Nothing) => Some(Ordering::Equal),
+            (Value::Text(a), Value::Text(b)) => a.partial_cmp(b),
+            (Value::Bool(a), Value::Bool(b)) => a.partial_cmp(b),
+            (Value::List(a), Value::List(b)) => a.partial_cmp(b),
+            (Value::Real(a), Value::Real(b)) => a.partial_cmp(b),
+            // Komplexní čísla uspořádat nelze, jen zjistit, jestli se rovnají
+            (Value::Complex(_), _) | (_, Value::Complex(_)) => {
+                let (a, b) = (Complex::from_value(self)?, Complex::from_value(other)?);
+                let ((a_re, a_im), (b_re, b_im)) = (a.parts(), b.parts());
+                match (a_re.partial_cmp(&b_re)?, a_im.partial_cmp(&b_im)?) {
+                    (Ordering::Equal, Ordering::Equal) => Some(Ordering::Equal),
+                    _ => None,
+                }
+            }
+            (Value::Real(r), _) if r.is_infinite() && other.is_number() => Some(if *r > 0.0 {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            }),
+            (_, Value::Real(r)) if r.is_infinite() && self.is_number() => Some(if *r > 0.0 {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }),
+            (Value::PiMultiple(a), Value::PiMultiple(b)) => Some(a.cmp(b)),
+            // Desetinné číslo je zlomek, násobek π nebo odmocnina se s ním porovná jako desetinné
+            // číslo s větší přesností, na které se už od zlomku liší
+            (Value::Decimal(_), Value::PiMultiple(_) | Value::Surd(_))
+            | (Value::PiMultiple(_) | Value::Surd(_), Value::Decimal(_)) => {
+                let digits = Decimal::precision_for(&[self, other], None)? + GUARD_DIGITS;
+                let (a, b) = (
+                    Decimal::from_value(self, digits)?,
+                    Decimal::from_value(other, digits)?,
+                );
+                Some(a.compare(&b))
+            }
+            // π je iracionální, s jinými čísly se násobek π porovná podle reálné hodnoty
+            (Value::PiMultiple(_), _) | (_, Value::PiMultiple(_)) => {
+                self.as_real().ok()?.partial_cmp(&other.as_real().ok()?)
+            }
+            (Value::Surd(_), _) | (_, Value::Surd(_)) => {
+                self.to_exact_surd()?.compare(&other.to_exact_surd()?)
+            }
+            _ => Some(self.to_exact_rational()?.cmp(&other.to_exact_rational()?)),
+        }
+    }
+}
+
+/// Rovnost podle hodnoty, konzistentní s PartialOrd: 1 == 2/2 == 1.0, ale 0.1 != 1/10,
+/// protože 0.1 v f64 není přesně jedna desetina. Pro toleranci viz metoda equals.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
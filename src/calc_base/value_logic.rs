@@ -0,0 +1,68 @@
+use crate::base::{CalcError, ErrorKind};
+use crate::calc_base::value::Value;
+use crate::s;
+
+/// Binární logický operátor: and, or, xor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogicOper {
+    And,
+    Or,
+    Xor,
+}
+
+impl LogicOper {
+    /// Pokud je symbol logický operátor, vrátí ho
+    pub fn from_symbol(symbol: &str) -> Option<LogicOper> {
+        match symbol {
+            "and" => Some(LogicOper::And),
+            "or" => Some(LogicOper::Or),
+            "xor" => Some(LogicOper::Xor),
+            _ => None,
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            LogicOper::And => "and",
+            LogicOper::Or => "or",
+            LogicOper::Xor => "xor",
+        }
+    }
+
+    /// Vyhodnotí left oper right. Pravý operand se vyhodnotí až funkcí eval_right, a to jen
+    /// tehdy, když na něm výsledek závisí (false and x, true or x se vyhodnotí bez x).
+    pub fn apply(
+        &self,
+        left: Value,
+        eval_right: impl FnOnce() -> Result<Value, CalcError>,
+    ) -> Result<Value, CalcError> {
+        let left = left.as_bool(self.symbol())?;
+        let result = match self {
+            LogicOper::And if !left => false,
+            LogicOper::Or if left => true,
+            LogicOper::Xor => left != eval_right()?.as_bool(self.symbol())?,
+            _ => eval_right()?.as_bool(self.symbol())?,
+        };
+        Ok(Value::Bool(result))
+    }
+}
+
+impl Value {
+    /// Hodnota jako bool. Logické operátory a podmínky nepřevádějí čísla na bool, to by
+    /// skrylo chyby typu "if(x, ...)" místo "if(x > 0, ...)". Parametr context se uvede
+    /// v chybě (název operátoru nebo funkce).
+    pub fn as_bool(&self, context: &str) -> Result<bool, CalcError> {
+        match self {
+            Value::Bool(b) => Ok(*b),
+            _ => Err(CalcError::EvaluateErr(ErrorKind::ExpectedBool {
+                context: s!(context),
+                got: s!(self.type_name()),
+            })),
+        }
+    }
+
+    /// Logická negace (not x, !x)
+    pub fn not(&self) -> Result<Value, CalcError> {
+        Ok(Value::Bool(!self.as_bool("not")?))
+    }
+}
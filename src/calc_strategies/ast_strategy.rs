@@ -0,0 +1,440 @@
+use crate::base::{CalcError, ErrorKind, Expected, Span};
+use crate::calc_base::context::{CalcContext, MAX_NESTING_DEPTH};
+use crate::calc_base::expr::Expr;
+use crate::calc_base::func_call::FuncCall;
+use crate::calc_base::lazy_funcs::{call_lazy_func, is_lazy_func};
+use crate::calc_base::lexer::{tokenize, Token, TokenKind};
+use crate::calc_base::locale::Locale;
+use crate::calc_base::settings::CalcSettings;
+use crate::calc_base::trig_funcs::{convert_angle, AngleUnit};
+use crate::calc_base::value::{is_named_const, named_const, Value};
+use crate::calc_base::value_cmp::CmpOper;
+use crate::calc_base::value_logic::LogicOper;
+use crate::calc_strategies::common::{
+    binary_oper, check_syntax, parse_func_definition, Assoc, UNARY_OPERS, UNARY_PRIORITY,
+};
+use crate::calc_strategies::ICalculatorStrategy;
+use std::ops::Neg;
+
+/// Strategie, která výraz nejprve rozloží na tokeny, z nich sestaví syntaktický strom (AST)
+/// a ten pak vyhodnotí. Text se prochází jen jednou, takže i dlouhé výrazy se spočítají rychle.
+/// Hloubka rekurze odpovídá jen zanoření závorek a volání funkcí, ne délce výrazu.
+#[derive(Default, Debug)]
+pub struct AstStrategy {
+    root: Option<AstNode>,
+}
+
+/// Uzel syntaktického stromu. Pamatuje si úsek vstupu, ke kterému patří, aby se chyba
+/// při vyhodnocení dala ukázat na konkrétním místě výrazu.
+#[derive(Debug, Clone)]
+pub struct AstNode {
+    pub kind: AstNodeKind,
+    /// Token, který uzel reprezentuje (operátor, název funkce, literál)
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum AstNodeKind {
+    /// Atomická hodnota - číslo, text nebo bool
+    Literal(Value),
+    /// Pojmenovaná konstanta (pi, e...). Hodnota se zjistí až při vyhodnocení, protože
+    /// může záviset na nastavení (v režimu desetinných čísel je e desetinné číslo).
+    Constant(String),
+    /// Úhel s příponou jednotky (30deg). Na jednotku z nastavení se převede až při
+    /// vyhodnocení, protože nastavení se může mezi výpočty změnit.
+    AngleLiteral { value: Value, unit: AngleUnit },
+    /// Unární operátor (např. mínus v -5)
+    UnaryOper {
+        oper: &'static str,
+        operand: Box<AstNode>,
+    },
+    /// Binární operátor s levým a pravým operandem
+    BinaryOper {
+        oper: &'static str,
+        left: Box<AstNode>,
+        right: Box<AstNode>,
+    },
+    /// Volání funkce. Parametry jsou opět podstromy.
+    FuncCall { name: String, params: Vec<AstNode> },
+    /// Proměnná, jejíž hodnota se zjistí z kontextu až při vyhodnocení
+    Variable(String),
+    /// Přiřazení hodnoty do proměnné, např. x = 3/7. Výsledkem je přiřazená hodnota.
+    Assign { name: String, value: Box<AstNode> },
+    /// Definice uživatelské funkce, např. f(x, y) = x^2 + y/3. Tělo zůstává jako text,
+    /// protože se ukládá do kontextu a při každém volání se znovu parsuje.
+    FuncDef {
+        name: String,
+        params: Vec<String>,
+        body: String,
+    },
+}
+
+impl<'expr> ICalculatorStrategy<'expr> for AstStrategy {
+    fn parse(&mut self, math_expr: Expr<'expr>, settings: &CalcSettings) -> Result<(), CalcError> {
+        // Syntaktické chyby najde check_syntax stejně jako u ostatních strategií, parser
+        // už je tedy hlásí jen pro jistotu
+        check_syntax(math_expr.as_str(), settings.locale)?;
+        self.root = Some(Self::build_tree(math_expr.as_str(), settings.locale)?);
+        Ok(())
+    }
+
+    fn evaluate(&mut self, ctx: &mut CalcContext) -> Result<Value, CalcError> {
+        match &self.root {
+            Some(root) => Self::evaluate_node(root, ctx),
+            None => Err(CalcError::EvaluateErr(ErrorKind::NotParsed)),
+        }
+    }
+
+    fn parse_func_call(
+        &self,
+        expr: Expr<'expr>,
+        ctx: &mut CalcContext,
+    ) -> Result<FuncCall, CalcError> {
+        match Self::build_tree(expr.as_str(), ctx.settings().locale)?.kind {
+            AstNodeKind::FuncCall { name, params } => {
+                let params = params
+                    .iter()
+                    .map(|param| Self::evaluate_node(param, ctx))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(FuncCall::new(&name, params))
+            }
+            _ => Err(CalcError::EvaluateErr(ErrorKind::NotFuncCall)),
+        }
+    }
+}
+
+impl AstStrategy {
+    /// Rozloží text na tokeny a sestaví z nich strom. Celý vstup musí být spotřebován.
+    /// Vstupem může být výraz, přiřazení do proměnné nebo definice funkce.
+    fn build_tree(expr: &str, locale: Locale) -> Result<AstNode, CalcError> {
+        let tokens = tokenize(expr, locale)?;
+        if let Some(def) = parse_func_definition(expr, &tokens) {
+            // Tělo se zkontroluje hned, aby se syntaktická chyba ukázala už při definici
+            Self::build_expr_tree(def.body, locale).map_err(|e| e.shift_span(def.body_offset))?;
+            return Ok(AstNode {
+                kind: AstNodeKind::FuncDef {
+                    name: def.name,
+                    params: def.params,
+                    body: def.body.to_string(),
+                },
+                span: tokens[0].span,
+            });
+        }
+        Parser::new(tokens, expr.len(), locale).parse_all(Parser::parse_statement)
+    }
+
+    /// Sestaví strom čistého výrazu bez přiřazení (např. tělo uživatelské funkce)
+    fn build_expr_tree(expr: &str, locale: Locale) -> Result<AstNode, CalcError> {
+        Parser::new(tokenize(expr, locale)?, expr.len(), locale)
+            .parse_all(|parser| parser.parse_expr(0))
+    }
+
+    /// Rekurzivně vyhodnotí podstrom. Výsledek se zjednoduší na co nejjednodušší typ.
+    /// Chybám bez pozice se přiřadí pozice uzlu, ve kterém vznikly.
+    fn evaluate_node(node: &AstNode, ctx: &mut CalcContext) -> Result<Value, CalcError> {
+        ctx.nested(|ctx| Self::evaluate_node_kind(&node.kind, ctx))
+            .map_err(|err| err.with_span(node.span))
+    }
+
+    fn evaluate_node_kind(kind: &AstNodeKind, ctx: &mut CalcContext) -> Result<Value, CalcError> {
+        let result = match kind {
+            AstNodeKind::Literal(value) => value.clone(),
+            AstNodeKind::Constant(name) => named_const(name, ctx.settings()).ok_or_else(|| {
+                CalcError::EvaluateErr(ErrorKind::UnknownConstant { name: name.clone() })
+            })?,
+            AstNodeKind::AngleLiteral { value, unit } => {
+                convert_angle(value.clone(), *unit, ctx.settings().angle_unit)?
+            }
+            AstNodeKind::Variable(name) => ctx.get_var(name).cloned().ok_or_else(|| {
+                CalcError::EvaluateErr(ErrorKind::UndefinedVariable { name: name.clone() })
+            })?,
+            AstNodeKind::Assign { name, value } => {
+                let value = Self::evaluate_node(value, ctx)?;
+                ctx.set_var(name, value.clone())?;
+                value
+            }
+            AstNodeKind::FuncDef { name, params, body } => {
+                ctx.define_func(name, params.clone(), body)?;
+                Value::Nothing
+            }
+            AstNodeKind::UnaryOper { oper, operand } => {
+                let operand = Self::evaluate_node(operand, ctx)?;
+                match *oper {
+                    "-" => operand.neg()?,
+                    "+" => operand,
+                    "!" | "not" => operand.not()?,
+                    _ => {
+                        return Err(CalcError::EvaluateErr(ErrorKind::UnknownUnaryOperator {
+                            op: oper.to_string(),
+                        }))
+                    }
+                }
+            }
+            AstNodeKind::BinaryOper { oper, left, right } => {
+                let left = Self::evaluate_node(left, ctx)?;
+                if let Some(logic_oper) = LogicOper::from_symbol(oper) {
+                    return logic_oper.apply(left, || Self::evaluate_node(right, ctx));
+                }
+                let right = Self::evaluate_node(right, ctx)?;
+                if let Some(cmp_oper) = CmpOper::from_symbol(oper) {
+                    return left.compare_by(cmp_oper, &right);
+                }
+                left.arith_oper(oper, right, ctx.settings())?
+            }
+            AstNodeKind::FuncCall { name, params } if is_lazy_func(name) => {
+                return call_lazy_func(name, params.len(), |idx| {
+                    Self::evaluate_node(&params[idx], ctx)
+                });
+            }
+            AstNodeKind::FuncCall { name, params } => {
+                let params = params
+                    .iter()
+                    .map(|param| Self::evaluate_node(param, ctx))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if ctx.get_func(name).is_some() {
+                    ctx.call_user_func(name, params, |body, locale, ctx| {
+                        Self::evaluate_node(&Self::build_expr_tree(body, locale)?, ctx)
+                    })?
+                } else {
+                    FuncCall::new(name, params).eval(ctx)?
+                }
+            }
+        };
+        result.simplify_type_move()
+    }
+}
+
+/// Sestavuje strom z tokenů metodou precedence climbing. Priority a asociativita operátorů
+/// jsou stejné jako u ostatních strategií (viz tabulka BINARY_OPERS).
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    expr_len: usize,
+    /// Hloubka zanoření parse_expr, omezená na MAX_NESTING_DEPTH
+    depth: usize,
+    /// Zápis číselných literálů (desetinná tečka, nebo čárka)
+    locale: Locale,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>, expr_len: usize, locale: Locale) -> Parser {
+        Parser {
+            tokens,
+            pos: 0,
+            expr_len,
+            depth: 0,
+            locale,
+        }
+    }
+
+    /// Načte vstup zadanou funkcí a zkontroluje, že byly spotřebovány všechny tokeny
+    fn parse_all(
+        mut self,
+        parse: impl FnOnce(&mut Parser) -> Result<AstNode, CalcError>,
+    ) -> Result<AstNode, CalcError> {
+        let root = parse(&mut self)?;
+        match self.peek() {
+            None => Ok(root),
+            Some(token) => Err(CalcError::ParseErr(ErrorKind::UnexpectedToken {
+                token: token.kind.to_string(),
+                expected: None,
+            })
+            .with_span(token.span)),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_kind(&self) -> Option<&TokenKind> {
+        self.peek().map(|token| &token.kind)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Chyba pro situaci, kdy na daném místě stojí nečekaný token nebo výraz předčasně končí
+    fn unexpected(&self, token: Option<Token>, expected: Expected) -> CalcError {
+        match token {
+            Some(token) => CalcError::ParseErr(ErrorKind::UnexpectedToken {
+                token: token.kind.to_string(),
+                expected: Some(expected),
+            })
+            .with_span(token.span),
+            None => CalcError::ParseErr(ErrorKind::UnexpectedEnd { expected })
+                .with_span(Span::new(self.expr_len, self.expr_len)),
+        }
+    }
+
+    /// Načte celý vstup. Ten může být buď výraz, nebo přiřazení do proměnné: název = výraz.
+    /// Přiřazení se dají řetězit (x = y = 1), ale nesmí být uvnitř výrazu.
+    fn parse_statement(&mut self) -> Result<AstNode, CalcError> {
+        if let (Some(TokenKind::Ident(name)), Some(TokenKind::Assign)) = (
+            self.peek_kind(),
+            self.tokens.get(self.pos + 1).map(|token| &token.kind),
+        ) {
+            let name = name.clone();
+            self.next();
+            let span = self.next().map(|token| token.span).unwrap_or_default();
+            let value = self.parse_statement()?;
+            return Ok(AstNode {
+                kind: AstNodeKind::Assign {
+                    name,
+                    value: Box::new(value),
+                },
+                span,
+            });
+        }
+        self.parse_expr(0)
+    }
+
+    /// Načte výraz, jehož operátory mají prioritu alespoň min_priority. Každá závorka
+    /// a unární operátor parsuje svůj operand o úroveň hlouběji.
+    fn parse_expr(&mut self, min_priority: i32) -> Result<AstNode, CalcError> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            let span = self
+                .peek()
+                .map_or(Span::new(self.expr_len, self.expr_len), |token| token.span);
+            return Err(CalcError::EvaluateErr(ErrorKind::NestingTooDeep {
+                max: MAX_NESTING_DEPTH,
+            })
+            .with_span(span));
+        }
+        self.depth += 1;
+        let result = self.parse_operators(min_priority);
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_operators(&mut self, min_priority: i32) -> Result<AstNode, CalcError> {
+        let mut left = self.parse_unary()?;
+        while let Some(TokenKind::Oper(symbol)) = self.peek_kind() {
+            let Some(oper) = binary_oper(symbol) else {
+                break;
+            };
+            if oper.priority < min_priority {
+                break;
+            }
+            let span = self.next().map(|token| token.span).unwrap_or_default();
+            // Levě asociativní operátor nesmí mít vpravo operátor stejné priority, pravě asociativní ano
+            let right_min_priority = match oper.assoc {
+                Assoc::Left => oper.priority + 1,
+                Assoc::Right => oper.priority,
+            };
+            let right = self.parse_expr(right_min_priority)?;
+            left = AstNode {
+                kind: AstNodeKind::BinaryOper {
+                    oper: oper.symbol,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            };
+        }
+        Ok(left)
+    }
+
+    /// Unární operátory (znaménka, negace) si jako operand vezmou vše, co váže těsněji než
+    /// UNARY_PRIORITY, tj. -2^2 = -(2^2), ale -2*3 = (-2)*3
+    fn parse_unary(&mut self) -> Result<AstNode, CalcError> {
+        if let Some(TokenKind::Oper(oper)) = self.peek_kind() {
+            let oper = *oper;
+            if !UNARY_OPERS.contains(&oper) {
+                let token = self.next();
+                return Err(self.unexpected(token, Expected::Value));
+            }
+            let span = self.next().map(|token| token.span).unwrap_or_default();
+            let operand = self.parse_expr(UNARY_PRIORITY)?;
+            return Ok(AstNode {
+                kind: AstNodeKind::UnaryOper {
+                    oper,
+                    operand: Box::new(operand),
+                },
+                span,
+            });
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<AstNode, CalcError> {
+        let Some(token) = self.next() else {
+            return Err(self.unexpected(None, Expected::Value));
+        };
+        let span = token.span;
+        let kind = match token.kind {
+            TokenKind::Number(number) => match AngleUnit::split_literal(&number) {
+                Some((number, unit)) => AstNodeKind::AngleLiteral {
+                    value: Value::parse_number(number, self.locale)
+                        .map_err(|e| e.with_span(span))?,
+                    unit,
+                },
+                None => AstNodeKind::Literal(
+                    Value::parse_number(&number, self.locale).map_err(|e| e.with_span(span))?,
+                ),
+            },
+            TokenKind::Text(text) => AstNodeKind::Literal(Value::Text(text)),
+            TokenKind::Ident(name) => {
+                if self.peek_kind() == Some(&TokenKind::LeftBracket) {
+                    self.next();
+                    let params = self.parse_params()?;
+                    AstNodeKind::FuncCall { name, params }
+                } else if is_named_const(&name).is_some() {
+                    // Pojmenované konstanty a booleany mají přednost před proměnnými
+                    AstNodeKind::Constant(name)
+                } else if let Ok(value) = Value::parse(&name) {
+                    AstNodeKind::Literal(value)
+                } else {
+                    AstNodeKind::Variable(name)
+                }
+            }
+            TokenKind::LeftBracket => {
+                let inner = self.parse_expr(0)?;
+                self.expect_right_bracket()?;
+                return Ok(inner);
+            }
+            _ => return Err(self.unexpected(Some(token), Expected::Value)),
+        };
+        Ok(AstNode { kind, span })
+    }
+
+    /// Načte parametry funkce oddělené čárkami (středníky v českém zápisu). Levá závorka už
+    /// je načtená.
+    fn parse_params(&mut self) -> Result<Vec<AstNode>, CalcError> {
+        let mut params = vec![];
+        if self.peek_kind() == Some(&TokenKind::RightBracket) {
+            self.next();
+            return Ok(params);
+        }
+        loop {
+            params.push(self.parse_expr(0)?);
+            match self.next() {
+                Some(Token {
+                    kind: TokenKind::Comma,
+                    ..
+                }) => {}
+                Some(Token {
+                    kind: TokenKind::RightBracket,
+                    ..
+                }) => return Ok(params),
+                token => {
+                    let expected = Expected::SeparatorOrRightBracket(self.locale.param_separator());
+                    return Err(self.unexpected(token, expected));
+                }
+            }
+        }
+    }
+
+    fn expect_right_bracket(&mut self) -> Result<(), CalcError> {
+        match self.next() {
+            Some(Token {
+                kind: TokenKind::RightBracket,
+                ..
+            }) => Ok(()),
+            token => Err(self.unexpected(token, Expected::RightBracket)),
+        }
+    }
+}
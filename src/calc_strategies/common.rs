@@ -1,92 +1,332 @@
-use crate::base::CalcError;
+use crate::base::{CalcError, ErrorKind, Expected, Span};
 use crate::calc_base::expr::Expr;
-use crate::s;
-
-/// Pokud je symbol operátor, vrací jeho prioritu
-pub fn is_operator_get_priority(symbol: char) -> Option<i32> {
-    match symbol {
-        '+' => Some(1),
-        '-' => Some(1),
-        '*' => Some(2),
-        '/' => Some(2),
-        '^' => Some(3),
-        _ => None, // není to znak operátoru
+use crate::calc_base::lexer::{bracket_depths, tokenize, Token, TokenKind};
+use crate::calc_base::locale::Locale;
+use crate::calc_base::trig_funcs::AngleUnit;
+use crate::calc_base::value::Value;
+
+/// Asociativita binárního operátoru určuje, jak se závorkuje řetěz operátorů se stejnou
+/// prioritou. Levá: 8/4/2 = (8/4)/2. Pravá: 2^3^2 = 2^(3^2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+/// Popis binárního operátoru. Čím vyšší priorita, tím těsněji operátor váže své operandy.
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryOper {
+    pub symbol: &'static str,
+    pub priority: i32,
+    pub assoc: Assoc,
+}
+
+impl BinaryOper {
+    const fn new(symbol: &'static str, priority: i32, assoc: Assoc) -> BinaryOper {
+        BinaryOper {
+            symbol,
+            priority,
+            assoc,
+        }
     }
 }
 
-pub fn trim_brackets(expr: Expr) -> Expr {
-    let mut subexpr = expr.as_str();
-    while subexpr.len() > 2 && subexpr.starts_with('(') && subexpr.ends_with(')') {
-        let subexpr_wout_brackets = &subexpr[1..subexpr.len() - 1];
-        if check_brackets_and_quots_simple(subexpr_wout_brackets) {
-            subexpr = subexpr_wout_brackets;
-        } else {
+/// Tabulka binárních operátorů. Všechny strategie musí gramatiku brát odsud.
+/// Logické operátory váží nejvolněji (or < xor < and), pak porovnání, takže
+/// x > 0 and x < 1 = (x > 0) and (x < 1). Rovnost váže volněji než nerovnosti:
+/// a < b == c < d = (a < b) == (c < d). Porovnání váží volněji než aritmetika.
+pub const BINARY_OPERS: &[BinaryOper] = &[
+    BinaryOper::new("or", 1, Assoc::Left),
+    BinaryOper::new("xor", 2, Assoc::Left),
+    BinaryOper::new("and", 3, Assoc::Left),
+    BinaryOper::new("==", 4, Assoc::Left),
+    BinaryOper::new("!=", 4, Assoc::Left),
+    BinaryOper::new("<", 5, Assoc::Left),
+    BinaryOper::new("<=", 5, Assoc::Left),
+    BinaryOper::new(">", 5, Assoc::Left),
+    BinaryOper::new(">=", 5, Assoc::Left),
+    BinaryOper::new("+", 6, Assoc::Left),
+    BinaryOper::new("-", 6, Assoc::Left),
+    BinaryOper::new("*", 7, Assoc::Left),
+    BinaryOper::new("/", 7, Assoc::Left),
+    BinaryOper::new("^", 9, Assoc::Right),
+];
+
+/// Unární operátory: znaménka a logická negace. Mají jedinou společnou prioritu.
+pub const UNARY_OPERS: &[&str] = &["+", "-", "!", "not"];
+
+/// Priorita unárních operátorů. Váže volněji než mocnina, ale těsněji než násobení:
+/// -2^2 = -(2^2), 2^-1 = 2^(-1), -2*3 = (-2)*3. Negace váže stejně jako v jazyce C:
+/// not a == b = (not a) == b, negaci porovnání je potřeba uzávorkovat.
+pub const UNARY_PRIORITY: i32 = 8;
+
+/// Pokud je symbol binární operátor, vrací jeho popis z tabulky
+pub fn binary_oper(symbol: &str) -> Option<&'static BinaryOper> {
+    BINARY_OPERS.iter().find(|oper| oper.symbol == symbol)
+}
+
+/// Operátor je unární, pokud před ním nestojí operand, tj. je na začátku výrazu,
+/// za jiným operátorem, za levou závorkou nebo za čárkou.
+pub fn is_unary_position(prev_token: Option<&Token>) -> bool {
+    matches!(
+        prev_token.map(|token| &token.kind),
+        None | Some(TokenKind::Oper(_) | TokenKind::LeftBracket | TokenKind::Comma)
+    )
+}
+
+pub fn trim_brackets(expr: Expr, locale: Locale) -> Expr {
+    let mut expr = expr;
+    while let Ok(tokens) = tokenize(expr.as_str(), locale) {
+        if tokens.len() < 3 || !encloses_whole_expr(&tokens) {
             break;
         }
+        let (first, last) = (&tokens[0], &tokens[tokens.len() - 1]);
+        expr = expr.slice(first.span.end..last.span.start);
+    }
+    expr
+}
+
+/// Vrací true, pokud je celý výraz uzavřen v jednom páru závorek, např. (1+2), ale ne (1)+(2)
+fn encloses_whole_expr(tokens: &[Token]) -> bool {
+    if tokens.len() < 2
+        || tokens[0].kind != TokenKind::LeftBracket
+        || tokens[tokens.len() - 1].kind != TokenKind::RightBracket
+    {
+        return false;
+    }
+    match bracket_depths(tokens) {
+        // Uvnitř krajních závorek nesmí hloubka klesnout na nulu
+        Ok(depths) => depths[1..depths.len() - 1].iter().all(|depth| *depth > 0),
+        Err(_) => false,
+    }
+}
+
+/// Vrací true, pokud má výraz tvar volání funkce: název(parametry)
+pub fn is_func_call_shape(tokens: &[Token]) -> bool {
+    matches!(
+        tokens.first(),
+        Some(Token {
+            kind: TokenKind::Ident(_),
+            ..
+        })
+    ) && encloses_whole_expr(&tokens[1..])
+}
+
+/// Definice uživatelské funkce: název(param1, param2, ...) = tělo
+#[derive(Debug)]
+pub struct FuncDefinition<'a> {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: &'a str,
+    /// Pozice těla funkce ve výrazu (v bajtech)
+    pub body_offset: usize,
+}
+
+/// Pokud má výraz tvar definice funkce, rozloží ho na název, parametry a tělo
+pub fn parse_func_definition<'a>(expr: &'a str, tokens: &[Token]) -> Option<FuncDefinition<'a>> {
+    let [Token {
+        kind: TokenKind::Ident(name),
+        ..
+    }, Token {
+        kind: TokenKind::LeftBracket,
+        ..
+    }, rest @ ..] = tokens
+    else {
+        return None;
+    };
+
+    let mut params = vec![];
+    let mut rest = rest.iter();
+    // Parametry jsou jen názvy oddělené čárkami, za závorkou musí následovat přiřazení
+    loop {
+        match rest.next().map(|token| &token.kind) {
+            Some(TokenKind::Ident(param)) => {
+                params.push(param.clone());
+                match rest.next().map(|token| &token.kind) {
+                    Some(TokenKind::Comma) => continue,
+                    Some(TokenKind::RightBracket) => break,
+                    _ => return None,
+                }
+            }
+            Some(TokenKind::RightBracket) if params.is_empty() => break,
+            _ => return None,
+        }
     }
-    Expr::new(subexpr)
+    let assign = rest
+        .next()
+        .filter(|token| token.kind == TokenKind::Assign)?;
+    Some(FuncDefinition {
+        name: name.clone(),
+        params,
+        body: &expr[assign.span.end..],
+        body_offset: assign.span.end,
+    })
 }
 
 /// Ještě před zahájením výpočtu je potřeba zkontrolovat správnost výrazu. Tj. správnost postavení
 /// závorek a stringů (každý string musí mít ukončovací uvozovku)
-pub fn check_brackets_and_quots(expr: &str) -> Result<(), CalcError> {
-    let mut is_in_string = false;
-    let mut le = 0;
-    let mut ri = 0;
-    for (i, c) in expr.chars().enumerate() {
-        if c == '"' {
-            is_in_string = !is_in_string;
-        } else if !is_in_string {
-            if c == '(' {
-                le += 1;
-            } else if c == ')' {
-                ri += 1;
-                if ri > le {
-                    return Err(CalcError::ParseErr(format!(
-                        "Na pozici {i} počet pravých závorek předběhl počet levých závorek"
-                    )));
-                }
+pub fn check_brackets_and_quots(expr: &str, locale: Locale) -> Result<(), CalcError> {
+    let tokens = tokenize(expr, locale)?;
+    let mut open_brackets = vec![]; // Pozice levých závorek, které ještě nebyly uzavřeny
+    for token in &tokens {
+        match token.kind {
+            TokenKind::LeftBracket => open_brackets.push(token.span),
+            TokenKind::RightBracket => {
+                open_brackets.pop().ok_or_else(|| {
+                    CalcError::ParseErr(ErrorKind::UnbalancedBrackets {
+                        pos: token.span.start,
+                        bracket: ')',
+                    })
+                    .with_span(token.span)
+                })?;
             }
+            _ => {}
         }
     }
 
-    return if is_in_string {
-        Err(CalcError::ParseErr(s!(
-            "Textovému výrazu chybí ukončovací uvozovka."
-        )))
-    } else if le == ri {
-        Ok(())
-    } else {
-        Err(CalcError::ParseErr(s!(
-            "Počet levých a pravých závorek musí být stejný"
-        )))
+    return match open_brackets.first() {
+        None => Ok(()),
+        Some(unclosed) => Err(CalcError::ParseErr(ErrorKind::UnbalancedBrackets {
+            pos: unclosed.start,
+            bracket: '(',
+        })
+        .with_span(*unclosed)),
+    };
+}
+
+/// Závorka, uvnitř které se kontroluje syntaxe: obyčejná, nebo parametry funkce
+#[derive(Debug, Clone, Copy)]
+enum OpenBracket {
+    Group,
+    Params,
+}
+
+/// Zkontroluje syntaxi výrazu, přiřazení nebo definice funkce (včetně těla a číselných
+/// literálů) jedním průchodem přes tokeny, bez sestavování jakékoli struktury. Volají ji
+/// všechny strategie při parse, takže syntaktické chyby hlásí všechny stejně.
+pub fn check_syntax(expr: &str, locale: Locale) -> Result<(), CalcError> {
+    let tokens = tokenize(expr, locale)?;
+    let statement = match parse_func_definition(expr, &tokens) {
+        // Tělo funkce je čistý výraz, přiřazení v něm být nesmí
+        Some(def) => {
+            let body_start = tokens
+                .iter()
+                .position(|token| token.span.start >= def.body_offset)
+                .unwrap_or(tokens.len());
+            &tokens[body_start..]
+        }
+        None => {
+            // Přiřazení se dají řetězit (x = y = 1), ale jen na začátku vstupu
+            let mut rest = tokens.as_slice();
+            while let [Token {
+                kind: TokenKind::Ident(_),
+                ..
+            }, Token {
+                kind: TokenKind::Assign,
+                ..
+            }, tail @ ..] = rest
+            {
+                rest = tail;
+            }
+            rest
+        }
     };
+    check_expr_syntax(statement, expr.len(), locale)
 }
 
-pub fn check_brackets_and_quots_simple(expr: &str) -> bool {
-    let mut is_in_string = false;
-    let mut le = 0;
-    let mut ri = 0;
-    for c in expr.chars() {
-        if c == '"' {
-            is_in_string = !is_in_string;
-        } else if !is_in_string {
-            if c == '(' {
-                le += 1;
-            } else if c == ')' {
-                ri += 1;
-                if ri > le {
-                    return false;
+/// Zkontroluje, že se v tokenech střídají hodnoty (před kterými mohou být unární operátory)
+/// s binárními operátory a že závorky a parametry funkcí jsou na svém místě
+fn check_expr_syntax(tokens: &[Token], expr_len: usize, locale: Locale) -> Result<(), CalcError> {
+    // Co může následovat za hodnotou uvnitř závorky, nebo na nejvyšší úrovni (None)
+    let expected_after = |bracket: Option<&OpenBracket>| match bracket {
+        None => None,
+        Some(OpenBracket::Group) => Some(Expected::RightBracket),
+        Some(OpenBracket::Params) => {
+            Some(Expected::SeparatorOrRightBracket(locale.param_separator()))
+        }
+    };
+    let unexpected = |token: &Token, expected: Option<Expected>| {
+        CalcError::ParseErr(ErrorKind::UnexpectedToken {
+            token: token.kind.to_string(),
+            expected,
+        })
+        .with_span(token.span)
+    };
+    let mut open_brackets = vec![];
+    let mut expects_value = true;
+    let mut tokens = tokens.iter().peekable();
+    while let Some(token) = tokens.next() {
+        if expects_value {
+            match &token.kind {
+                TokenKind::Oper(oper) if UNARY_OPERS.contains(oper) => {}
+                TokenKind::Number(number) => {
+                    let number = AngleUnit::split_literal(number).map_or(number.as_str(), |n| n.0);
+                    Value::parse_number(number, locale).map_err(|e| e.with_span(token.span))?;
+                    expects_value = false;
+                }
+                TokenKind::Text(_) => expects_value = false,
+                TokenKind::Ident(_) => {
+                    let is_call = tokens
+                        .next_if(|next| next.kind == TokenKind::LeftBracket)
+                        .is_some();
+                    // Funkce s parametry čeká hodnotu prvního z nich, funkce bez nich je hodnota
+                    if is_call
+                        && tokens
+                            .next_if(|next| next.kind == TokenKind::RightBracket)
+                            .is_none()
+                    {
+                        open_brackets.push(OpenBracket::Params);
+                    } else {
+                        expects_value = false;
+                    }
                 }
+                TokenKind::LeftBracket => open_brackets.push(OpenBracket::Group),
+                _ => return Err(unexpected(token, Some(Expected::Value))),
+            }
+        } else {
+            match (&token.kind, open_brackets.last()) {
+                (TokenKind::Oper(oper), _) if binary_oper(oper).is_some() => expects_value = true,
+                (TokenKind::RightBracket, Some(_)) => {
+                    open_brackets.pop();
+                }
+                (TokenKind::Comma, Some(OpenBracket::Params)) => expects_value = true,
+                (_, bracket) => return Err(unexpected(token, expected_after(bracket))),
             }
         }
     }
-
-    return if is_in_string {
-        false
-    } else if le == ri {
-        true
-    } else {
-        false
+    let expected = match (expects_value, open_brackets.last()) {
+        (true, _) => Expected::Value,
+        (false, None) => return Ok(()),
+        (false, bracket) => expected_after(bracket).unwrap_or(Expected::Value),
     };
+    Err(CalcError::ParseErr(ErrorKind::UnexpectedEnd { expected })
+        .with_span(Span::new(expr_len, expr_len)))
+}
+
+/// Rozdělí tokeny na úseky podle tokenů, které leží mimo závorky a splňují podmínku
+/// is_delim. Vrací rozsahy indexů tokenů jednotlivých úseků (oddělovače do nich nepatří).
+pub fn split_top_level(
+    tokens: &[Token],
+    is_delim: impl Fn(&Token) -> bool,
+) -> Vec<std::ops::Range<usize>> {
+    let depths = bracket_depths(tokens).unwrap_or_else(|_| vec![0; tokens.len()]);
+    let mut parts = vec![];
+    let mut part_begin = 0;
+    for (idx, token) in tokens.iter().enumerate() {
+        if depths[idx] == 0 && is_delim(token) {
+            parts.push(part_begin..idx);
+            part_begin = idx + 1;
+        }
+    }
+    parts.push(part_begin..tokens.len());
+    parts
+}
+
+/// Vrátí úsek výrazu, který pokrývají dané tokeny výrazu (od začátku prvního po konec
+/// posledního). Pozice tokenů jsou v bajtech, takže se jimi dá výraz přímo řezat.
+pub fn tokens_source<'a>(src: &Expr<'a>, tokens: &[Token]) -> Expr<'a> {
+    match (tokens.first(), tokens.last()) {
+        (Some(first), Some(last)) => src.slice(first.span.start..last.span.end),
+        _ => src.slice(0..0),
+    }
 }
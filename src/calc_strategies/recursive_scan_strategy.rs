@@ -1,11 +1,18 @@
-use crate::base::CalcError;
+use crate::base::{CalcError, ErrorKind, Span};
+use crate::calc_base::context::CalcContext;
 use crate::calc_base::expr::Expr;
 use crate::calc_base::func_call::FuncCall;
-use crate::calc_base::value::Value;
+use crate::calc_base::lazy_funcs::{call_lazy_func, is_lazy_func};
+use crate::calc_base::lexer::{bracket_depths, tokenize, Token, TokenKind};
+use crate::calc_base::locale::Locale;
+use crate::calc_base::settings::CalcSettings;
+use crate::calc_base::trig_funcs::{convert_angle, AngleUnit};
+use crate::calc_base::value::{named_const, Value};
+use crate::calc_base::value_cmp::CmpOper;
+use crate::calc_base::value_logic::LogicOper;
 use crate::calc_strategies::common::*;
 use crate::calc_strategies::ICalculatorStrategy;
 use crate::s;
-use regex::Regex;
 use std::ops::Neg;
 
 /// Strategie, která jen čte výraz jako text a rekurzivně spočítá výsledek. Neprovádí žádné
@@ -14,228 +21,365 @@ use std::ops::Neg;
 #[derive(Default, Debug)]
 pub struct RecursiveScanStrategy<'expr> {
     math_expr: Expr<'expr>,
+    /// Zápis čísel a oddělovačů ve výrazu. Určí se při parse, tělo uživatelské funkce se
+    /// čte v zápisu, ve kterém byla funkce definovaná.
+    locale: Locale,
 }
 
 impl<'expr> ICalculatorStrategy<'expr> for RecursiveScanStrategy<'expr> {
-    ///Tato strategie nepoužívá žádnou speciální strukturu, jen rekurzivně skenuje text
-    fn parse(&mut self, math_expr: Expr<'expr>) -> Result<(), CalcError> {
+    /// Tato strategie nepoužívá žádnou speciální strukturu, jen rekurzivně skenuje text.
+    /// Syntaxi ale ověří předem (viz check_syntax), vyhodnocení pak pracuje jen se správným
+    /// výrazem.
+    fn parse(&mut self, math_expr: Expr<'expr>, settings: &CalcSettings) -> Result<(), CalcError> {
+        check_syntax(math_expr.as_str(), settings.locale)?;
         self.math_expr = math_expr;
+        self.locale = settings.locale;
         Ok(())
     }
 
-    fn evaluate(&mut self) -> Result<Value, CalcError> {
-        self.evaluate_rec_simplify(self.math_expr.clone())
+    fn evaluate(&mut self, ctx: &mut CalcContext) -> Result<Value, CalcError> {
+        self.evaluate_statement(self.math_expr.clone(), ctx)
     }
 
-    fn parse_func_call(&self, expr: Expr) -> Result<FuncCall, CalcError> {
-        let func_call_regex = Regex::new(r"^(?<fname>[a-zA-Z]+) *\((?<params>.*)\)$").unwrap();
-        let captures = ok_or_error(func_call_regex.captures(expr.as_str()))?;
-
-        let func_name = ok_or_error(captures.name("fname"))?.as_str();
-        let params_as_str = ok_or_error(captures.name("params"))?.as_str().trim();
-        let params_vec = if params_as_str.is_empty() {
-            Vec::<_>::new()
-        } else {
-            self.parse_params_str(Expr::new(params_as_str))?
+    fn parse_func_call(&self, expr: Expr, ctx: &mut CalcContext) -> Result<FuncCall, CalcError> {
+        let tokens = tokenize(expr.as_str(), self.locale)?;
+        let (
+            true,
+            Some(Token {
+                kind: TokenKind::Ident(func_name),
+                ..
+            }),
+        ) = (is_func_call_shape(&tokens), tokens.first())
+        else {
+            return Err(CalcError::EvaluateErr(ErrorKind::NotFuncCall));
         };
 
-        return Ok(FuncCall::new(func_name, params_vec));
-
-        // Pomocná funkce na vyhazování chyb
-        fn ok_or_error<T>(opts: Option<T>) -> Result<T, CalcError> {
-            opts.ok_or(CalcError::EvaluateErr(s!(
-                "Výraz nemá tvar volání funkce: název(parametry)"
-            )))
+        let mut params = Vec::<Value>::new();
+        for param_expr in Self::param_exprs(&expr, &tokens) {
+            params.push(self.evaluate_rec_simplify(param_expr, ctx)?);
         }
+
+        return Ok(FuncCall::new(func_name, params));
     }
 }
 
 impl<'expr> RecursiveScanStrategy<'expr> {
-    /// Parametry, které jsou zadány oddělené čárkami, se převedou na vektor parametrů.
-    fn parse_params_str(&self, params_str: Expr) -> Result<Vec<Value>, CalcError> {
-        let mut delims = vec![]; // Pozice, podle nichz se string roztrha na jednotlive parametry
-        let mut curr_depth = 0; // Carky, ktere oddeluji parametry musi samozrejme byt mimo zavorky
-        let mut is_in_string = false; // Ty carky nesmi byt ani ve stringu
-        let mut bytes_scanned = 0; // Kazdy znak muze mit jiny pocet bajtu (UTF-8). Proto je dulezita pozice v bajtech, ne znacich!
-        for c in params_str.chars() {
-            match c {
-                '(' => curr_depth += 1,
-                ')' => curr_depth -= 1,
-                '"' => is_in_string = !is_in_string,
-                ',' => {
-                    if curr_depth == 0 && !is_in_string {
-                        delims.push(bytes_scanned);
-                    }
-                }
-                _ => {}
-            };
-            bytes_scanned += c.len_utf8();
+    /// Celý vstup je buď výraz, nebo přiřazení do proměnné: název = výraz.
+    /// Přiřazení se dají řetězit (x = y = 1), ale nesmí být uvnitř výrazu.
+    fn evaluate_statement(&self, expr: Expr, ctx: &mut CalcContext) -> Result<Value, CalcError> {
+        let tokens = tokenize(expr.as_str(), self.locale)?;
+        if let Some(def) = parse_func_definition(expr.as_str(), &tokens) {
+            let name_span = tokens[0].span.shift(expr.span().start);
+            ctx.define_func(&def.name, def.params, def.body)
+                .map_err(|e| e.with_span(name_span))?;
+            // Definice funkce nemá žádnou hodnotu
+            return Ok(Value::Nothing);
         }
-        let mut params = Vec::<Value>::new();
-        if delims.len() == 0 {
-            // Parametr je jen jeden, neni potreba nic trhat. Cely string je jeden parametr
-            params.push(parse_param(self, params_str.clone())?);
-        } else {
-            // Pamateru je vic, string se musi rozthrat
-            let mut last_substring_begin = 0usize;
-            for substring_end in delims {
-                params.push(parse_param(
-                    self,
-                    Expr::new(&params_str.as_str()[last_substring_begin..substring_end]),
-                )?);
-                last_substring_begin = substring_end + 1;
-            }
-            params.push(parse_param(
-                self,
-                Expr::new(&params_str.as_str()[last_substring_begin..]),
-            )?);
+        if let [Token {
+            kind: TokenKind::Ident(name),
+            ..
+        }, assign @ Token {
+            kind: TokenKind::Assign,
+            ..
+        }, ..] = tokens.as_slice()
+        {
+            let assign_span = expr.span().start + assign.span.start;
+            let value_expr = expr.slice(assign.span.end..expr.as_str().len());
+            let value = self.evaluate_statement(value_expr, ctx)?;
+            ctx.set_var(name, value.clone())
+                .map_err(|e| e.with_span(Span::new(assign_span, assign_span + 1)))?;
+            return Ok(value);
         }
+        self.evaluate_rec_simplify(expr, ctx)
+    }
 
-        return Ok(params);
-
-        // Pomocná funkce, která parsuje jeden parametr. Vrátí ho jako value, nebo vrátí chybu.
-        fn parse_param(this: &RecursiveScanStrategy, paramstr: Expr) -> Result<Value, CalcError> {
-            this.evaluate_rec_simplify(paramstr)
+    /// Rozdělí volání funkce na jednotlivé parametry (zatím nevyhodnocené). Tokeny musí mít
+    /// tvar volání funkce: název, levá závorka, ..., pravá závorka na konci. Čárky, které
+    /// oddělují parametry, musí být mimo závorky. Ve stringu být nemohou, string je pro
+    /// lexer jediný token.
+    fn param_exprs<'a>(call_expr: &Expr<'a>, tokens: &[Token]) -> Vec<Expr<'a>> {
+        let params_tokens = &tokens[2..tokens.len() - 1];
+        if params_tokens.is_empty() {
+            return vec![];
         }
+        split_top_level(params_tokens, |t| t.kind == TokenKind::Comma)
+            .into_iter()
+            .map(|part| tokens_source(call_expr, &params_tokens[part]))
+            .collect()
     }
 
     /// Používá se k rekurzivnímu vyhodnocení výrazu. Výraz vyhodnotí a zjednoduší
     /// (např. zlomek na celé číslo, pokud to jde. BigInt na integer apod.)
     #[inline]
-    fn evaluate_rec_simplify(&self, expr: Expr) -> Result<Value, CalcError> {
-        self.evaluate_rec(expr)?.simplify_type_move()
+    fn evaluate_rec_simplify(&self, expr: Expr, ctx: &mut CalcContext) -> Result<Value, CalcError> {
+        ctx.nested(|ctx| self.evaluate_rec(expr, ctx))?
+            .simplify_type_move()
     }
 
     /// Používá se k rekurzivnímu vyhodnocení výrazu.
-    fn evaluate_rec(&self, expr: Expr) -> Result<Value, CalcError> {
-        let expr = trim_brackets(expr);
-        match Self::find_oper(expr.clone()) {
+    fn evaluate_rec(&self, expr: Expr, ctx: &mut CalcContext) -> Result<Value, CalcError> {
+        let locale = self.locale;
+        let expr = trim_brackets(expr, locale);
+        let expr_span = expr.span();
+        match Self::find_oper(expr.clone(), locale) {
             None => {
                 // Není-li ve výrazu dělící operátor, pak to bude buď volání funkce, nebo atomická hodnota
-                return if let Ok(func_call) = self.parse_func_call(expr.clone()) {
-                    func_call.eval()
+                let tokens =
+                    tokenize(expr.as_str(), locale).map_err(|e| e.shift_span(expr_span.start))?;
+                return if is_func_call_shape(&tokens) {
+                    // Chyba ve funkci se vyznačí na jejím názvu
+                    let name_span = tokens[0].span.shift(expr_span.start);
+                    if let Some(TokenKind::Ident(name)) = tokens.first().map(|t| &t.kind) {
+                        if is_lazy_func(name) {
+                            // Parametry se vyhodnotí, až si o ně funkce řekne
+                            let params = Self::param_exprs(&expr, &tokens);
+                            return call_lazy_func(name, params.len(), |idx| {
+                                self.evaluate_rec_simplify(params[idx].clone(), ctx)
+                            })
+                            .map_err(|e| e.with_span(name_span));
+                        }
+                    }
+                    let func_call = self.parse_func_call(expr.clone(), ctx)?;
+                    let result = if ctx.get_func(func_call.name()).is_some() {
+                        let name = func_call.name().to_string();
+                        ctx.call_user_func(&name, func_call.into_params(), |body, locale, ctx| {
+                            // Tělo funkce je jiný text než vstupní výraz (pozice chyb se v něm
+                            // počítají od jeho začátku), proto vlastní strategie
+                            let body_strategy = RecursiveScanStrategy {
+                                math_expr: Expr::new(body),
+                                locale,
+                            };
+                            body_strategy.evaluate_rec_simplify(Expr::new(body), ctx)
+                        })
+                    } else {
+                        func_call.eval(ctx)
+                    };
+                    result.map_err(|e| e.with_span(name_span))
+                } else if let [Token {
+                    kind: TokenKind::Number(number),
+                    ..
+                }] = tokens.as_slice()
+                {
+                    match AngleUnit::split_literal(number) {
+                        // Úhel s příponou jednotky (30deg) se převede na jednotku z nastavení
+                        Some((number, unit)) => {
+                            Value::parse_number(number, locale).and_then(|value| {
+                                convert_angle(value, unit, ctx.settings().angle_unit)
+                            })
+                        }
+                        None => Value::parse_number(number, locale),
+                    }
+                    .and_then(Value::simplify_type_move)
+                    .map_err(|e| e.with_span(expr_span))
+                } else if let Some(value) = match tokens.as_slice() {
+                    [Token {
+                        kind: TokenKind::Ident(name),
+                        ..
+                    }] => named_const(name, ctx.settings()),
+                    _ => None,
+                } {
+                    // Pojmenované konstanty mají přednost před proměnnými
+                    Ok(value)
                 } else {
-                    Value::parse(expr.as_str())?.simplify_type_move()
+                    // Booleany mají přednost před proměnnými
+                    Value::parse(expr.as_str())
+                        .and_then(Value::simplify_type_move)
+                        .or_else(|parse_err| match tokens.as_slice() {
+                            [Token {
+                                kind: TokenKind::Ident(name),
+                                ..
+                            }] => ctx.get_var(name).cloned().ok_or_else(|| {
+                                CalcError::EvaluateErr(ErrorKind::UndefinedVariable {
+                                    name: name.clone(),
+                                })
+                            }),
+                            _ => Err(parse_err),
+                        })
+                        .map_err(|e| e.with_span(expr_span))
                 };
             }
             Some((oper_symbol, oper_pos)) => {
-                let (left, right) = Self::halve_expr(expr, oper_pos);
-                if left.is_empty() && right.is_empty() {
-                    return Err(CalcError::EvaluateErr(format!(
-                        "Operátor {oper_symbol} na pozici {oper_pos} nemá žádné operandy"
-                    )));
+                let oper_start = expr_span.start + oper_pos;
+                let oper_span = Span::new(oper_start, oper_start + oper_symbol.len());
+                self.evaluate_oper(expr, oper_symbol, oper_pos, ctx)
+                    .map_err(|e| e.with_span(oper_span))
+            }
+        }
+    }
+
+    /// Vyhodnotí výraz rozdělený na dvě části operátorem na pozici oper_pos
+    fn evaluate_oper(
+        &self,
+        expr: Expr,
+        oper_symbol: &str,
+        oper_pos: usize,
+        ctx: &mut CalcContext,
+    ) -> Result<Value, CalcError> {
+        let (left, right) = Self::halve_expr(expr, oper_pos, oper_symbol.len());
+        if left.is_empty() && right.is_empty() {
+            return Err(CalcError::EvaluateErr(ErrorKind::NoOperands {
+                op: s!(oper_symbol),
+            }));
+        }
+        if let Some(logic_oper) = LogicOper::from_symbol(oper_symbol) {
+            return if left.is_empty() || right.is_empty() {
+                Err(CalcError::EvaluateErr(ErrorKind::MissingOperand {
+                    op: s!(oper_symbol),
+                }))
+            } else {
+                let left = self.evaluate_rec_simplify(left, ctx)?;
+                logic_oper.apply(left, || self.evaluate_rec_simplify(right, ctx))
+            };
+        }
+        if let Some(cmp_oper) = CmpOper::from_symbol(oper_symbol) {
+            return if left.is_empty() || right.is_empty() {
+                Err(CalcError::EvaluateErr(ErrorKind::MissingOperand {
+                    op: s!(oper_symbol),
+                }))
+            } else {
+                let left = self.evaluate_rec_simplify(left, ctx)?;
+                left.compare_by(cmp_oper, &self.evaluate_rec_simplify(right, ctx)?)
+            };
+        }
+        match oper_symbol {
+            "+" => {
+                return if left.is_empty() {
+                    self.evaluate_rec_simplify(right, ctx)
+                } else if right.is_empty() {
+                    Err(CalcError::EvaluateErr(ErrorKind::MissingRightOperand {
+                        op: s!("+"),
+                    }))
+                } else {
+                    self.evaluate_rec_simplify(left, ctx)?.arith_oper(
+                        "+",
+                        self.evaluate_rec_simplify(right, ctx)?,
+                        ctx.settings(),
+                    )
                 }
-                match oper_symbol {
-                    '+' => {
-                        return if left.is_empty() {
-                            self.evaluate_rec_simplify(right)
-                        } else if right.is_empty() {
-                            Err(CalcError::EvaluateErr(s!(
-                                "Operátoru + chybí pravý operand"
-                            )))
-                        } else {
-                            self.evaluate_rec_simplify(left)? + self.evaluate_rec_simplify(right)?
-                        }
-                    }
-                    '-' => {
-                        return if left.is_empty() {
-                            let r = self.evaluate_rec_simplify(right)?;
-                            r.neg()
-                        } else if right.is_empty() {
-                            Err(CalcError::EvaluateErr(s!(
-                                "Operátoru - chybí pravý operand"
-                            )))
-                        } else {
-                            self.evaluate_rec_simplify(left)? - self.evaluate_rec_simplify(right)?
-                        }
-                    }
-                    '*' => {
-                        return if left.is_empty() || right.is_empty() {
-                            Err(CalcError::EvaluateErr(String::from(
-                                "Operátor * vyžaduje dva operandy",
-                            )))
-                        } else {
-                            self.evaluate_rec_simplify(left)? * self.evaluate_rec_simplify(right)?
-                        }
-                    }
-                    '/' => {
-                        return if left.is_empty() || right.is_empty() {
-                            Err(CalcError::EvaluateErr(String::from(
-                                "Operátor / vyžaduje dva operandy",
-                            )))
-                        } else {
-                            self.evaluate_rec_simplify(left)? / self.evaluate_rec_simplify(right)?
-                        }
-                    }
-                    '^' => {
-                        return if left.is_empty() || right.is_empty() {
-                            Err(CalcError::EvaluateErr(String::from(
-                                "Operátor ^ vyžaduje dva operandy",
-                            )))
-                        } else {
-                            self.evaluate_rec_simplify(left)?
-                                .pow(&self.evaluate_rec_simplify(right)?)
-                        }
-                    }
-                    _ => Err(CalcError::EvaluateErr(format!(
-                        "Znak '{oper_symbol}' není definovaný operátor"
-                    ))),
+            }
+            "-" => {
+                return if left.is_empty() {
+                    let r = self.evaluate_rec_simplify(right, ctx)?;
+                    r.neg()
+                } else if right.is_empty() {
+                    Err(CalcError::EvaluateErr(ErrorKind::MissingRightOperand {
+                        op: s!("-"),
+                    }))
+                } else {
+                    self.evaluate_rec_simplify(left, ctx)?.arith_oper(
+                        "-",
+                        self.evaluate_rec_simplify(right, ctx)?,
+                        ctx.settings(),
+                    )
+                }
+            }
+            "!" | "not" => {
+                return if !left.is_empty() || right.is_empty() {
+                    Err(CalcError::EvaluateErr(ErrorKind::MissingRightOperand {
+                        op: s!(oper_symbol),
+                    }))
+                } else {
+                    self.evaluate_rec_simplify(right, ctx)?.not()
+                }
+            }
+            "*" => {
+                return if left.is_empty() || right.is_empty() {
+                    Err(CalcError::EvaluateErr(ErrorKind::MissingOperand {
+                        op: s!("*"),
+                    }))
+                } else {
+                    self.evaluate_rec_simplify(left, ctx)?.arith_oper(
+                        "*",
+                        self.evaluate_rec_simplify(right, ctx)?,
+                        ctx.settings(),
+                    )
+                }
+            }
+            "/" => {
+                return if left.is_empty() || right.is_empty() {
+                    Err(CalcError::EvaluateErr(ErrorKind::MissingOperand {
+                        op: s!("/"),
+                    }))
+                } else {
+                    self.evaluate_rec_simplify(left, ctx)?.arith_oper(
+                        "/",
+                        self.evaluate_rec_simplify(right, ctx)?,
+                        ctx.settings(),
+                    )
+                }
+            }
+            "^" => {
+                return if left.is_empty() || right.is_empty() {
+                    Err(CalcError::EvaluateErr(ErrorKind::MissingOperand {
+                        op: s!("^"),
+                    }))
+                } else {
+                    self.evaluate_rec_simplify(left, ctx)?.arith_oper(
+                        "^",
+                        self.evaluate_rec_simplify(right, ctx)?,
+                        ctx.settings(),
+                    )
                 }
             }
+            _ => Err(CalcError::EvaluateErr(ErrorKind::UnknownOperator {
+                op: s!(oper_symbol),
+            })),
         }
     }
 
-    fn halve_expr(expr: Expr, oper_pos: usize) -> (Expr, Expr) {
-        if oper_pos == 0 {
-            (Expr::new(""), Expr::new(&expr.as_str()[1..].trim()))
-        } else {
-            (
-                Expr::new(&expr.as_str()[..oper_pos].trim()),
-                Expr::new(&expr.as_str()[oper_pos + 1..]),
-            )
-        }
+    /// Rozdělí výraz na část před operátorem a za ním. Operátor má oper_len bajtů.
+    fn halve_expr(expr: Expr, oper_pos: usize, oper_len: usize) -> (Expr, Expr) {
+        (
+            expr.slice(0..oper_pos),
+            expr.slice(oper_pos + oper_len..expr.as_str().len()),
+        )
     }
 
-    /// Vrací nalezený operátor a jeho pozici v textu.
+    /// Vrací operátor, který je kořenem výrazu (vyhodnocuje se jako poslední), a jeho pozici
+    /// v textu. Gramatika se řídí tabulkou BINARY_OPERS a prioritou UNARY_PRIORITY.
     /// POZOR! Nejedná se o pozici ve smyslu index znaku, ale index bajtu!
     /// Znak operátoru má mít jeden bajt, ale jiné znaky UTF-8 mohou mít víc bajtů.
-    fn find_oper(expr: Expr) -> Option<(char, usize)> {
-        let expr_bytes_len = expr.as_str().len();
-        let mut is_in_string = false;
-        let mut curr_depth = 0;
-        let mut best_oper_priority = i32::MAX;
-        let mut best_oper_pos = usize::MAX;
-        let mut best_operator_symbol = '\0';
-
-        let mut bytes_scanned: usize = 0;
-        for c in expr.as_str().chars().rev() {
-            if c == '"' {
-                is_in_string = !is_in_string;
-            } else if !is_in_string {
-                if c == ')' {
-                    curr_depth += 1;
-                } else if c == '(' {
-                    curr_depth -= 1;
-                } else {
-                    if let Some(oper_priority) = is_operator_get_priority(c) {
-                        if curr_depth == 0 // Operátor dělení výrazu nesmí být v závorkách!
-                        && oper_priority < best_oper_priority
-                        {
-                            best_oper_priority = oper_priority;
-                            best_oper_pos = expr_bytes_len - bytes_scanned - 1;
-                            best_operator_symbol = c;
-                        }
+    fn find_oper(expr: Expr, locale: Locale) -> Option<(&'static str, usize)> {
+        let tokens = tokenize(expr.as_str(), locale).ok()?;
+        let depths = bracket_depths(&tokens).ok()?;
+        let mut best: Option<(&BinaryOper, usize)> = None;
+
+        for (idx, token) in tokens.iter().enumerate() {
+            let TokenKind::Oper(symbol) = token.kind else {
+                continue;
+            };
+            // Operátor dělení výrazu nesmí být v závorkách! Unární operátor výraz nedělí.
+            if depths[idx] != 0 || is_unary_position(idx.checked_sub(1).map(|i| &tokens[i])) {
+                continue;
+            }
+            if let Some(oper) = binary_oper(symbol) {
+                // Při stejné prioritě se levě asociativní výraz dělí na nejpravějším
+                // operátoru, pravě asociativní na nejlevějším
+                let is_better = match best {
+                    None => true,
+                    Some((best_oper, _)) => {
+                        oper.priority < best_oper.priority
+                            || (oper.priority == best_oper.priority && oper.assoc == Assoc::Left)
                     }
+                };
+                if is_better {
+                    best = Some((oper, token.span.start));
                 }
             }
-            // Každý znak má jiný počet bajtů (UTF-8). Pozice dělícího operátoru se určuje indexem bajtu!
-            bytes_scanned += c.len_utf8();
         }
 
-        return if best_oper_pos == usize::MAX {
-            None
-        } else {
-            Some((best_operator_symbol, best_oper_pos))
-        };
+        // Unární operátor na začátku je kořenem, pokud všechny binární operátory váží těsněji
+        if let Some(Token {
+            kind: TokenKind::Oper(symbol),
+            span,
+        }) = tokens.first()
+        {
+            let binds_loosest = best.is_none_or(|(oper, _)| oper.priority > UNARY_PRIORITY);
+            if UNARY_OPERS.contains(symbol) && binds_loosest {
+                return Some((symbol, span.start));
+            }
+        }
+
+        return best.map(|(oper, pos)| (oper.symbol, pos));
     }
 }
@@ -1,31 +1,51 @@
 use crate::base::CalcError;
+use crate::calc_base::context::CalcContext;
 use crate::calc_base::expr::Expr;
 use crate::calc_base::value::Value;
 use crate::calc_strategies::common::check_brackets_and_quots;
+use crate::calc_strategies::recursive_scan_strategy::RecursiveScanStrategy;
 use crate::calc_strategies::ICalculatorStrategy;
 use std::marker::PhantomData;
 
-/// Calculator pomocí metody evaluate_expr vypočítá zadaný matematický výraz. Potřebuje ale
-/// doplnit typ strategie. Strategie určuje použitý algoritmus parsování a výpočtů.
-pub struct Calculator<'expr, TStrategy: ICalculatorStrategy<'expr>> {
+/// Calculator pomocí metody evaluate_expr vypočítá zadaný matematický výraz. Typ strategie
+/// určuje použitý algoritmus parsování a výpočtů. Bez něj se použije výchozí strategie
+/// knihovny, takže konzole nemusí strategie znát.
+/// Proměnné a výsledek posledního výpočtu (ans) se drží v kontextu, který se předává zvenku.
+/// Díky tomu může kontext přežít mezi výpočty, i když se strategie pokaždé vytváří znovu.
+pub struct Calculator<'expr, TStrategy: ICalculatorStrategy<'expr> = RecursiveScanStrategy<'expr>> {
     g: PhantomData<TStrategy>, // Phantom data nic neobsahuje, jen vyznačuje kompilátoru, jak se používají generické parametry
     h: PhantomData<&'expr str>,
 }
 
 impl<'expr, TStrategy: ICalculatorStrategy<'expr>> Calculator<'expr, TStrategy> {
-    pub fn evaluate_expr(&self, math_expr: &'expr str) -> Result<Value, CalcError> {
-        check_brackets_and_quots(math_expr)?;
+    pub fn evaluate_expr(
+        &self,
+        math_expr: &'expr str,
+        ctx: &mut CalcContext,
+    ) -> Result<Value, CalcError> {
+        check_brackets_and_quots(math_expr, ctx.settings().locale)?;
+
+        // Expr ořízne bílé znaky na začátku výrazu. Pozice chyb, které najde strategie, je pak
+        // potřeba posunout, aby odpovídaly původnímu textu.
+        let trim_offset = math_expr.len() - math_expr.trim_start().len();
 
         // Výraz prošel validační procedurou, nyní je považován za syntakticky správný
         let mut calc_strategy: TStrategy = Default::default();
-        return match calc_strategy.parse(Expr::new(math_expr)) {
+        let result = match calc_strategy.parse(Expr::new(math_expr), ctx.settings()) {
             // 1. krok strategie: parse
             Ok(_) => {
                 // 2. krok strategie: evaluace parsovaneho vyrazu
-                calc_strategy.evaluate()
+                calc_strategy.evaluate(ctx)
             }
             Err(parse_err) => Err(parse_err),
         };
+        // Definice funkce nemá žádnou hodnotu, ans se tím nepřepíše
+        if let Ok(value) = &result {
+            if !matches!(value, Value::Nothing) {
+                ctx.set_ans(value.clone());
+            }
+        }
+        return result.map_err(|err| err.shift_span(trim_offset));
     }
 }
 
@@ -37,3 +57,1084 @@ impl<'expr, TStagegy: ICalculatorStrategy<'expr>> Default for Calculator<'expr,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{ErrorKind, Expected, Language, Span};
+    use crate::calc_base::context::MAX_CALL_DEPTH;
+    use crate::calc_base::func_registry::Arity;
+    use crate::calc_base::lexer::{bracket_depths, tokenize, TokenKind};
+    use crate::calc_base::locale::Locale;
+    use crate::calc_base::number_format::NumberFormat;
+    use crate::calc_base::rational::Rational;
+    use crate::calc_base::settings::CalcSettings;
+    use crate::calc_strategies::ast_strategy::AstStrategy;
+    use crate::calc_strategies::common::check_syntax;
+    use crate::calc_strategies::recursive_scan_strategy::RecursiveScanStrategy;
+    use crate::s;
+    use num_bigint::BigInt;
+
+    /// Spočítá výraz oběma strategiemi a porovná výsledek s očekávanou hodnotou.
+    /// Očekávaná hodnota se zadává také jako výraz (např. "1/2"), porovnává se textový výpis.
+    fn check(expr: &str, expected: &str) {
+        check_in(&mut CalcContext::default(), expr, expected);
+    }
+
+    /// Jako check, ale počítá v daném kontextu (s jeho nastavením, proměnnými a funkcemi)
+    fn check_in(ctx: &mut CalcContext, expr: &str, expected: &str) {
+        let expected = eval_both(ctx, expected, Value::format_with).unwrap();
+        assert_eq!(
+            eval_both(ctx, expr, Value::format_with).unwrap(),
+            expected,
+            "{expr}"
+        );
+    }
+
+    /// Spočítá výraz oběma strategiemi ve stejném kontextu a vrátí výsledek vypsaný funkcí
+    /// show. Strategie se musí shodnout na výpisu výsledku, nebo na chybě.
+    fn eval_both(
+        ctx: &mut CalcContext,
+        expr: &str,
+        show: fn(&Value, &CalcSettings) -> String,
+    ) -> Result<String, CalcError> {
+        let ast = Calculator::<AstStrategy>::default()
+            .evaluate_expr(expr, ctx)
+            .map(|value| show(&value, ctx.settings()));
+        let recursive = Calculator::<RecursiveScanStrategy>::default()
+            .evaluate_expr(expr, ctx)
+            .map(|value| show(&value, ctx.settings()));
+        match (&ast, &recursive) {
+            (Ok(ast), Ok(recursive)) => assert_eq!(ast, recursive, "{expr}"),
+            (Err(ast), Err(recursive)) => {
+                assert_eq!(ast.to_string(), recursive.to_string(), "{expr}")
+            }
+            _ => panic!("{expr}: AstStrategy {ast:?}, RecursiveScanStrategy {recursive:?}"),
+        }
+        ast
+    }
+
+    #[test]
+    fn ast_strategy_evaluates_like_recursive_scan() {
+        check("1 + 2 * 3", "7");
+        check("(1 + 2) * 3", "9");
+        check("2 * (3 + 4) - 5", "9");
+        check("10 / 4", "5/2");
+        check("1/2 + 1/3", "5/6");
+        check("abs(0 - 3) * 2", "6");
+        check("nck(5, 2) + 1", "11");
+        check("\"text\"", "\"text\"");
+    }
+
+    #[test]
+    fn lexer_tokens_and_spans() {
+        let tokens = tokenize("max(1.5, x) >= 2", Locale::International).unwrap();
+        let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Ident(s!("max")),
+                TokenKind::LeftBracket,
+                TokenKind::Number(s!("1.5")),
+                TokenKind::Comma,
+                TokenKind::Ident(s!("x")),
+                TokenKind::RightBracket,
+                TokenKind::Oper(">="),
+                TokenKind::Number(s!("2")),
+            ]
+        );
+        let spans: Vec<(usize, usize)> = tokens
+            .iter()
+            .map(|token| (token.span.start, token.span.end))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                (0, 3),
+                (3, 4),
+                (4, 7),
+                (7, 8),
+                (9, 10),
+                (10, 11),
+                (12, 14),
+                (15, 16)
+            ]
+        );
+
+        // Escapované uvozovky a zpětné lomítko patří do textu, span zahrnuje uvozovky
+        let tokens = tokenize(r#"len("a\"b\\c") "#, Locale::International).unwrap();
+        assert_eq!(tokens[2].kind, TokenKind::Text(s!(r#"a"b\c"#)));
+        assert_eq!(tokens[2].span, Span::new(4, 13));
+        let err = tokenize(r#"1 + "abc\""#, Locale::International).unwrap_err();
+        assert_eq!(err.kind(), Some(&ErrorKind::UnterminatedText));
+        assert_eq!(err.span(), Some(Span::new(4, 10)));
+        let err = tokenize("1 # 2", Locale::International).unwrap_err();
+        assert_eq!(err.span(), Some(Span::new(2, 3)));
+
+        let tokens = tokenize("(1 + (2)) * 3", Locale::International).unwrap();
+        assert_eq!(bracket_depths(&tokens), Ok(vec![0, 1, 1, 1, 2, 1, 0, 0, 0]));
+        let tokens = tokenize("(1)) + (2", Locale::International).unwrap();
+        assert_eq!(bracket_depths(&tokens), Err(3));
+    }
+
+    #[test]
+    fn errors_point_into_expression() {
+        let ctx = &mut CalcContext::default();
+        let ast = Calculator::<AstStrategy>::default();
+        let recursive = Calculator::<RecursiveScanStrategy>::default();
+        for err in [
+            ast.evaluate_expr("1+2/0", ctx).unwrap_err(),
+            recursive.evaluate_expr("1+2/0", ctx).unwrap_err(),
+        ] {
+            assert_eq!(err.span(), Some(Span::new(3, 4)), "{err}");
+            assert_eq!(err.span().unwrap().underline("1+2/0").unwrap(), "   ^");
+        }
+        // Podvýrazy v závorkách, parametrech a za přiřazením si pamatují svou pozici
+        let expr = "x = ( 1 + max(2,  3 / 0 ))";
+        let slash = expr.find('/').unwrap();
+        for err in [
+            ast.evaluate_expr(expr, ctx).unwrap_err(),
+            recursive.evaluate_expr(expr, ctx).unwrap_err(),
+        ] {
+            assert_eq!(err.span(), Some(Span::new(slash, slash + 1)), "{err}");
+        }
+
+        // Mezery na začátku výrazu se do pozice chyby započítají
+        let err = ast.evaluate_expr("  foo(1)", ctx).unwrap_err();
+        assert_eq!(err.span(), Some(Span::new(2, 5)));
+        assert_eq!(err.span().unwrap().underline("  foo(1)").unwrap(), "  ^~~");
+
+        // Odsazení značky je ve znacích, ne v bajtech; tabulátor zůstane tabulátorem
+        let span = Span::new("\tπ·π + ".len(), "\tπ·π + x".len());
+        assert_eq!(span.underline("\tπ·π + x").unwrap(), "\t      ^");
+        assert_eq!(Span::new(0, 10).underline("1+2"), None);
+    }
+
+    #[test]
+    fn power_is_right_associative() {
+        check("2^3^2", "512");
+        check("(2^3)^2", "64");
+        check("2^2^3", "256");
+        check("2^3^0", "2");
+    }
+
+    #[test]
+    fn unary_minus_binds_looser_than_power() {
+        check("-2^2", "-4");
+        check("(-2)^2", "4");
+        check("-2^-2", "-1/4");
+        check("-(2)^2", "-4");
+        check("0-2^2", "-4");
+    }
+
+    #[test]
+    fn unary_minus_in_exponent() {
+        check("2^-1", "1/2");
+        check("2^-1^2", "1/2");
+        check("2^-1*4", "2");
+        check("2^+2", "4");
+    }
+
+    #[test]
+    fn unary_minus_binds_tighter_than_multiplication() {
+        check("-2*3", "-6");
+        check("2*-3", "-6");
+        check("-6/-2", "3");
+        check("2--3", "5");
+        check("1 - -2", "3");
+        check("-1+2", "1");
+        check("--2", "2");
+    }
+
+    #[test]
+    fn left_associative_operators() {
+        check("8/4/2", "1");
+        check("10-4-3", "3");
+        check("1-2+3", "2");
+        check("2*3/4", "3/2");
+        check("1+2*3^2", "19");
+    }
+
+    #[test]
+    fn unary_minus_in_function_params() {
+        check("abs(-2^2)", "4");
+        check("abs(-3)*-1", "-3");
+        check("nck(5,2)-2^3^0", "8");
+    }
+
+    #[test]
+    fn variables_and_ans_persist_in_context() {
+        let ctx = &mut CalcContext::default();
+        let ast = Calculator::<AstStrategy>::default();
+        let recursive = Calculator::<RecursiveScanStrategy>::default();
+        let value = |result: Result<Value, CalcError>| result.unwrap().to_string();
+
+        assert_eq!(
+            value(ast.evaluate_expr("x = 3/7", ctx)),
+            "3 / 7\t(rational)"
+        );
+        assert_eq!(value(recursive.evaluate_expr("x*7", ctx)), "3\t(integer)");
+        assert_eq!(value(ast.evaluate_expr("ans + 1", ctx)), "4\t(integer)");
+        assert_eq!(
+            value(recursive.evaluate_expr("y = ans*2", ctx)),
+            "8\t(integer)"
+        );
+        assert!(ast.evaluate_expr("pi = 3", ctx).is_err());
+        assert!(recursive.evaluate_expr("ans = 3", ctx).is_err());
+        assert!(ast.evaluate_expr("z + 1", ctx).is_err());
+    }
+
+    #[test]
+    fn user_defined_functions() {
+        // Rekurze až k MAX_CALL_DEPTH potřebuje v ladicím sestavení větší zásobník
+        let test = std::thread::Builder::new()
+            .stack_size(64 << 20)
+            .spawn(|| {
+                let ctx = &mut CalcContext::default();
+                let ast = Calculator::<AstStrategy>::default();
+                let recursive = Calculator::<RecursiveScanStrategy>::default();
+                let value = |result: Result<Value, CalcError>| result.unwrap().to_string();
+                let kind = |result: Result<Value, CalcError>| result.unwrap_err().kind().cloned();
+
+                assert!(matches!(
+                    ast.evaluate_expr("f(x, y) = x^2 + y/3", ctx),
+                    Ok(Value::Nothing)
+                ));
+                assert_eq!(value(ast.evaluate_expr("f(2, 3)", ctx)), "5\t(integer)");
+                assert_eq!(
+                    value(recursive.evaluate_expr("F(1, 1/2) * 6", ctx)),
+                    "7\t(integer)"
+                );
+                // Parametry nejsou vidět mimo tělo funkce
+                assert!(recursive.evaluate_expr("x", ctx).is_err());
+
+                for calc_result in [
+                    ast.evaluate_expr("f(1)", ctx),
+                    recursive.evaluate_expr("f(1, 2, 3)", ctx),
+                ] {
+                    assert!(matches!(
+                        kind(calc_result),
+                        Some(ErrorKind::ArityMismatch {
+                            expected: Arity::Exact(2),
+                            ..
+                        })
+                    ));
+                }
+                assert!(matches!(
+                    kind(recursive.evaluate_expr("h(a, a) = a", ctx)),
+                    Some(ErrorKind::DuplicateParam { .. })
+                ));
+                assert!(matches!(
+                    kind(ast.evaluate_expr("sin(x) = x", ctx)),
+                    Some(ErrorKind::BuiltinFunc { .. })
+                ));
+                assert!(matches!(
+                    kind(recursive.evaluate_expr("pi(x) = x", ctx)),
+                    Some(ErrorKind::ConstantFuncName { .. })
+                ));
+
+                ast.evaluate_expr("g(n) = if(n <= 0, 0, n + g(n - 1))", ctx)
+                    .unwrap();
+                assert_eq!(value(ast.evaluate_expr("g(100)", ctx)), "5050\t(integer)");
+                assert_eq!(
+                    value(recursive.evaluate_expr("g(100)", ctx)),
+                    "5050\t(integer)"
+                );
+                for calc_result in [
+                    ast.evaluate_expr("g(1000)", ctx),
+                    recursive.evaluate_expr("g(1000)", ctx),
+                ] {
+                    assert_eq!(
+                        kind(calc_result),
+                        Some(ErrorKind::CallDepthExceeded {
+                            func: s!("g"),
+                            max: MAX_CALL_DEPTH,
+                        })
+                    );
+                }
+            })
+            .unwrap();
+        test.join().unwrap();
+    }
+
+    #[test]
+    fn registered_function_is_callable() {
+        use crate::calc_base::func_registry::{Arity, FuncDescriptor, ParamType};
+
+        let ctx = &mut CalcContext::default();
+        let double = FuncDescriptor::new("double", Arity::Exact(1), |p| {
+            p[0].clone() * Value::Integer(2)
+        })
+        .with_params(&[ParamType::Number])
+        .with_help("Dvojnásobek čísla");
+        ctx.register_func(double).unwrap();
+        let ast = Calculator::<AstStrategy>::default();
+        let recursive = Calculator::<RecursiveScanStrategy>::default();
+
+        assert_eq!(
+            ast.evaluate_expr("double(3/4)", ctx).unwrap().to_string(),
+            "3 / 2\t(rational)"
+        );
+        assert_eq!(
+            recursive
+                .evaluate_expr("DOUBLE(-2)", ctx)
+                .unwrap()
+                .to_string(),
+            "-4\t(integer)"
+        );
+        assert!(ast.evaluate_expr("double(1, 2)", ctx).is_err());
+        assert!(recursive.evaluate_expr("double(\"a\")", ctx).is_err());
+        assert!(ctx.define_func("double", vec![], "1").is_err());
+    }
+
+    #[test]
+    fn comparison_operators() {
+        check("1/3 == 2/6", "true");
+        check("-1/2 < -1/3", "true");
+        check("2^70 > 2^69", "true");
+        check("2^64 == 18446744073709551616", "true");
+        check("1/3 < 0.34", "true");
+        check("0.1 + 0.2 == 0.3", "true");
+        check("3 >= 3.0", "true");
+        check("1/3 != 0.3333", "true");
+        check("\"ab\" == \"ab\"", "true");
+        check("\"a\" != 1", "true");
+        check("1 + 2 < 4", "true");
+        check("1 < 2 == 2 < 3", "true");
+        check("x = 2 <= 1", "false");
+    }
+
+    #[test]
+    fn ordering_functions() {
+        check("max(3, 7/2, 3.4)", "7/2");
+        check("max(-1/2, -1/3)", "-1/3");
+        check("min(1/3, 0.33, 2^70)", "0.33");
+        check("argmax(1, 5, 5, 2)", "2");
+        check("argmin(-1/2, -1/3, -0.6)", "3");
+        check("clamp(5, 1, 3)", "3");
+        check("clamp(-1/2, -1, 0)", "-1/2");
+        check("sort(3, -1/2, 2^65, 0)", "sort(-1/2, 0, 3, 2^65)");
+    }
+
+    #[test]
+    fn logic_operators_short_circuit() {
+        check("true and false", "false");
+        check("true xor true", "false");
+        check("!false or false", "true");
+        check("not (1 < 2)", "false");
+        check("1 < 2 and 2 < 3 or false", "true");
+        // Pravý operand se nevyhodnotí, takže dělení nulou nevadí
+        check("false and 1/0 == 1", "false");
+        check("true or 1/0 == 1", "true");
+    }
+
+    #[test]
+    fn if_evaluates_only_chosen_branch() {
+        check("if(1 > 0, 10, 1/0)", "10");
+        check("if(1 < 0, 1/0, -1)", "-1");
+        check("if(2 > 1, if(false, 1, 2), 3)", "2");
+        let ctx = &mut CalcContext::default();
+        let ast = Calculator::<AstStrategy>::default();
+        let recursive = Calculator::<RecursiveScanStrategy>::default();
+        assert!(ast.evaluate_expr("if(1, 2, 3)", ctx).is_err());
+        assert!(recursive.evaluate_expr("if(true, 1)", ctx).is_err());
+        assert!(recursive.evaluate_expr("if(x) = x", ctx).is_err());
+    }
+
+    #[test]
+    fn complex_numbers() {
+        check("i*i", "-1");
+        check("sqrt(-4)", "2*i");
+        check("sqrt(-1/4) + 1", "1 + 1/2*i");
+        check("(1 + i)*(1 - i)", "2");
+        check("(1 + 2*i)/(3 - 4*i)", "-1/5 + 2/5*i");
+        check("(1 + i)^4", "-4");
+        check("i^-1", "-i");
+        check("abs(3 + 4*i)", "5");
+        check("sqrt(-2)^2", "-2");
+        check("sqrt(-8)", "2*sqrt(2)*i");
+        check("(1 + sqrt(2)*i)*(1 - sqrt(2)*i)", "3");
+        check("abs(1 + i)", "sqrt(2)");
+        check("(-4)^(1/2) == 2*i", "true");
+        check("ln(-1) == pi*i", "true");
+        check("sin(i) == i*(e - 1/e)/2", "true");
+        let ctx = &mut CalcContext::default();
+        let ast = Calculator::<AstStrategy>::default();
+        assert_eq!(
+            ast.evaluate_expr("1 - 2*i", ctx).unwrap().to_string(),
+            "1 - 2*i\t(complex)"
+        );
+        assert_eq!(
+            ast.evaluate_expr("sqrt(-2)", ctx).unwrap().to_string(),
+            "sqrt(2)*i\t(complex)"
+        );
+        assert!(ast.evaluate_expr("i < 1", ctx).is_err());
+        assert!(ast.evaluate_expr("max(i, 1)", ctx).is_err());
+    }
+
+    #[test]
+    fn square_roots_stay_exact() {
+        check("sqrt(8)", "2*sqrt(2)");
+        check("sqrt(3/4)", "1/2*sqrt(3)");
+        check("sqrt(2)*sqrt(2)", "2");
+        check("sqrt(2)*sqrt(6)", "2*sqrt(3)");
+        check("(1 + sqrt(2))^2", "3 + 2*sqrt(2)");
+        check("1/(1 + sqrt(2))", "sqrt(2) - 1");
+        check("sqrt2 - sqrt(2)", "0");
+        check("sind(45)", "sqrt(2)/2");
+        check("sind(60)", "sqrt(3)/2");
+        check("sind(-45)", "-sqrt(2)/2");
+        check("sqrt(2) < 17/12 and sqrt(2) > 1.414", "true");
+        check("sqrt(2) + sqrt(3) == 3.1462643699419726", "true");
+    }
+
+    #[test]
+    fn multiples_of_pi_stay_exact() {
+        check("sin(pi)", "0");
+        check("pi/6 + pi/3", "pi/2");
+        check("2*pi - pi", "pi");
+        check("(2*pi)/pi", "2");
+        check("sin(pi/6)", "1/2");
+        check("sin(-pi/4)", "-sqrt(2)/2");
+        check("cos(pi/3)", "1/2");
+        check("cos(2*pi)", "1");
+        check("tan(pi/4)", "1");
+        check("tan(pi/3)", "sqrt(3)");
+        check("sinpi(1/6)", "1/2");
+        check("pi == 3.141592653589793", "true");
+        check("pi*pi > 9.86 and pi*pi < 9.87", "true");
+        let ctx = &mut CalcContext::default();
+        let ast = Calculator::<AstStrategy>::default();
+        assert!(ast.evaluate_expr("tan(pi/2)", ctx).is_err());
+    }
+
+    #[test]
+    fn trig_function_family() {
+        check("cosd(60)", "1/2");
+        check("tand(45)", "1");
+        check("cotpi(1/4)", "1");
+        check("secd(60)", "2");
+        check("asin(1/2)", "pi/6");
+        check("asind(sqrt(2)/2)", "45");
+        check("acospi(-1/2)", "2/3");
+        check("atan2d(1, -1)", "135");
+        check("atan2(1, 0)", "pi/2");
+        check("sinh(0)", "0");
+        check("acosh(1)", "0");
+        check("cos(1) == 0.5403023058681398", "true");
+        // Velké přesné úhly se zmenšují o celé otáčky bez ztráty přesnosti
+        check("sind(10^30)", "sind(280)");
+        check("sind(1e20)", "sind(280)");
+        check("cospi(10^30 + 1/3)", "1/2");
+        let ctx = &mut CalcContext::default();
+        ctx.set_decimal_digits(Some(30)).unwrap();
+        check_in(ctx, "sind(10^30)", "sind(280)");
+        let ctx = &mut CalcContext::default();
+        let ast = Calculator::<AstStrategy>::default();
+        assert!(ast.evaluate_expr("tand(90)", ctx).is_err());
+        assert!(ast.evaluate_expr("asin(2)", ctx).is_err());
+        assert!(ast.evaluate_expr("atanh(1)", ctx).is_err());
+    }
+
+    #[test]
+    fn angle_unit_setting() {
+        use crate::calc_base::trig_funcs::AngleUnit;
+
+        check("sin(30deg)", "1/2");
+        check("30deg + 15deg", "pi/4");
+        check("cos(100grad)", "0");
+        for unit in [AngleUnit::Deg, AngleUnit::Grad] {
+            let ctx = &mut CalcContext::default();
+            ctx.set_angle_unit(unit);
+            let right_angle = if unit == AngleUnit::Deg { "90" } else { "100" };
+            check_in(ctx, &format!("sin({right_angle})"), "1");
+            check_in(ctx, "asin(1)", right_angle);
+            check_in(ctx, "atan2(1, 0)", right_angle);
+            check_in(ctx, "sinpi(1/2)", "1");
+            check_in(ctx, "sin(1rad) == sinpi(1/pi)", "true");
+            check_in(ctx, "90deg", right_angle);
+            assert!(eval_both(ctx, "sin(i)", Value::format_with).is_err());
+        }
+    }
+
+    #[test]
+    fn decimal_precision() {
+        let eval = |expr: &str, ctx: &mut CalcContext| {
+            eval_both(ctx, expr, Value::to_plain_string_with).unwrap()
+        };
+        let sqrt2 = "1.414213562373095048801688724209698078569671875376948073176679737990732478462107038850387534327641573";
+        let ln10 = "2.3025850929940456840179914546843642076011014886288";
+
+        let ctx = &mut CalcContext::default();
+        assert_eq!(eval("dec(sqrt(2), 100)", ctx), sqrt2);
+        assert_eq!(eval("sqrt(dec(2, 100))", ctx), sqrt2);
+        assert_eq!(eval("ln(dec(10, 50))", ctx), ln10);
+        assert_eq!(eval("dec(1, 5)/3 + 1/3", ctx), "0.66666");
+        assert_eq!(eval("dec(2, 30) > pi", ctx), "false");
+        assert_eq!(eval("ln(10)", ctx), 10f64.ln().to_string());
+        check("dec(1, 20)/3", "dec(1/3, 20)");
+
+        assert!(ctx.set_decimal_digits(Some(0)).is_err());
+        ctx.set_decimal_digits(Some(50)).unwrap();
+        assert_eq!(eval("ln(10)", ctx), ln10);
+        assert_eq!(
+            eval("e", ctx),
+            "2.7182818284590452353602874713526624977572470937"
+        );
+        // e^π = 23.14069263277926900572908636794854738026610624260021..., na 50 platných
+        // číslic končí nulami, které se nevypisují
+        assert_eq!(
+            eval("e^pi", ctx),
+            "23.1406926327792690057290863679485473802661062426"
+        );
+        assert_eq!(
+            eval("e^(1/2)", ctx),
+            "1.6487212707001281468486507878141635716537761007101"
+        );
+        assert_eq!(
+            eval("pi*pi", ctx),
+            "9.8696044010893586188344909998761511353136994072408"
+        );
+        assert_eq!(
+            eval("sin(1)", ctx),
+            "0.84147098480789650665250232163029899962256306079837"
+        );
+        assert_eq!(eval("sin(pi/6)", ctx), "1 / 2");
+        assert_eq!(eval("sqrt(8)", ctx), "2*sqrt(2)");
+        assert_eq!(
+            eval_both(ctx, "2*pi", Value::format_with).unwrap(),
+            "2*pi ≈ 6.2831853071795864769252867665590057683943387987502\t(pi multiple)"
+        );
+        assert_eq!(eval("ln(-10)", ctx), format!("{ln10} + pi*i"));
+        assert_eq!(
+            eval("sqrt(-pi)", ctx),
+            "1.7724538509055160272981674833411451827975494561224*i"
+        );
+    }
+
+    #[test]
+    fn number_literals() {
+        check("1.5e-3", "3/2000");
+        check("2.5E2", "250");
+        check("1e-400 * 10^400", "1");
+        check("0xFF", "255");
+        check("0b1010", "10");
+        check("0o17", "15");
+        check("0x1_0000_0000_0000_0000", "2^64");
+        check("1_000_000", "1000000");
+        check("0xdead_beef", "3735928559");
+        check("0b1010_1010", "170");
+        check("1_5e1_0", "15*10^10");
+        check("1 000 000 + 2 500", "1002500");
+        check("max(1, 2 345)", "2345");
+        check("1e3deg", "50/9*pi");
+        check("2*e", "e + e");
+        assert!(Calculator::<AstStrategy>::default()
+            .evaluate_expr("1 2", &mut CalcContext::default())
+            .is_err());
+        // Podtržítko smí být jen mezi číslicemi soustavy literálu
+        for literal in ["1_e5", "1e_5", "1.5_e2", "0x_FF", "0b1_2", "0o7_8", "1_.5"] {
+            assert!(Value::parse(literal).is_err(), "{literal}");
+        }
+    }
+
+    #[test]
+    fn signed_decimal_literals() {
+        check(".5", "1/2");
+        check("5.", "5");
+        check("1.50e2", "150");
+        check("5.e3", "5000");
+        check("-.25 + 1", "3/4");
+        assert_eq!(
+            Value::parse("-0.5").unwrap().to_string(),
+            "-1 / 2\t(rational)"
+        );
+        assert_eq!(
+            Value::parse("+2.25").unwrap().to_string(),
+            "9 / 4\t(rational)"
+        );
+        assert_eq!(Value::parse("-0x10").unwrap().to_string(), "-16\t(integer)");
+        assert_eq!(
+            Rational::parse_literal("3,5", ',').unwrap(),
+            Rational::new(7, 2)
+        );
+
+        // Neplatný literál je chyba, ne nepřesné reálné číslo
+        for literal in [".", "3.5.5", "1e+", "1.5e2.5", "1_", "0xG"] {
+            assert!(Value::parse(literal).is_err(), "{literal}");
+        }
+        for expr in ["3.5.5", "1..2 + 1"] {
+            let ctx = &mut CalcContext::default();
+            assert!(Calculator::<AstStrategy>::default()
+                .evaluate_expr(expr, ctx)
+                .is_err());
+            assert!(Calculator::<RecursiveScanStrategy>::default()
+                .evaluate_expr(expr, ctx)
+                .is_err());
+        }
+        assert!(Value::parse("inf").is_err());
+    }
+
+    #[test]
+    fn number_formats() {
+        check("hex(255)", "\"0xFF\"");
+        check("bin(-10)", "\"-0b1010\"");
+        check("oct(15)", "\"0o17\"");
+        check("mixed(-5/3)", "\"-1 2/3\"");
+        check("repeating(1/7)", "\"0.(142857)\"");
+        check("repeating(1/6)", "\"0.1(6)\"");
+        check("fixed(2/3, 2)", "\"0.67\"");
+        check("sig(1/2, 3)", "\"0.500\"");
+        check("sci(123456)", "\"1.23456e5\"");
+        check("eng(0.00012345)", "\"123.45e-6\"");
+        check("group(2^30)", "\"1 073 741 824\"");
+        check("group(-1234567.891)", "\"-1 234 567.891\"");
+        check("group(12345678/7)", "\"1 763 668.(285714)\"");
+
+        let ctx = &mut CalcContext::default();
+        let calc = Calculator::<RecursiveScanStrategy>::default();
+        let show = |expr: &str, ctx: &mut CalcContext| {
+            let value = calc.evaluate_expr(expr, ctx).unwrap();
+            value.format_with(ctx.settings())
+        };
+        ctx.set_number_format("hex".parse().unwrap()).unwrap();
+        assert_eq!(show("255", ctx), "0xFF\t(integer)");
+        assert_eq!(show("1/2", ctx), "1 / 2\t(rational)");
+        ctx.set_number_format("fixed 3".parse().unwrap()).unwrap();
+        ctx.set_digit_grouping(true);
+        assert_eq!(show("12345678/7", ctx), "1 763 668.286\t(rational)");
+        assert!("sig 0".parse::<NumberFormat>().is_err());
+    }
+
+    #[test]
+    fn czech_locale() {
+        let ctx = &mut CalcContext::default();
+        ctx.set_locale(Locale::Czech);
+        let eval = |expr: &str, ctx: &mut CalcContext| eval_both(ctx, expr, Value::format_with);
+        assert_eq!(eval("3,5 * 2", ctx).unwrap(), "7\t(integer)");
+        assert_eq!(eval("max(1,5; 2,25)", ctx).unwrap(), "9 / 4\t(rational)");
+        assert_eq!(eval("f(x; y) = x*y + 0,5", ctx).unwrap(), "{}");
+        assert_eq!(eval("f(2; 1,5)", ctx).unwrap(), "7 / 2\t(rational)");
+        assert_eq!(eval("fixed(2/3; 2)", ctx).unwrap(), "\"0,67\"");
+        assert_eq!(eval("\"a,b\"", ctx).unwrap(), "\"a,b\"");
+        ctx.set_number_format(NumberFormat::Repeating).unwrap();
+        assert_eq!(eval("1/6", ctx).unwrap(), "0,1(6)\t(rational)");
+        assert!(eval("1.5", ctx).is_err());
+        assert!(eval("3,5,5", ctx).is_err());
+
+        // Tělo funkce se čte v zápisu, ve kterém byla funkce definovaná
+        let ctx = &mut CalcContext::default();
+        assert_eq!(eval("g(x) = max(x, 1.5) * 2", ctx).unwrap(), "{}");
+        ctx.set_locale(Locale::Czech);
+        check_in(ctx, "g(1)", "3");
+        assert_eq!(eval("h(x; y) = g(x) + y*0,5", ctx).unwrap(), "{}");
+        check_in(ctx, "h(2; 1,5)", "19/4");
+        ctx.set_locale(Locale::International);
+        check_in(ctx, "h(2, 1.5)", "19/4");
+    }
+
+    #[test]
+    fn rational_decimal_expansion() {
+        let expand = |q: Rational| q.decimal_expansion(100).to_string();
+        assert_eq!(expand(Rational::new(1, 7)), "0.(142857)");
+        assert_eq!(expand(Rational::new(-1, 6)), "-0.1(6)");
+        assert_eq!(expand(Rational::new(3, 8)), "0.375");
+        assert_eq!(expand(Rational::new(22, 1)), "22");
+        assert_eq!(
+            Rational::new(1, 3).decimal_expansion(1).to_string(),
+            "0.(3)"
+        );
+        assert_eq!(
+            Rational::new(1, 97).decimal_expansion(5).to_string(),
+            "0.01030…"
+        );
+
+        // Čitatel ani jmenovatel se nevejde do f64, rozvoj je přesto přesný
+        let huge = Rational::new_bigint(BigInt::from(10).pow(400) + 1, BigInt::from(10).pow(399));
+        assert!(!huge.to_real().is_some_and(f64::is_finite));
+        let expansion = huge.decimal_expansion(500);
+        assert!(!expansion.truncated);
+        assert_eq!(expansion.whole, BigInt::from(10));
+        assert_eq!(expansion.fraction, "0".repeat(398) + "1");
+    }
+
+    #[test]
+    fn localized_errors() {
+        let ctx = &mut CalcContext::default();
+        let ast = Calculator::<AstStrategy>::default();
+        let recursive = Calculator::<RecursiveScanStrategy>::default();
+        for err in [
+            ast.evaluate_expr("1/0", ctx).unwrap_err(),
+            recursive.evaluate_expr("1/0", ctx).unwrap_err(),
+        ] {
+            assert_eq!(err.kind(), Some(&ErrorKind::DivisionByZero));
+            assert_eq!(err.code(), "division_by_zero");
+            assert_eq!(err.to_string(), err.localized(Language::Czech));
+            assert_eq!(
+                err.localized(Language::English),
+                "Error evaluating the expression: Division by zero"
+            );
+        }
+
+        let err = recursive.evaluate_expr("foo(1)", ctx).unwrap_err();
+        assert_eq!(err.code(), "unknown_function");
+        assert!(err
+            .localized(Language::English)
+            .contains("Function 'foo' is not defined"));
+        assert!(err.to_string().contains("Funkce 'foo' není definována"));
+
+        let err = ast.evaluate_expr("max()", ctx).unwrap_err();
+        assert!(err
+            .localized(Language::English)
+            .contains("at least 1 parameter, got 0"));
+
+        assert_eq!(Language::from_name("en_US.UTF-8"), Some(Language::English));
+        assert_eq!(Language::from_name("cs"), Some(Language::Czech));
+        assert_eq!(Language::from_name("de"), None);
+    }
+
+    #[test]
+    fn structured_error_kinds() {
+        let ctx = &mut CalcContext::default();
+        let ast = Calculator::<AstStrategy>::default();
+        let recursive = Calculator::<RecursiveScanStrategy>::default();
+        let cases = [
+            (
+                "1 / \"a\"",
+                ErrorKind::TypeMismatch {
+                    op: '/',
+                    left_type: "Integer",
+                    right_type: "Text",
+                },
+            ),
+            (
+                "sci(1, 2, 3)",
+                ErrorKind::ArityMismatch {
+                    name: s!("sci"),
+                    expected: Arity::Range(1, 2),
+                    got: 3,
+                },
+            ),
+            (
+                "fact(-1)",
+                ErrorKind::DomainError {
+                    func: s!("fact"),
+                    arg: s!("-1"),
+                },
+            ),
+            (
+                "ln(0)",
+                ErrorKind::DomainError {
+                    func: s!("ln"),
+                    arg: s!("0"),
+                },
+            ),
+            ("2^10000000", ErrorKind::Overflow),
+            ("ln(10) * 1e308", ErrorKind::Overflow),
+            (
+                "(1 + 2",
+                ErrorKind::UnbalancedBrackets {
+                    pos: 0,
+                    bracket: '(',
+                },
+            ),
+            (
+                "1 + 2)",
+                ErrorKind::UnbalancedBrackets {
+                    pos: 5,
+                    bracket: ')',
+                },
+            ),
+        ];
+        for (expr, kind) in cases {
+            for err in [
+                ast.evaluate_expr(expr, ctx).unwrap_err(),
+                recursive.evaluate_expr(expr, ctx).unwrap_err(),
+            ] {
+                assert_eq!(err.kind(), Some(&kind), "{expr}");
+            }
+        }
+        let err = ast.evaluate_expr("sin(true)", ctx).unwrap_err();
+        assert_eq!(err.code(), "param_type_mismatch");
+        assert!(matches!(
+            err.kind(),
+            Some(ErrorKind::ParamTypeMismatch {
+                got_type: "Bool",
+                position: 1,
+                ..
+            })
+        ));
+        // Syntaktické chyby hlásí obě strategie stejně, i když část výrazu vypadá platně
+        for expr in ["y=", "=5", "+", "and=1", "0b102", "0o9", "0x", "1e", "1_e5"] {
+            let ast_err = ast.evaluate_expr(expr, ctx).unwrap_err().without_span();
+            let recursive_err = recursive
+                .evaluate_expr(expr, ctx)
+                .unwrap_err()
+                .without_span();
+            assert!(
+                matches!(ast_err, CalcError::ParseErr(_)),
+                "{expr}: {ast_err:?}"
+            );
+            assert_eq!(ast_err.to_string(), recursive_err.to_string(), "{expr}");
+        }
+        let err = check_syntax("f(x) = max(x; 1", Locale::Czech).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            Some(&ErrorKind::UnexpectedEnd {
+                expected: Expected::SeparatorOrRightBracket(';')
+            })
+        );
+        assert!(check_syntax("x = y = -sin(30deg)^2 + f()", Locale::International).is_ok());
+        // Stejná chyba má všude stejnou variantu, ať ji hlásí funkce nebo formátování
+        for expr in ["fixed(1, -1)", "fixed(1, 100000000)", "sci(1, 0)"] {
+            for err in [
+                ast.evaluate_expr(expr, ctx).unwrap_err(),
+                recursive.evaluate_expr(expr, ctx).unwrap_err(),
+            ] {
+                assert!(
+                    matches!(
+                        err.without_span(),
+                        CalcError::EvaluateErr(ErrorKind::DigitsOutOfRange { .. })
+                    ),
+                    "{expr}"
+                );
+            }
+        }
+    }
+
+    /// Vstupy, které nejsou platné výrazy nebo jsou na hranici možností kalkulátoru.
+    /// Žádný z nich nesmí způsobit paniku, jen chybu.
+    const MALFORMED_CORPUS: &[&str] = &[
+        "",
+        " ",
+        "(",
+        ")",
+        ")(",
+        "()",
+        "((1)",
+        "1))",
+        "\"",
+        "\"abc",
+        "1 +",
+        "* 2",
+        "1 ** 2",
+        "1 // 2",
+        "^",
+        "-",
+        "--",
+        "!",
+        "1 2",
+        "1,2",
+        "1;2",
+        ",",
+        "f(",
+        "f()",
+        "sqrt(",
+        "sqrt()",
+        "sqrt(,)",
+        "max(,1)",
+        "max(1,)",
+        "sin()",
+        "foo(1)(2)",
+        "1 = 2",
+        "x =",
+        "= 1",
+        "ans = 1",
+        "pi = 3",
+        "f(x, x) = x",
+        "f(x) = f(x)",
+        "f(1)",
+        "1e",
+        "1e+",
+        "1e99999999999",
+        "1.2.3",
+        "1__0",
+        "._",
+        "0x",
+        "9223372036854775807 + 1",
+        "-9223372036854775807 - 1",
+        "(-9223372036854775807 - 1) / -1",
+        "(-9223372036854775807 - 1) * -1",
+        "-(-9223372036854775807 - 1)",
+        "abs(-9223372036854775807 - 1)",
+        "(-9223372036854775807 - 1) ^ -1",
+        "2 ^ -9223372036854775807",
+        "2 ^ 9223372036854775807",
+        "2 ^ (-9223372036854775807 - 1)",
+        "(1/3) ^ 9223372036854775807",
+        "(-1) ^ 9223372036854775807",
+        "1 ^ (-9223372036854775807 - 1)",
+        "0 ^ (-9223372036854775807 - 1)",
+        "0 ^ 4294967296",
+        "(-1) ^ 4294967297",
+        "(1/2) ^ (-9223372036854775807 - 1)",
+        "9 ^ 9 ^ 9",
+        "99999999999999999999999 ^ 99999999999999999999999",
+        "0 ^ -1",
+        "0 ^ 0",
+        "(-8) ^ (1/3)",
+        "1 / 0",
+        "1 / 0.0",
+        "1 / (1 - 1)",
+        "ln(0)",
+        "ln(-1)",
+        "sqrt(-1)",
+        "fact(-1)",
+        "fact(100000)",
+        "fact(99999999999999999999999)",
+        "nck(5, 10)",
+        "nck(-5, 2)",
+        "comb(-1, -1, true)",
+        "tan(pi/2)",
+        "tand(90)",
+        "asin(2)",
+        "acosh(0)",
+        "atanh(1)",
+        "atan2(0, 0)",
+        "sin(1 + i)",
+        "sind(i)",
+        "dec(1/3, 0)",
+        "dec(1/3, 99999999999)",
+        "dec(pi, -1)",
+        "sci(1, -5)",
+        "fixed(1, 99999999999)",
+        "sig(1, 0)",
+        "hex(1/2)",
+        "mixed(-1/0)",
+        "repeating(1/9999991)",
+        "group(-0.5)",
+        "cista(0)",
+        "cista(-1)",
+        "cista(1e300)",
+        "clamp(1, 3, 2)",
+        "sort()",
+        "argmax()",
+        "max(i, 1)",
+        "if(1, 2, 3)",
+        "if(true, 1)",
+        "!1",
+        "1 && true",
+        "\"a\" + 1",
+        "\"a\" < 1",
+        "true ^ 2",
+        "sort(3, 1, 2) + 1",
+        "-sort(1)",
+        "1e308 * 10",
+        "ln(10) * 1e308",
+        "sqrt(2) ^ 99999",
+        "pi ^ 99999",
+        "i ^ 99999999999",
+        "1°",
+        "°",
+        "€",
+        "1 ☃ 2",
+        "ánsa",
+    ];
+
+    /// Útržky výrazů, ze kterých se skládají náhodné vstupy
+    const FRAGMENTS: &[&str] = &[
+        "1",
+        "0",
+        "-1",
+        "2.5",
+        ".5",
+        "1e3",
+        "9223372036854775807",
+        "1/3",
+        "x",
+        "ans",
+        "pi",
+        "i",
+        "e",
+        "true",
+        "\"t\"",
+        "+",
+        "-",
+        "*",
+        "/",
+        "^",
+        "!",
+        "=",
+        "==",
+        "<",
+        ">=",
+        "&&",
+        "||",
+        "(",
+        ")",
+        "(",
+        ")",
+        ",",
+        ";",
+        "sqrt(",
+        "sin(",
+        "ln(",
+        "fact(",
+        "max(",
+        "dec(",
+        "sci(",
+        "if(",
+        "sort(",
+        "nck(",
+        "hex(",
+        "f(",
+        "g(x)=",
+        "°",
+        " ",
+        "\"",
+    ];
+
+    #[test]
+    fn malformed_inputs_do_not_panic() {
+        // Hluboce zanořené vstupy potřebují větší zásobník, testovací vlákna mají jen 2 MB
+        let worker = std::thread::Builder::new()
+            .stack_size(8 << 20)
+            .spawn(|| {
+                let mut inputs: Vec<String> = MALFORMED_CORPUS.iter().map(|s| s!(*s)).collect();
+                for depth in [100, 300] {
+                    inputs.push(format!("{}1{}", "(".repeat(depth), ")".repeat(depth)));
+                    inputs.push(format!("{}1", "-".repeat(depth)));
+                    inputs.push(format!("{}true", "!".repeat(depth)));
+                    inputs.push(vec!["2"; depth].join("^"));
+                    inputs.push(vec!["1"; depth].join("+"));
+                    inputs.push(format!("{}1{}", "sqrt(".repeat(depth), ")".repeat(depth)));
+                }
+                // Pseudonáhodné vstupy (xorshift), aby byl test opakovatelný
+                let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+                for _ in 0..3000 {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 7;
+                    seed ^= seed << 17;
+                    let len = 1 + seed % 10;
+                    let input: String = (0..len)
+                        .map(|idx| {
+                            FRAGMENTS[((seed >> (6 * idx)) % FRAGMENTS.len() as u64) as usize]
+                        })
+                        .collect();
+                    inputs.push(input);
+                }
+
+                let mut czech = CalcContext::default();
+                czech.set_locale(Locale::Czech);
+                czech.set_decimal_digits(Some(30)).unwrap();
+                let mut panics = vec![];
+                for ctx in [&mut CalcContext::default(), &mut czech] {
+                    for input in &inputs {
+                        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                            _ = Calculator::<AstStrategy>::default().evaluate_expr(input, ctx);
+                            _ = Calculator::<RecursiveScanStrategy>::default()
+                                .evaluate_expr(input, ctx);
+                        }));
+                        if result.is_err() {
+                            ctx.abort_evaluation();
+                            panics.push(input.clone());
+                        }
+                    }
+                }
+                panics
+            })
+            .unwrap();
+        let panics = worker.join().unwrap();
+        assert!(panics.is_empty(), "Panika pro vstupy: {panics:?}");
+    }
+}
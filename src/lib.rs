@@ -0,0 +1,55 @@
+//! Neucalc - kalkulátor matematických výrazů zadaných textem. Počítá přesně se zlomky
+//! a velkými celými čísly, reálná čísla používá jen tam, kde to jinak nejde.
+//!
+//! ```
+//! use neucalc::{AstStrategy, CalcContext, Calculator, Value};
+//!
+//! let mut ctx = CalcContext::default();
+//! let calc = Calculator::<AstStrategy>::default();
+//! let result = calc.evaluate_expr("x = 1/2 + 1/3", &mut ctx).unwrap();
+//! assert!(matches!(result, Value::Rational(_)));
+//! assert_eq!(calc.evaluate_expr("6*x", &mut ctx).unwrap().to_string(), "5\t(integer)");
+//! ```
+//!
+//! Vlastní funkce se přidají do registru v kontextu:
+//!
+//! ```
+//! use neucalc::{Arity, CalcContext, Calculator, FuncDescriptor, ParamType, RecursiveScanStrategy, Value};
+//!
+//! let mut ctx = CalcContext::default();
+//! let vat = FuncDescriptor::new("dph", Arity::Exact(1), |p| p[0].clone() * Value::Integer(21))
+//!     .with_params(&[ParamType::Number])
+//!     .with_help("DPH 21 % (v setinách)");
+//! ctx.register_func(vat).unwrap();
+//! let calc = Calculator::<RecursiveScanStrategy>::default();
+//! assert!(calc.evaluate_expr("dph(100)/100", &mut ctx).is_ok());
+//! ```
+#![allow(clippy::needless_return)]
+
+mod base;
+mod calc_base;
+mod calc_strategies;
+mod calculator;
+
+pub use base::{CalcError, ErrorKind, Expected, Language, Span};
+pub use calc_base::complex::Complex;
+pub use calc_base::context::{CalcContext, UserFunc, ANS, MAX_CALL_DEPTH, MAX_NESTING_DEPTH};
+pub use calc_base::decimal::Decimal;
+pub use calc_base::expr::Expr;
+pub use calc_base::func_call::FuncCall;
+pub use calc_base::func_registry::{
+    Arity, FuncDescriptor, FuncImpl, FunctionRegistry, ParamType, SettingsFuncImpl,
+};
+pub use calc_base::locale::Locale;
+pub use calc_base::number_format::NumberFormat;
+pub use calc_base::rational::{DecimalExpansion, Rational};
+pub use calc_base::settings::CalcSettings;
+pub use calc_base::surd::Surd;
+pub use calc_base::trig_funcs::AngleUnit;
+pub use calc_base::value::Value;
+pub use calc_base::value_cmp::{CmpOper, REAL_TOLERANCE};
+pub use calc_base::value_logic::LogicOper;
+pub use calc_strategies::ast_strategy::AstStrategy;
+pub use calc_strategies::recursive_scan_strategy::RecursiveScanStrategy;
+pub use calc_strategies::ICalculatorStrategy;
+pub use calculator::Calculator;
@@ -1,67 +1,335 @@
-use crate::calc_base::value::Value;
-use crate::calc_strategies::recursive_scan_strategy::RecursiveScanStrategy;
-use crate::calculator::Calculator;
+#![allow(clippy::needless_return)]
+
+use neucalc::{
+    AngleUnit, CalcContext, CalcError, Calculator, Decimal, ErrorKind, Language, Locale,
+    NumberFormat, Value,
+};
 use std::io;
-use std::panic::catch_unwind;
 
-mod base;
-mod calc_base;
-mod calc_strategies;
-mod calculator;
+/// Flushne výstupní konzoli, čímž se vytiskne vše, co je v bufferu.
+macro_rules! flush_stdout {
+    () => {
+        _ = std::io::Write::flush(&mut std::io::stdout());
+    };
+}
+
+/// Simple print with one or multiple parameters. Flushes IO automatically
+macro_rules! sprint {
+    ($expr:tt) => {
+       print!("{}", $expr);
+       flush_stdout!();
+    };
+    ($($arg:tt)*) => {
+        print!($($arg)*);
+        flush_stdout!();
+    };
+}
+
+/// Simple println with one parameter
+macro_rules! sprintln {
+    ($expr:tt) => {
+        println!("{}", $expr)
+    };
+    ($expr:expr) => {
+        println!("{}", $expr)
+    };
+}
+
+/// Vybere text konzole podle jazyka. Chybová hlášení překládá katalog v knihovně
+/// (CalcError::localized), tady jsou jen texty samotné konzole.
+macro_rules! tr {
+    ($lang:expr, $cs:literal, $en:literal) => {
+        match $lang {
+            Language::Czech => format!($cs),
+            Language::English => format!($en),
+        }
+    };
+}
+
+/// Nejvíce číslic za desetinnou tečkou, se kterými se vypíše přesný desetinný rozvoj
+/// zlomku. Delší rozvoj se vypíše zaokrouhlený na tolik platných číslic.
+const EXPANSION_DIGITS: u32 = 60;
+
+/// Velikost zásobníku vlákna, ve kterém konzole počítá. Výchozí zásobník hlavního vlákna
+/// (obvykle 8 MB) by na výraz zanořený až na MAX_NESTING_DEPTH v ladicím sestavení nestačil.
+const EVAL_STACK_SIZE: usize = 64 << 20;
 
 fn main() {
-    print_header();
-    let calculator = Calculator::<RecursiveScanStrategy>::default();
+    let console = std::thread::Builder::new()
+        .stack_size(EVAL_STACK_SIZE)
+        .spawn(run_console)
+        .expect("Nepodařilo se spustit vlákno konzole");
+    _ = console.join();
+}
+
+fn run_console() {
+    let mut lang = language_from_args()
+        .or_else(Language::from_env)
+        .unwrap_or_default();
+    print_header(lang);
+    // Proměnné a výsledek posledního výpočtu (ans) přetrvávají mezi řádky
+    let mut context = CalcContext::default();
     let stdin = io::stdin();
 
     loop {
-        let operation_result = catch_unwind(|| {
-            let mut input = String::default();
-            sprint!(">> ");
+        let mut input = String::default();
+        sprint!(">> ");
 
-            if let Err(e) = stdin.read_line(&mut input) {
-                println!("Chyba při čtení vstupu z konzole: {:?}", e);
-                return false;
-            }
-            remove_comment_trim(&mut input);
-
-            if input.trim().is_empty() {
-                return false;
-            } else {
-                let calc_result = calculator.evaluate_expr(&input);
-                match calc_result {
-                    Ok(result) => {
-                        // Zlomek se pro přehlednost vypíše i jako zlomek, i jako reálné číslo
-                        if let Value::Rational(ratio) = result.clone() {
-                            sprintln!(result);
-                            if let Some(real) = ratio.to_real() {
-                                let as_real = Value::Real(real);
-                                sprintln!(as_real);
-                            }
-                        } else {
-                            sprintln!(result);
+        if let Err(e) = stdin.read_line(&mut input) {
+            let message = tr!(
+                lang,
+                "Chyba při čtení vstupu z konzole: {e:?}",
+                "Error reading console input: {e:?}"
+            );
+            println!("{message}");
+            return;
+        }
+        remove_comment_trim(&mut input);
+
+        if input.trim().is_empty() {
+            return; // Konec z důvodu prázdného vstupu
+        } else if let Some(command) = input.trim().strip_prefix(':') {
+            run_command(command, &mut context, &mut lang);
+        } else {
+            let calculator: Calculator = Calculator::default();
+            let calc_result = calculator.evaluate_expr(&input, &mut context);
+            match calc_result {
+                // Definice funkce nemá žádnou hodnotu, nic se nevypíše
+                Ok(Value::Nothing) => {}
+                Ok(result) => {
+                    let settings = context.settings();
+                    sprintln!(result.format_with(settings));
+                    // Ve výchozím formátu se zlomek pro přehlednost vypíše i jako desetinné
+                    // číslo: přesně s periodou (0.1(6)), a pokud je rozvoj příliš dlouhý,
+                    // zaokrouhleně. Funguje i pro zlomky, které se nevejdou do f64.
+                    if let Value::Rational(ratio) = &result {
+                        if settings.number_format == NumberFormat::Default {
+                            let expansion = ratio.decimal_expansion(EXPANSION_DIGITS as usize);
+                            let line = if expansion.truncated {
+                                let rounded = Decimal::from_rational(ratio, EXPANSION_DIGITS);
+                                format!("≈ {rounded}")
+                            } else {
+                                format!("= {expansion}")
+                            };
+                            sprintln!(settings.locale.localize(&line));
                         }
                     }
-                    Err(err) => println!("Chyba: {}", err),
                 }
+                Err(err) => print_error(&input, &err, lang),
             }
-            println!();
-            return true;
-        });
-
-        match operation_result {
-            Ok(val) => {
-                if val == false {
-                    return; // Konec z důvodu prázdného vstupu
-                }
+        }
+        println!();
+    }
+}
+
+/// Příkazy konzole začínají dvojtečkou, aby se nepletly s výrazy:
+///     :help        vypíše seznam vestavěných funkcí
+///     :help sqrt   vypíše nápovědu k jedné funkci
+///     :angle       vypíše nastavenou jednotku úhlu
+///     :angle deg   nastaví jednotku úhlu (rad, deg, grad)
+///     :digits      vypíše nastavení režimu desetinných čísel
+///     :digits 50   zapne režim desetinných čísel s 50 platnými číslicemi
+///     :digits off  vypne režim desetinných čísel
+///     :format      vypíše nastavený formát čísel
+///     :format hex  nastaví formát čísel (viz NumberFormat), např. :format fixed 2
+///     :group on    zapne oddělování tisíců (off vypne)
+///     :locale cs   desetinná čárka a parametry oddělené středníkem (intl vrátí tečku a čárku)
+///     :lang en     jazyk hlášení (cs, en)
+fn run_command(command: &str, context: &mut CalcContext, lang: &mut Language) {
+    let mut words = command.split_whitespace();
+    match (words.next(), words.next()) {
+        (Some("help"), None) => {
+            for func in context.registry().funcs() {
+                println!("{:<24} {}", func.signature(), func.help());
+            }
+        }
+        (Some("help"), Some(name)) => match context.registry().get(name) {
+            Some(func) => println!("{}\n    {}", func.signature(), func.help()),
+            None => println!(
+                "{}",
+                tr!(
+                    *lang,
+                    "Funkce '{name}' není vestavěná",
+                    "Function '{name}' is not built in"
+                )
+            ),
+        },
+        (Some("angle"), None) => {
+            let unit = angle_unit_name(context.settings().angle_unit, *lang);
+            println!(
+                "{}",
+                tr!(*lang, "Jednotka úhlu: {unit}", "Angle unit: {unit}")
+            );
+        }
+        (Some("angle"), Some(name)) => match AngleUnit::from_name(name) {
+            Some(unit) => {
+                context.set_angle_unit(unit);
+                run_command("angle", context, lang);
+            }
+            None => println!(
+                "{}",
+                tr!(
+                    *lang,
+                    "Neznámá jednotka úhlu '{name}'. Dostupné jednotky: rad, deg, grad",
+                    "Unknown angle unit '{name}'. Available units: rad, deg, grad"
+                )
+            ),
+        },
+        (Some("digits"), None) => match context.settings().decimal_digits {
+            Some(digits) => println!(
+                "{}",
+                tr!(
+                    *lang,
+                    "Desetinná čísla: {digits} platných číslic",
+                    "Decimal numbers: {digits} significant digits"
+                )
+            ),
+            None => println!(
+                "{}",
+                tr!(*lang, "Desetinná čísla: vypnuto", "Decimal numbers: off")
+            ),
+        },
+        (Some("digits"), Some(value)) => {
+            let digits = match value {
+                "off" => Ok(None),
+                _ => value.parse().map(Some).map_err(|_| {
+                    CalcError::ParseErr(ErrorKind::NotDigitCount {
+                        text: value.to_string(),
+                    })
+                }),
+            };
+            match digits.and_then(|digits| context.set_decimal_digits(digits)) {
+                Ok(()) => run_command("digits", context, lang),
+                Err(err) => print_command_error(&err, *lang),
             }
-            Err(err) => {
-                println!("V programu došlo k chybě: {:?}", err);
-                println!("Stiskněte enter pro ukončení");
-                _ = stdin.read_line(&mut String::new());
-                return; // Konec z důvodu závažné chyby v programu
+        }
+        (Some("format"), None) => {
+            let format = context.settings().number_format;
+            println!(
+                "{}",
+                tr!(*lang, "Formát čísel: {format}", "Number format: {format}")
+            );
+        }
+        (Some("format"), Some(_)) => {
+            let format = command.trim_start()["format".len()..].parse();
+            match format.and_then(|format| context.set_number_format(format)) {
+                Ok(()) => run_command("format", context, lang),
+                Err(err) => print_command_error(&err, *lang),
             }
         }
+        (Some("group"), None) => match context.settings().digit_grouping {
+            true => println!(
+                "{}",
+                tr!(*lang, "Oddělování tisíců: zapnuto", "Digit grouping: on")
+            ),
+            false => println!(
+                "{}",
+                tr!(*lang, "Oddělování tisíců: vypnuto", "Digit grouping: off")
+            ),
+        },
+        (Some("group"), Some(value @ ("on" | "off"))) => {
+            context.set_digit_grouping(value == "on");
+            run_command("group", context, lang);
+        }
+        (Some("locale"), None) => {
+            let locale = locale_name(context.settings().locale, *lang);
+            println!(
+                "{}",
+                tr!(*lang, "Zápis čísel: {locale}", "Number notation: {locale}")
+            );
+        }
+        (Some("locale"), Some(name)) => match Locale::from_name(name) {
+            Some(locale) => {
+                context.set_locale(locale);
+                run_command("locale", context, lang);
+            }
+            None => println!(
+                "{}",
+                tr!(
+                    *lang,
+                    "Neznámý zápis čísel '{name}'. Dostupné zápisy: intl, cs",
+                    "Unknown number notation '{name}'. Available notations: intl, cs"
+                )
+            ),
+        },
+        (Some("lang"), None) => println!("{}", tr!(*lang, "Jazyk: {lang}", "Language: {lang}")),
+        (Some("lang"), Some(name)) => match Language::from_name(name) {
+            Some(new_lang) => {
+                *lang = new_lang;
+                run_command("lang", context, lang);
+            }
+            None => println!(
+                "{}",
+                tr!(
+                    *lang,
+                    "Neznámý jazyk '{name}'. Dostupné jazyky: cs, en",
+                    "Unknown language '{name}'. Available languages: cs, en"
+                )
+            ),
+        },
+        _ => println!(
+            "{}",
+            tr!(
+                *lang,
+                "Neznámý příkaz ':{command}'. Dostupné příkazy: :help, :help <funkce>, \
+                :angle <jednotka>, :digits <číslice|off>, :format <formát>, :group <on|off>, \
+                :locale <intl|cs>, :lang <cs|en>",
+                "Unknown command ':{command}'. Available commands: :help, :help <function>, \
+                :angle <unit>, :digits <digits|off>, :format <format>, :group <on|off>, \
+                :locale <intl|cs>, :lang <cs|en>"
+            )
+        ),
+    }
+}
+
+/// Jazyk zadaný přepínačem --lang en (nebo --lang=en)
+fn language_from_args() -> Option<Language> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .enumerate()
+        .find_map(|(idx, arg)| match arg.strip_prefix("--lang") {
+            Some("") => args.get(idx + 1).and_then(|name| Language::from_name(name)),
+            Some(rest) => rest.strip_prefix('=').and_then(Language::from_name),
+            None => None,
+        })
+}
+
+fn angle_unit_name(unit: AngleUnit, lang: Language) -> String {
+    match (lang, unit) {
+        (Language::Czech, unit) => unit.to_string(),
+        (Language::English, AngleUnit::Rad) => String::from("radians"),
+        (Language::English, AngleUnit::Deg) => String::from("degrees"),
+        (Language::English, AngleUnit::Grad) => String::from("gradians"),
+        (Language::English, AngleUnit::PiFraction) => String::from("multiples of π"),
+    }
+}
+
+fn locale_name(locale: Locale, lang: Language) -> String {
+    match (lang, locale) {
+        (Language::Czech, locale) => locale.to_string(),
+        (Language::English, Locale::International) => {
+            String::from("international (1.5, parameters separated by commas)")
+        }
+        (Language::English, Locale::Czech) => {
+            String::from("Czech (1,5, parameters separated by semicolons)")
+        }
+    }
+}
+
+fn print_command_error(err: &CalcError, lang: Language) {
+    let message = err.localized(lang);
+    println!("{}", tr!(lang, "Chyba: {message}", "Error: {message}"));
+}
+
+/// Vypíše chybu. Pokud chyba nese pozici ve výrazu, zopakuje se výraz a místo chyby
+/// se podtrhne, např.:
+///     1 + 2/0
+///          ^
+fn print_error(input: &str, err: &CalcError, lang: Language) {
+    print_command_error(err, lang);
+    if let Some(underline) = err.span().and_then(|span| span.underline(input)) {
+        println!("    {}", input.trim_end());
+        println!("    {underline}");
     }
 }
 
@@ -72,10 +340,12 @@ fn remove_comment_trim(input: &mut String) {
 }
 
 /// Vytiskne logo programu Neucalc a přidá autorský podpis a číslo verze.
-fn print_header() {
-    let app_info = format!(
-        "Neucalc - verze {}, Martin Sebera 2025",
-        env!("CARGO_PKG_VERSION")
+fn print_header(lang: Language) {
+    let version = env!("CARGO_PKG_VERSION");
+    let app_info = tr!(
+        lang,
+        "Neucalc - verze {version}, Martin Sebera 2025",
+        "Neucalc - version {version}, Martin Sebera 2025"
     );
     sprintln!(app_info);
     sprintln!("━".repeat(app_info.chars().count())); // Posledni radek bude podtrzeny